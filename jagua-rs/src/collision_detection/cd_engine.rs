@@ -1,18 +1,27 @@
-use indexmap::IndexSet;
-use tribool::Tribool;
+use alloc::{vec, vec::Vec};
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use itertools::Itertools;
 
 use crate::collision_detection::hazard::Hazard;
 use crate::collision_detection::hazard::HazardEntity;
 use crate::collision_detection::hpg::grid::Grid;
 use crate::collision_detection::hpg::hazard_proximity_grid::{DirtyState, HazardProximityGrid};
 use crate::collision_detection::hpg::hpg_cell::HPGCell;
+use crate::collision_detection::quadtree::qt_dump::QuadtreeDump;
+use crate::collision_detection::quadtree::qt_hazard::{QTHazPresence, QTHazPresenceHistogram};
 use crate::collision_detection::quadtree::qt_node::QTNode;
 use crate::collision_detection::quadtree::qt_traits::QTQueryable;
 use crate::fsize;
 use crate::geometry::fail_fast::sp_surrogate::SPSurrogate;
-use crate::geometry::geo_enums::{GeoPosition, GeoRelation};
-use crate::geometry::geo_traits::{CollidesWith, Shape, Transformable, TransformableFrom};
+use crate::geometry::geo_enums::{GeoPosition, GeoRelation, Tribool};
+use crate::geometry::geo_traits::{
+    CollidesWith, DistanceFrom, Shape, Transformable, TransformableFrom,
+};
 use crate::geometry::primitives::aa_rectangle::AARectangle;
+use crate::geometry::primitives::capsule::Capsule;
 use crate::geometry::primitives::circle::Circle;
 use crate::geometry::primitives::edge::Edge;
 use crate::geometry::primitives::point::Point;
@@ -20,6 +29,10 @@ use crate::geometry::primitives::simple_polygon::SimplePolygon;
 use crate::geometry::transformation::Transformation;
 use crate::util::assertions;
 use crate::util::config::CDEConfig;
+use crate::util::fnv::FnvIndexSet;
+
+/// Tolerance of [`CDEConfig::allow_touching`], as a fraction of the diameter of the engine's bounding box
+const TOUCH_TOLERANCE: fsize = 1e-6;
 
 /// The Collision Detection Engine (CDE).
 /// The CDE can resolve a range of collision queries
@@ -29,10 +42,19 @@ pub struct CDEngine {
     quadtree: QTNode,
     static_hazards: Vec<Hazard>,
     dynamic_hazards: Vec<Hazard>,
+    soft_hazards: Vec<Hazard>,
     haz_prox_grid: Option<HazardProximityGrid>,
     config: CDEConfig,
     bbox: AARectangle,
     uncommitted_deregisters: Vec<Hazard>,
+    journal: Option<Vec<JournalEntry>>,
+}
+
+/// Operation on the dynamic hazards of the [CDEngine], recorded in its journal
+#[derive(Clone, Debug)]
+enum JournalEntry {
+    Registered(HazardEntity),
+    Deregistered(Hazard),
 }
 
 /// Snapshot of the state of [CDEngine] at a given time.
@@ -45,6 +67,10 @@ pub struct CDESnapshot {
 
 impl CDEngine {
     pub fn new(bbox: AARectangle, static_hazards: Vec<Hazard>, config: CDEConfig) -> CDEngine {
+        //soft hazards never cause collisions and are kept out of the quadtree and hazard proximity grid
+        let (soft_hazards, static_hazards): (Vec<Hazard>, Vec<Hazard>) =
+            static_hazards.into_iter().partition(|h| h.entity.is_soft());
+
         let haz_prox_grid = match config.hpg_n_cells {
             0 => None,
             hpg_n_cells => Some(HazardProximityGrid::new(
@@ -54,7 +80,11 @@ impl CDEngine {
             )),
         };
 
-        let mut qt_root = QTNode::new(config.quadtree_depth, bbox.clone());
+        let mut qt_root = QTNode::new(
+            config.quadtree_depth,
+            bbox.clone(),
+            config.quadtree_min_hazards_to_split,
+        );
 
         for haz in static_hazards.iter() {
             qt_root.register_hazard(haz.into());
@@ -64,11 +94,46 @@ impl CDEngine {
             quadtree: qt_root,
             static_hazards,
             dynamic_hazards: vec![],
+            soft_hazards,
             haz_prox_grid,
             config,
             bbox,
             uncommitted_deregisters: vec![],
+            journal: None,
+        }
+    }
+
+    /// Starts recording all registrations and deregistrations of hazards, so they can be undone with [`Self::rollback`].
+    /// Any previously recorded operations are discarded.
+    pub fn start_journal(&mut self) {
+        self.journal = Some(vec![]);
+    }
+
+    /// Stops recording operations and discards the journal.
+    pub fn stop_journal(&mut self) {
+        self.journal = None;
+    }
+
+    /// Number of operations in the journal which can be rolled back.
+    pub fn journal_len(&self) -> usize {
+        self.journal.as_ref().map_or(0, |j| j.len())
+    }
+
+    /// Undoes the last `n` registrations and deregistrations in the journal, most recent first.
+    /// Much cheaper than restoring a snapshot when only a few operations have to be undone.
+    pub fn rollback(&mut self, n: usize) {
+        let mut journal = self.journal.take().expect("journal not started");
+        assert!(
+            n <= journal.len(),
+            "cannot roll back more operations than recorded"
+        );
+        for entry in journal.drain(journal.len() - n..).rev() {
+            match entry {
+                JournalEntry::Registered(entity) => self.deregister_hazard(entity, true),
+                JournalEntry::Deregistered(hazard) => self.register_hazard(hazard),
+            }
         }
+        self.journal = Some(journal);
     }
 
     /// Registers a new hazard in the CDE.
@@ -99,6 +164,9 @@ impl CDEngine {
         if let Some(hpg) = self.haz_prox_grid.as_mut() {
             hpg.register_hazard(&hazard)
         }
+        if let Some(journal) = self.journal.as_mut() {
+            journal.push(JournalEntry::Registered(hazard.entity));
+        }
         self.dynamic_hazards.push(hazard);
 
         debug_assert!(assertions::qt_contains_no_dangling_hazards(self));
@@ -122,6 +190,9 @@ impl CDEngine {
             .expect("Hazard not found");
 
         let hazard = self.dynamic_hazards.swap_remove(haz_index);
+        if let Some(journal) = self.journal.as_mut() {
+            journal.push(JournalEntry::Deregistered(hazard.clone()));
+        }
 
         match commit_instant {
             true => self.quadtree.deregister_hazard(hazard_entity),
@@ -136,6 +207,15 @@ impl CDEngine {
         debug_assert!(assertions::qt_contains_no_dangling_hazards(self));
     }
 
+    /// Replaces a registered hazard by another one, for instance when a placed item is moved.
+    /// <br>
+    /// Deregisters `old_entity` with `commit_instant = true`, then registers `new_hazard`.
+    /// Only the quadtree nodes overlapped by the old and the new shape are touched, the rest of the engine is left as is.
+    pub fn update_hazard(&mut self, old_entity: HazardEntity, new_hazard: Hazard) {
+        self.deregister_hazard(old_entity, true);
+        self.register_hazard(new_hazard);
+    }
+
     pub fn create_snapshot(&mut self) -> CDESnapshot {
         self.commit_deregisters();
         assert!(self
@@ -149,13 +229,17 @@ impl CDEngine {
     }
 
     /// Restores the CDE to a previous state, as described by the snapshot.
+    /// The journal, if started, is cleared.
     pub fn restore(&mut self, snapshot: &CDESnapshot) {
+        if let Some(journal) = self.journal.as_mut() {
+            journal.clear();
+        }
         //Quadtree
         let mut hazards_to_remove = self
             .dynamic_hazards
             .iter()
             .map(|h| h.entity)
-            .collect::<IndexSet<HazardEntity>>();
+            .collect::<FnvIndexSet<HazardEntity>>();
         debug_assert!(hazards_to_remove.len() == self.dynamic_hazards.len());
         let mut hazards_to_add = vec![];
 
@@ -229,6 +313,21 @@ impl CDEngine {
         &self.bbox
     }
 
+    /// Counts how the active hazards are present across all nodes of the quadtree.
+    /// Partially present hazards are the expensive ones to resolve during collision detection.
+    pub fn hazard_presence_histogram(&self) -> QTHazPresenceHistogram {
+        let mut histogram = QTHazPresenceHistogram::default();
+        self.quadtree.add_to_presence_histogram(&mut histogram);
+        histogram
+    }
+
+    /// Exports the boundaries of all nodes of the quadtree, together with the number of hazards present in each
+    pub fn export_quadtree(&self) -> QuadtreeDump {
+        let mut nodes = vec![];
+        self.quadtree.dump(&mut nodes);
+        QuadtreeDump { nodes }
+    }
+
     pub fn smallest_qt_node_dimension(&self) -> fsize {
         let bbox = &self.quadtree.bbox;
         let level = self.quadtree.level;
@@ -271,6 +370,11 @@ impl CDEngine {
         &self.static_hazards
     }
 
+    /// Returns all soft hazards in the CDE, these are not considered during collision detection.
+    pub fn soft_hazards(&self) -> &Vec<Hazard> {
+        &self.soft_hazards
+    }
+
     /// Returns all hazards in the CDE, both static and dynamic.
     pub fn all_hazards(&self) -> impl Iterator<Item = &Hazard> {
         self.static_hazards
@@ -278,6 +382,88 @@ impl CDEngine {
             .chain(self.dynamic_hazards.iter())
     }
 
+    /// Returns the distance between a shape and the closest soft hazard, zero if they overlap.
+    /// Returns `None` if there are no soft hazards.
+    pub fn soft_hazard_proximity(&self, shape: &SimplePolygon) -> Option<fsize> {
+        self.soft_hazards
+            .iter()
+            .map(|haz| {
+                let haz_shape = haz.shape.as_ref();
+                let overlapping = shape.points.iter().any(|p| haz_shape.collides_with(p))
+                    || haz_shape.points.iter().any(|p| shape.collides_with(p))
+                    || shape
+                        .edge_iter()
+                        .any(|e| haz_shape.edge_iter().any(|he| e.collides_with(&he)));
+                match overlapping {
+                    true => 0.0,
+                    false => {
+                        let shape_to_haz = shape
+                            .points
+                            .iter()
+                            .map(|p| haz_shape.distance_from_border(p).1);
+                        let haz_to_shape = haz_shape
+                            .points
+                            .iter()
+                            .map(|p| shape.distance_from_border(p).1);
+                        shape_to_haz
+                            .chain(haz_to_shape)
+                            .fold(fsize::MAX, fsize::min)
+                    }
+                }
+            })
+            .reduce(fsize::min)
+    }
+
+    /// Returns the distance between the shape, with the transformation applied, and the closest placed item, zero if they overlap.
+    /// Returns `None` if no items are placed, the hazards of the container are not considered.
+    /// Items are visited in order of their bounding box distance, which bounds the result from below.
+    /// The fail-fast poles bound it from above, only items whose bounding box lies closer than the current best are compared exactly.
+    pub fn nearest_hazard_distance(
+        &self,
+        transform: &Transformation,
+        shape: &SimplePolygon,
+    ) -> Option<fsize> {
+        let hazards = self
+            .dynamic_hazards
+            .iter()
+            .filter(|h| h.active)
+            .collect_vec();
+        if hazards.is_empty() {
+            return None;
+        }
+        let shape = shape.transform_clone(transform);
+        let shape_bbox = shape.bbox();
+
+        //the distance between the bounding boxes bounds the distance to a hazard from below
+        let candidates = hazards
+            .iter()
+            .map(|h| (bbox_distance(&shape_bbox, &h.shape.bbox()), h))
+            .sorted_by(|(d1, _), (d2, _)| d1.total_cmp(d2))
+            .collect_vec();
+
+        //broad-phase: the closest pair of poles bounds the distance from above, only for hazards that can still be nearer
+        let mut nearest = fsize::MAX;
+        for (lower_bound, haz) in candidates.iter() {
+            if *lower_bound >= nearest {
+                break;
+            }
+            nearest = ff_poles(&shape)
+                .iter()
+                .cartesian_product(ff_poles(&haz.shape))
+                .map(|(p1, p2)| p1.distance(p2))
+                .fold(nearest, fsize::min);
+        }
+
+        //exact distances, as long as the lower bound can still improve the current best
+        for (lower_bound, haz) in candidates.iter() {
+            if *lower_bound >= nearest {
+                break;
+            }
+            nearest = fsize::min(nearest, shape.separation_distance(&haz.shape));
+        }
+        Some(nearest)
+    }
+
     ///Checks whether a reference simple polygon, with a transformation applies, collides with any of the hazards.
     ///The check is first done on the surrogate, then with the actual shape.
     ///A buffer shape is used as a temporary storage for the transformed shape.
@@ -304,7 +490,41 @@ impl CDEngine {
         }
     }
 
-    ///Checks whether a simple polygon collides with any of the (relevant) hazards
+    /// Checks a batch of reference shapes, each with a transformation to apply, for collisions with the (relevant) hazards.
+    /// Equivalent to calling [`CDEngine::surrogate_or_poly_collides`] for every query, the results are in the same order as `queries`.
+    /// The queries are evaluated in Z-order of their position, so consecutive queries visit the same nodes of the quadtree,
+    /// and a single buffer shape is used for all queries of the same reference shape.
+    pub fn collides_batch(
+        &self,
+        queries: &[(Transformation, &SimplePolygon)],
+        irrelevant_hazards: &[HazardEntity],
+    ) -> Vec<bool> {
+        let order = (0..queries.len()).sorted_by_cached_key(|&i| {
+            let (transform, shape) = &queries[i];
+            z_order(&self.bbox, &shape.poi.center.transform_clone(transform))
+        });
+
+        let mut results = vec![false; queries.len()];
+        let mut buffers: Vec<(&SimplePolygon, SimplePolygon)> = vec![];
+        for i in order {
+            let (transform, shape) = &queries[i];
+            let buffer_idx = match buffers.iter().position(|(r, _)| core::ptr::eq(*r, *shape)) {
+                Some(idx) => idx,
+                None => {
+                    buffers.push((shape, (*shape).clone()));
+                    buffers.len() - 1
+                }
+            };
+            let buffer = &mut buffers[buffer_idx].1;
+            results[i] =
+                self.surrogate_or_poly_collides(shape, transform, buffer, irrelevant_hazards);
+        }
+        results
+    }
+
+    ///Checks whether a simple polygon collides with any of the (relevant) hazards.
+    ///Shapes closer to a hazard than the [`collision_margin`](CDEConfig::collision_margin) of the engine are considered colliding as well,
+    ///or closer than the [`container_margin`](CDEConfig::container_margin) for the hazards of the container.
     /// # Arguments
     /// * `shape` - The shape (already transformed) to be checked for collisions
     /// * `irrelevant_hazards` - entities to be ignored during the check
@@ -313,16 +533,95 @@ impl CDEngine {
         shape: &SimplePolygon,
         irrelevant_hazards: &[HazardEntity],
     ) -> bool {
-        match self.bbox.relation_to(&shape.bbox()) {
+        //shapes touching the bounds of the engine are still inside when touching is allowed
+        let bbox = match self.touch_epsilon() {
+            None => self.bbox.clone(),
+            Some(eps) => self.bbox.inflate(eps),
+        };
+        match bbox.relation_to(&shape.bbox()) {
             //Not fully inside bbox => definite collision
             GeoRelation::Disjoint | GeoRelation::Enclosed | GeoRelation::Intersecting => true,
             GeoRelation::Surrounding => {
+                //broad-phase: the bin exterior and holes can be ignored if the shape cannot reach them
+                let with_static;
+                let irrelevant_hazards = match self.out_of_reach_of_static_hazards(&shape.bbox()) {
+                    false => irrelevant_hazards,
+                    true => {
+                        with_static = irrelevant_hazards
+                            .iter()
+                            .copied()
+                            .chain(self.container_hazards())
+                            .collect_vec();
+                        &with_static
+                    }
+                };
                 self.poly_collides_by_edge_intersection(shape, irrelevant_hazards)
                     || self.poly_collides_by_containment(shape, irrelevant_hazards)
             }
         }
     }
 
+    /// Checks whether the shape, with the transformation applied, lies entirely inside the container: within the bin exterior and outside of all holes.
+    /// Unlike [`CDEngine::poly_collides`], no margins apply and shapes touching the boundary of the container are contained,
+    /// as long as no part of them pokes out. Points within a tolerance of 1e-6 times the size of the engine from the boundary are considered to lie on it.
+    /// Every vertex is tested, as well as every part of an edge between two points where it meets the boundary.
+    pub fn is_fully_contained(&self, transform: &Transformation, shape: &SimplePolygon) -> bool {
+        let shape = shape.transform_clone(transform);
+        let sq_eps = (self.bbox.diameter() * TOUCH_TOLERANCE).powi(2);
+        let boundaries = self
+            .static_hazards
+            .iter()
+            .filter(|h| {
+                matches!(
+                    h.entity,
+                    HazardEntity::BinExterior | HazardEntity::BinHole { .. }
+                )
+            })
+            .collect_vec();
+
+        let on_boundary = |p: &Point| {
+            boundaries
+                .iter()
+                .any(|h| h.shape.edge_iter().any(|e| e.sq_distance(p) <= sq_eps))
+        };
+        let inside = |p: &Point| {
+            on_boundary(p)
+                || boundaries.iter().all(|h| match h.entity {
+                    HazardEntity::BinExterior => h.shape.collides_with(p),
+                    _ => !h.shape.collides_with(p),
+                })
+        };
+
+        shape.points.iter().all(inside)
+            && shape.edge_iter().all(|edge| {
+                let (Point(x, y), Point(x_end, y_end)) = (edge.start, edge.end);
+                let (dx, dy) = (x_end - x, y_end - y);
+                let param =
+                    |Point(px, py): Point| ((px - x) * dx + (py - y) * dy) / (dx * dx + dy * dy);
+                //the edge can only leave the container where it meets its boundary: at an intersection or a boundary vertex on the edge
+                let intersections = boundaries
+                    .iter()
+                    .flat_map(|h| h.shape.edge_iter())
+                    .filter_map(|b_edge| edge.collides_at(&b_edge));
+                let vertices_on_edge = boundaries
+                    .iter()
+                    .flat_map(|h| h.shape.points.iter())
+                    .filter(|p| edge.sq_distance(*p) <= sq_eps)
+                    .copied();
+                let params = [0.0, 1.0]
+                    .into_iter()
+                    .chain(intersections.chain(vertices_on_edge).map(param))
+                    .map(|t| t.clamp(0.0, 1.0))
+                    .sorted_by(fsize::total_cmp)
+                    .dedup()
+                    .collect_vec();
+                params.windows(2).all(|w| {
+                    let t = (w[0] + w[1]) / 2.0;
+                    inside(&Point(x + t * dx, y + t * dy))
+                })
+            })
+    }
+
     /// Checks whether a surrogate collides with any of the (relevant) hazards.
     /// # Arguments
     /// * `base_surrogate` - The (untransformed) surrogate to be checked for collisions
@@ -334,8 +633,10 @@ impl CDEngine {
         transform: &Transformation,
         irrelevant_hazards: &[HazardEntity],
     ) -> bool {
+        let radius_offset = self.pole_radius_offset();
         for pole in base_surrogate.ff_poles() {
-            let t_pole = pole.transform_clone(transform);
+            let mut t_pole = pole.transform_clone(transform);
+            t_pole.radius = (t_pole.radius + radius_offset).max(0.0);
             if self
                 .quadtree
                 .collides(&t_pole, irrelevant_hazards)
@@ -345,12 +646,8 @@ impl CDEngine {
             }
         }
         for pier in base_surrogate.ff_piers() {
-            let t_pier = pier.transform_clone(transform);
-            if self
-                .quadtree
-                .collides(&t_pier, irrelevant_hazards)
-                .is_some()
-            {
+            let t_pier = self.query_pier(pier.transform_clone(transform));
+            if self.edge_collides(t_pier, irrelevant_hazards) {
                 return true;
             }
         }
@@ -399,9 +696,91 @@ impl CDEngine {
         shape: &SimplePolygon,
         irrelevant_hazards: &[HazardEntity],
     ) -> bool {
-        shape
-            .edge_iter()
-            .any(|e| self.quadtree.collides(&e, irrelevant_hazards).is_some())
+        self.query_edges(shape)
+            .any(|e| self.edge_collides(e, irrelevant_hazards))
+    }
+
+    /// Whether an edge collides with any of the (relevant) hazards, or lies within the margin of one
+    fn edge_collides(&self, edge: Edge, irrelevant_hazards: &[HazardEntity]) -> bool {
+        let (min_margin, max_margin) = self.config.margin_range();
+        let collides = match min_margin {
+            0.0 => self.quadtree.collides(&edge, irrelevant_hazards),
+            margin => self
+                .quadtree
+                .collides(&Capsule::new(edge.clone(), margin), irrelevant_hazards),
+        }
+        .is_some();
+        match collides || min_margin == max_margin {
+            true => collides,
+            false => {
+                //only the hazards subject to the larger margin can still be within reach
+                let mut detected = irrelevant_hazards.to_vec();
+                let n_irrelevant = detected.len();
+                self.quadtree
+                    .collect_collisions(&Capsule::new(edge, max_margin), &mut detected);
+                detected[n_irrelevant..]
+                    .iter()
+                    .any(|h| self.config.margin_for(h) == max_margin)
+            }
+        }
+    }
+
+    /// Collects the hazards the edge collides with, or lies within the margin of
+    fn collect_edge_collisions(&self, edge: Edge, detected: &mut Vec<HazardEntity>) {
+        let (min_margin, max_margin) = self.config.margin_range();
+        match min_margin {
+            0.0 => self.quadtree.collect_collisions(&edge, detected),
+            margin => self
+                .quadtree
+                .collect_collisions(&Capsule::new(edge.clone(), margin), detected),
+        }
+        if min_margin != max_margin {
+            //hazards subject to the smaller margin found within the larger one are out of reach
+            let n_detected = detected.len();
+            self.quadtree
+                .collect_collisions(&Capsule::new(edge, max_margin), detected);
+            let newly_detected = detected.split_off(n_detected);
+            detected.extend(
+                newly_detected
+                    .into_iter()
+                    .filter(|h| self.config.margin_for(h) == max_margin),
+            );
+        }
+    }
+
+    /// Poles of surrogates are enlarged by the smallest margin, or slightly shrunk to allow them to touch hazards
+    fn pole_radius_offset(&self) -> fsize {
+        match self.touch_epsilon() {
+            Some(epsilon) => -epsilon,
+            None => self.config.margin_range().0,
+        }
+    }
+
+    /// The edges of a shape to query the quadtree with.
+    /// When touching is allowed, every edge is shortened and moved inwards by a tiny epsilon,
+    /// so only shapes which overlap by more than the epsilon collide.
+    fn query_edges<'a>(&self, shape: &'a SimplePolygon) -> impl Iterator<Item = Edge> + 'a {
+        let epsilon = self.touch_epsilon();
+        shape.edge_iter().map(move |e| match epsilon {
+            None => e,
+            Some(eps) => shrink_edge(e, eps).offset_left(eps),
+        })
+    }
+
+    /// Piers lie inside the shape, when touching is allowed only their ends have to be pulled back
+    fn query_pier(&self, pier: Edge) -> Edge {
+        match self.touch_epsilon() {
+            None => pier,
+            Some(eps) => shrink_edge(pier, eps),
+        }
+    }
+
+    /// Tolerance within which touching shapes are not considered colliding, if enabled in the config
+    fn touch_epsilon(&self) -> Option<fsize> {
+        match self.config.allow_touching && self.config.margin_range() == (0.0, 0.0) {
+            true => Some(self.bbox.diameter() * TOUCH_TOLERANCE),
+            false => None,
+        }
     }
 
     fn poly_collides_by_containment(
@@ -415,6 +794,31 @@ impl CDEngine {
             .any(|haz| self.poly_or_hazard_are_contained(shape, haz))
     }
 
+    /// Whether the circle enclosing `bbox`, enlarged by the largest margin, is guaranteed to be clear of the bin exterior and holes.
+    /// The distance to these hazards is bounded from below by the one of a nearby cell of the [`HazardProximityGrid`],
+    /// which is never invalidated, since these hazards are static.
+    fn out_of_reach_of_static_hazards(&self, bbox: &AARectangle) -> bool {
+        let Some(hpg) = self.haz_prox_grid.as_ref() else {
+            return false;
+        };
+        let center = bbox.centroid();
+        let reach = bbox.diameter() / 2.0 + self.config.margin_range().1;
+        let grid = &hpg.grid;
+        grid.rows_in_range(center.1..=center.1)
+            .cartesian_product(grid.cols_in_range(center.0..=center.0))
+            .filter_map(|(row, col)| grid.to_index(row, col).ok())
+            .filter_map(|i| grid.cells[i].as_ref())
+            .any(|cell| cell.static_uni_prox.0 - cell.centroid.distance(center) > reach)
+    }
+
+    /// The entities of the bin exterior and holes
+    fn container_hazards(&self) -> impl Iterator<Item = HazardEntity> + '_ {
+        self.static_hazards
+            .iter()
+            .map(|h| h.entity)
+            .filter(|e| matches!(e, HazardEntity::BinExterior | HazardEntity::BinHole { .. }))
+    }
+
     fn poly_or_hazard_are_contained(&self, shape: &SimplePolygon, haz: &Hazard) -> bool {
         //Due to possible fp issues, we check if the bboxes are "almost" related
         //"almost" meaning that, when edges are very close together, they are considered equal.
@@ -434,7 +838,7 @@ impl CDEngine {
             }
         };
 
-        if std::ptr::eq(haz_shape, s_omega) {
+        if core::ptr::eq(haz_shape, s_omega) {
             //s_omega is registered in the quadtree.
             //maybe the quadtree can help us.
             if let Ok(collides) = self
@@ -453,6 +857,38 @@ impl CDEngine {
         }
     }
 
+    /// Returns the entities of all placed items which lie (partially) within `radius` of `point`.
+    /// With a `radius` of zero, only the items containing the point are returned.
+    pub fn items_within(&self, point: &Point, radius: fsize) -> Vec<HazardEntity> {
+        let mut detected = FnvIndexSet::default();
+
+        if radius > 0.0 {
+            //static hazards are ignored by registering them as already detected
+            let mut colliding = self.static_hazards.iter().map(|h| h.entity).collect_vec();
+            let n_static = colliding.len();
+            let circle = Circle::new(*point, radius);
+            self.quadtree.collect_collisions(&circle, &mut colliding);
+            detected.extend(colliding.drain(n_static..));
+        }
+
+        //items fully surrounding the circle are not detected by the quadtree, they contain the point itself.
+        //Only the hazards present in the deepest node containing the point can contain it.
+        if let Some(node) = self.quadtree.deepest_node_containing(point) {
+            for qt_haz in node.hazards.active_hazards() {
+                let contains_point = match &qt_haz.presence {
+                    QTHazPresence::None => false,
+                    QTHazPresence::Entire => true,
+                    QTHazPresence::Partial(p_haz) => p_haz.shape_arc().collides_with(point),
+                };
+                if contains_point && matches!(qt_haz.entity, HazardEntity::PlacedItem { .. }) {
+                    detected.insert(qt_haz.entity);
+                }
+            }
+        }
+
+        detected.into_iter().collect()
+    }
+
     /// Returns all the (relevant) hazards present inside any [QTQueryable] entity
     pub fn hazards_within<T>(
         &self,
@@ -493,12 +929,15 @@ impl CDEngine {
         //temporarily add the irrelevant hazards to the buffer
         let n_init_detected = detected.len();
         detected.extend(irrelevant_hazards.iter().cloned());
+        //broad-phase: the bin exterior and holes can be ignored if the shape cannot reach them
+        if self.out_of_reach_of_static_hazards(&shape.bbox()) {
+            detected.extend(self.container_hazards());
+        }
         let irrelevant_range = n_init_detected..detected.len();
 
         //collect all colliding entities due to edge intersection
-        shape
-            .edge_iter()
-            .for_each(|e| self.quadtree.collect_collisions(&e, detected));
+        self.query_edges(shape)
+            .for_each(|e| self.collect_edge_collisions(e, detected));
 
         //collect all colliding entities due to containment
         //TODO: check if gathering the hazards inside the bbox using the quadtree is faster
@@ -526,16 +965,50 @@ impl CDEngine {
         detected.extend(irrelevant_hazards.iter().cloned());
         let irrelevant_range = n_init_detected..detected.len();
 
+        let radius_offset = self.pole_radius_offset();
         for pole in base_surrogate.ff_poles() {
-            let t_pole = pole.transform_clone(transform);
+            let mut t_pole = pole.transform_clone(transform);
+            t_pole.radius = (t_pole.radius + radius_offset).max(0.0);
             self.quadtree.collect_collisions(&t_pole, detected)
         }
         for pier in base_surrogate.ff_piers() {
-            let t_pier = pier.transform_clone(transform);
-            self.quadtree.collect_collisions(&t_pier, detected);
+            let t_pier = self.query_pier(pier.transform_clone(transform));
+            self.collect_edge_collisions(t_pier, detected);
         }
 
         //drain the irrelevant hazards, leaving only the colliding entities
         detected.drain(irrelevant_range);
     }
 }
+
+/// The fail-fast poles of the shape, none if it has no surrogate
+fn ff_poles(shape: &SimplePolygon) -> &[Circle] {
+    shape.surrogate.as_ref().map_or(&[], |s| s.ff_poles())
+}
+
+/// Distance between two rectangles, zero if they overlap
+fn bbox_distance(r1: &AARectangle, r2: &AARectangle) -> fsize {
+    let dx = fsize::max(0.0, fsize::max(r1.x_min - r2.x_max, r2.x_min - r1.x_max));
+    let dy = fsize::max(0.0, fsize::max(r1.y_min - r2.y_max, r2.y_min - r1.y_max));
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// Position of a point on the Z-order curve through `bbox`, which visits the quadrants in the same recursive order as the quadtree
+fn z_order(bbox: &AARectangle, point: &Point) -> u32 {
+    let quantize = |v: fsize, min: fsize, size: fsize| {
+        (((v - min) / size).clamp(0.0, 1.0) * u16::MAX as fsize) as u32
+    };
+    let x = quantize(point.0, bbox.x_min, bbox.width());
+    let y = quantize(point.1, bbox.y_min, bbox.height());
+    (0..16).fold(0, |key, bit| {
+        key | (((x >> bit) & 1) << (2 * bit)) | (((y >> bit) & 1) << (2 * bit + 1))
+    })
+}
+
+/// Shortens the edge by `epsilon` at both ends, edges too short for this are left untouched
+fn shrink_edge(edge: Edge, epsilon: fsize) -> Edge {
+    match edge.diameter() > 4.0 * epsilon {
+        true => edge.extend_at_front(-epsilon).extend_at_back(-epsilon),
+        false => edge,
+    }
+}
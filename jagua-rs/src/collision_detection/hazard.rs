@@ -2,8 +2,8 @@ use crate::entities::placed_item::PlacedItem;
 use crate::geometry::d_transformation::DTransformation;
 use crate::geometry::geo_enums::GeoPosition;
 use crate::geometry::primitives::simple_polygon::SimplePolygon;
-use std::borrow::Borrow;
-use std::sync::Arc;
+use alloc::sync::Arc;
+use core::borrow::Borrow;
 
 /// Defines a certain spatial constraint that affects the feasibility of a placement.
 #[derive(Clone, Debug)]
@@ -37,6 +37,9 @@ pub enum HazardEntity {
     BinHole { id: usize },
     /// Represents a zone in the bin with a specific quality level that is inferior to the base quality.
     InferiorQualityZone { quality: usize, id: usize },
+    /// Represents a zone in the bin which is discouraged, but not forbidden.
+    /// Soft zones never cause collisions, but optimizers can penalize proximity to them.
+    SoftZone { id: usize },
 }
 
 impl HazardEntity {
@@ -47,6 +50,7 @@ impl HazardEntity {
             HazardEntity::BinExterior => GeoPosition::Exterior,
             HazardEntity::BinHole { .. } => GeoPosition::Interior,
             HazardEntity::InferiorQualityZone { .. } => GeoPosition::Interior,
+            HazardEntity::SoftZone { .. } => GeoPosition::Interior,
         }
     }
 
@@ -57,6 +61,7 @@ impl HazardEntity {
             HazardEntity::BinExterior => false,
             HazardEntity::BinHole { .. } => false,
             HazardEntity::InferiorQualityZone { .. } => false,
+            HazardEntity::SoftZone { .. } => false,
         }
     }
 
@@ -67,8 +72,14 @@ impl HazardEntity {
             HazardEntity::BinExterior => true,
             HazardEntity::BinHole { .. } => true,
             HazardEntity::InferiorQualityZone { .. } => false,
+            HazardEntity::SoftZone { .. } => true,
         }
     }
+
+    /// Whether the entity is soft, i.e. it does not cause collisions
+    pub fn is_soft(&self) -> bool {
+        matches!(self, HazardEntity::SoftZone { .. })
+    }
 }
 
 impl<T> From<T> for HazardEntity
@@ -1,3 +1,5 @@
+use alloc::{boxed::Box, vec::Vec};
+
 use itertools::Itertools;
 
 use crate::collision_detection::hazard::Hazard;
@@ -45,6 +47,7 @@ impl HazardFilter for BinHazardFilter {
             HazardEntity::BinExterior => true,
             HazardEntity::BinHole { .. } => true,
             HazardEntity::InferiorQualityZone { .. } => true,
+            HazardEntity::SoftZone { .. } => true,
         }
     }
 }
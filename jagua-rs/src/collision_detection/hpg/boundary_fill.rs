@@ -1,5 +1,6 @@
-use std::collections::VecDeque;
-use std::ops::RangeInclusive;
+use alloc::collections::VecDeque;
+use alloc::{vec, vec::Vec};
+use core::ops::RangeInclusive;
 
 use crate::collision_detection::hpg::grid::Grid;
 use crate::geometry::geo_enums::GeoPosition;
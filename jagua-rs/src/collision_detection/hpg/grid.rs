@@ -1,5 +1,6 @@
-use std::cmp::Ordering;
-use std::ops::RangeInclusive;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::ops::RangeInclusive;
 
 use itertools::Itertools;
 use ordered_float::NotNan;
@@ -28,15 +29,15 @@ impl<T> Grid<T> {
         let rows = elements
             .iter()
             .map(|(_e, Point(_x, y))| NotNan::new(*y).unwrap())
-            .unique()
             .sorted()
+            .dedup()
             .collect::<Vec<NotNan<fsize>>>();
 
         let cols = elements
             .iter()
             .map(|(_e, Point(x, _y))| NotNan::new(*x).unwrap())
-            .unique()
             .sorted()
+            .dedup()
             .collect::<Vec<NotNan<fsize>>>();
 
         let n_rows = rows.len();
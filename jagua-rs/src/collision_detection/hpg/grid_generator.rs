@@ -1,4 +1,8 @@
-use std::cmp::Ordering;
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
 use log::debug;
 
@@ -85,7 +89,7 @@ where
     hazards
         .map(|haz| {
             let (pos, prox) = haz.shape.distance_from_border(point);
-            match pos == haz.entity.position() {
+            match pos.is_in(haz.entity.position()) {
                 true => -prox, //cell in hazard, negative distance
                 false => prox,
             }
@@ -1,5 +1,6 @@
-use std::fmt::{Display, Formatter};
-use std::iter;
+use alloc::{vec, vec::Vec};
+use core::fmt::{Display, Formatter};
+use core::iter;
 
 use itertools::Itertools;
 
@@ -153,7 +154,7 @@ impl HazardProximityGrid {
 pub struct DirtyState;
 
 impl Display for DirtyState {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "Dirty state detected. Make sure all changes are flushed before accessing the grid."
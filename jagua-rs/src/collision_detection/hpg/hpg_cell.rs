@@ -1,4 +1,5 @@
-use std::cmp::Ordering;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
 
 use itertools::Itertools;
 use ordered_float::NotNan;
@@ -8,7 +9,7 @@ use crate::collision_detection::hazard::HazardEntity;
 use crate::entities::item::Item;
 use crate::entities::quality_zone::N_QUALITIES;
 use crate::fsize;
-use crate::geometry::geo_enums::GeoPosition;
+use crate::geometry::geo_enums::{BorderPosition, GeoPosition};
 use crate::geometry::geo_traits::{DistanceFrom, Shape};
 use crate::geometry::primitives::aa_rectangle::AARectangle;
 use crate::geometry::primitives::circle::Circle;
@@ -40,7 +41,7 @@ impl HPGCell {
 
         for hazard in static_hazards {
             let (pos, distance) = hazard.shape.distance_from_border(&centroid);
-            let prox = match pos == hazard.entity.position() {
+            let prox = match pos.is_in(hazard.entity.position()) {
                 true => 0.0, //cells centroid is inside the hazard
                 false => distance,
             };
@@ -82,8 +83,10 @@ impl HPGCell {
                         let pole_bounding_circle = &haz.shape.surrogate().poles_bounding_circle;
                         let proximity = pole_bounding_circle.distance_from_border(&self.centroid);
                         match proximity {
-                            (GeoPosition::Interior, _) => (haz, Some(0.0)),
-                            (GeoPosition::Exterior, dist) => (haz, Some(dist.abs())),
+                            (BorderPosition::Exterior, dist) => (haz, Some(dist.abs())),
+                            (BorderPosition::Interior | BorderPosition::Boundary, _) => {
+                                (haz, Some(0.0))
+                            }
                         }
                     }
                 }
@@ -163,8 +166,8 @@ impl HPGCell {
         //For dynamic hazards, the surrogate poles are used to calculate the distance to the hazard (overestimation, but fast)
         let new_prox = match to_register.entity.position() {
             GeoPosition::Interior => match pole.distance_from_border(&self.centroid) {
-                (GeoPosition::Interior, _) => 0.0,
-                (GeoPosition::Exterior, dist) => dist.abs(),
+                (BorderPosition::Exterior, dist) => dist.abs(),
+                (BorderPosition::Interior | BorderPosition::Boundary, _) => 0.0,
             },
             GeoPosition::Exterior => {
                 panic!("No implementation yet for dynamic exterior hazards")
@@ -248,8 +251,8 @@ pub fn distance_to_surrogate_poles_border(hp_cell: &HPGCell, poles: &[Circle]) -
         .iter()
         .map(|p| p.distance_from_border(&hp_cell.centroid))
         .map(|(pos, dist)| match pos {
-            GeoPosition::Interior => 0.0,
-            GeoPosition::Exterior => dist.abs(),
+            BorderPosition::Exterior => dist.abs(),
+            BorderPosition::Interior | BorderPosition::Boundary => 0.0,
         })
         .min_by(|a, b| a.partial_cmp(b).unwrap())
         .unwrap()
@@ -1,3 +1,4 @@
+pub mod qt_dump;
 pub mod qt_hazard;
 mod qt_hazard_vec;
 pub mod qt_node;
@@ -0,0 +1,31 @@
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::fsize;
+
+/// Serializable representation of the structure of a quadtree, for visualization and debugging,
+/// see [CDEngine::export_quadtree](crate::collision_detection::cd_engine::CDEngine::export_quadtree).
+/// Coordinates are expressed in the internal coordinate system of the bin.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QuadtreeDump {
+    /// All nodes of the quadtree in depth-first order, the root first
+    pub nodes: Vec<QTNodeDump>,
+}
+
+/// A single node of a [QuadtreeDump]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct QTNodeDump {
+    /// The level of the node in the tree, 0 being the bottom-most level
+    pub level: u8,
+    pub x_min: fsize,
+    pub y_min: fsize,
+    pub x_max: fsize,
+    pub y_max: fsize,
+    /// Whether the node has no children
+    pub leaf: bool,
+    /// Number of active hazards entirely present in the node
+    pub n_entire: usize,
+    /// Number of active hazards partially present in the node
+    pub n_partial: usize,
+}
@@ -1,5 +1,5 @@
-use std::borrow::Borrow;
-use std::sync::Arc;
+use alloc::sync::Arc;
+use core::borrow::Borrow;
 
 use arr_macro::arr;
 
@@ -30,6 +30,22 @@ pub enum QTHazPresence {
     /// The hazard is entirely present in the node
     Entire,
 }
+/// Number of occurrences of each kind of [QTHazPresence] among the active hazards in a quadtree,
+/// see [CDEngine::hazard_presence_histogram](crate::collision_detection::cd_engine::CDEngine::hazard_presence_histogram)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QTHazPresenceHistogram {
+    /// Number of nodes in which no active hazard is present
+    pub none: usize,
+    /// Number of (node, hazard) pairs where the hazard is partially present
+    pub partial: usize,
+    /// Number of (node, hazard) pairs where the hazard is entirely present
+    pub entire: usize,
+    /// Total number of edges across all partially present hazards
+    pub partial_edges: usize,
+    /// Number of leaf nodes in which at least one hazard is partially present
+    pub partial_leaves: usize,
+}
+
 impl QTHazard {
     fn new(entity: HazardEntity, presence: QTHazPresence, active: bool) -> Option<Self> {
         match presence {
@@ -1,4 +1,5 @@
-use std::cmp::Ordering;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
 
 use crate::collision_detection::hazard::HazardEntity;
 use crate::collision_detection::quadtree::qt_hazard::QTHazPresence;
@@ -140,6 +141,14 @@ impl QTHazardVec {
         self.hazards.len()
     }
 
+    /// Number of hazards which are partially present
+    pub fn n_partial(&self) -> usize {
+        self.hazards
+            .iter()
+            .filter(|hz| matches!(hz.presence, QTHazPresence::Partial(_)))
+            .count()
+    }
+
     pub fn has_only_entire_hazards(&self) -> bool {
         self.hazards
             .iter()
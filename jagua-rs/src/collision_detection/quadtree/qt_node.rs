@@ -1,12 +1,17 @@
-use tribool::Tribool;
+use alloc::{boxed::Box, vec::Vec};
+
+use crate::geometry::geo_enums::Tribool;
 
 use crate::collision_detection::hazard::HazardEntity;
+use crate::collision_detection::quadtree::qt_dump::QTNodeDump;
 use crate::collision_detection::quadtree::qt_hazard::QTHazPresence;
-use crate::collision_detection::quadtree::qt_hazard::QTHazard;
+use crate::collision_detection::quadtree::qt_hazard::{QTHazPresenceHistogram, QTHazard};
 use crate::collision_detection::quadtree::qt_hazard_vec::QTHazardVec;
+use crate::collision_detection::quadtree::qt_partial_hazard::RelevantEdges;
 use crate::collision_detection::quadtree::qt_traits::QTQueryable;
 use crate::geometry::geo_traits::CollidesWith;
 use crate::geometry::primitives::aa_rectangle::AARectangle;
+use crate::geometry::primitives::point::Point;
 
 /// A node in the quadtree
 #[derive(Clone, Debug)]
@@ -19,15 +24,18 @@ pub struct QTNode {
     pub children: Option<Box<[QTNode; 4]>>,
     /// The hazards present in the node
     pub hazards: QTHazardVec,
+    /// Minimum number of partially present hazards before the node is split
+    pub min_hazards_to_split: usize,
 }
 
 impl QTNode {
-    pub fn new(level: u8, bbox: AARectangle) -> Self {
+    pub fn new(level: u8, bbox: AARectangle, min_hazards_to_split: usize) -> Self {
         QTNode {
             level,
             bbox,
             children: None,
             hazards: QTHazardVec::new(),
+            min_hazards_to_split,
         }
     }
 
@@ -45,10 +53,11 @@ impl QTNode {
             }
         }
 
-        //If the hazard is of the partial type, and we are not at the max tree depth: generate children
+        //If the hazard is of the partial type, enough partial hazards are present and we are not at the max tree depth: generate children
         if !self.has_children()
             && self.level > 0
             && matches!(hazard.presence, QTHazPresence::Partial(_))
+            && self.hazards.n_partial() + 1 >= self.min_hazards_to_split
         {
             self.generate_children();
             //register all existing hazards to the newly created children
@@ -65,8 +74,8 @@ impl QTNode {
         let removed_ch = self.hazards.remove(hazard_entity);
 
         if removed_ch.is_some() && self.has_children() {
-            if self.hazards.is_empty() || self.hazards.has_only_entire_hazards() {
-                //If there are no hazards, or only entire hazards, drop the children
+            if self.hazards.n_partial() < self.min_hazards_to_split.max(1) {
+                //If there are too few partial hazards left (or only entire hazards), drop the children
                 self.children = None;
             } else {
                 //Otherwise, recursively deregister the entity from the children
@@ -102,7 +111,8 @@ impl QTNode {
     fn generate_children(&mut self) {
         if self.level > 0 {
             let quadrants = self.bbox.quadrants();
-            let children = quadrants.map(|q| QTNode::new(self.level - 1, q));
+            let children =
+                quadrants.map(|q| QTNode::new(self.level - 1, q, self.min_hazards_to_split));
             self.children = Some(Box::new(children));
         }
     }
@@ -123,6 +133,74 @@ impl QTNode {
         self.children.is_some()
     }
 
+    /// Appends this node and all of its descendants to `nodes`, in depth-first order
+    pub fn dump(&self, nodes: &mut Vec<QTNodeDump>) {
+        let active_hazards = self.hazards.active_hazards();
+        let n_entire = active_hazards
+            .iter()
+            .filter(|hz| matches!(hz.presence, QTHazPresence::Entire))
+            .count();
+        nodes.push(QTNodeDump {
+            level: self.level,
+            x_min: self.bbox.x_min,
+            y_min: self.bbox.y_min,
+            x_max: self.bbox.x_max,
+            y_max: self.bbox.y_max,
+            leaf: !self.has_children(),
+            n_entire,
+            n_partial: active_hazards.len() - n_entire,
+        });
+        if let Some(children) = &self.children {
+            children.iter().for_each(|c| c.dump(nodes));
+        }
+    }
+
+    /// Adds the presence of the active hazards in this node and all of its descendants to the histogram
+    pub fn add_to_presence_histogram(&self, histogram: &mut QTHazPresenceHistogram) {
+        let active_hazards = self.hazards.active_hazards();
+        if active_hazards.is_empty() {
+            histogram.none += 1;
+        }
+        let mut has_partial = false;
+        for hazard in active_hazards {
+            match &hazard.presence {
+                QTHazPresence::None => {} //never stored in a node
+                QTHazPresence::Entire => histogram.entire += 1,
+                QTHazPresence::Partial(partial_haz) => {
+                    has_partial = true;
+                    histogram.partial += 1;
+                    histogram.partial_edges += match &partial_haz.edges {
+                        RelevantEdges::All => partial_haz.shape_arc().number_of_points(),
+                        RelevantEdges::Some(indices) => indices.len(),
+                    };
+                }
+            }
+        }
+        match &self.children {
+            Some(children) => children
+                .iter()
+                .for_each(|c| c.add_to_presence_histogram(histogram)),
+            None => {
+                if has_partial {
+                    histogram.partial_leaves += 1;
+                }
+            }
+        }
+    }
+
+    /// Returns the deepest node whose bounding box contains the point, `None` if the point lies outside this node
+    pub fn deepest_node_containing(&self, point: &Point) -> Option<&QTNode> {
+        match self.bbox.collides_with(point) {
+            false => None,
+            true => match &self.children {
+                Some(children) => children
+                    .iter()
+                    .find_map(|child| child.deepest_node_containing(point)),
+                None => Some(self),
+            },
+        }
+    }
+
     /// Used to detect collisions in a binary fashion: either there is a collision or there isn't.
     /// Returns `None` if no collision between the entity and any hazard is detected,
     /// otherwise the first encountered hazard that collides with the entity is returned.
@@ -1,6 +1,7 @@
-use std::borrow::Borrow;
-use std::hash::Hash;
-use std::sync::{Arc, Weak};
+use alloc::sync::{Arc, Weak};
+use alloc::{vec, vec::Vec};
+use core::borrow::Borrow;
+use core::hash::Hash;
 
 use crate::collision_detection::hazard::Hazard;
 use crate::collision_detection::quadtree::qt_traits::QTQueryable;
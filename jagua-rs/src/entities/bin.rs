@@ -1,4 +1,5 @@
-use std::sync::Arc;
+use alloc::sync::Arc;
+use alloc::{vec, vec::Vec};
 
 use itertools::Itertools;
 
@@ -8,11 +9,14 @@ use crate::collision_detection::hazard::HazardEntity;
 use crate::entities::quality_zone::InferiorQualityZone;
 use crate::entities::quality_zone::N_QUALITIES;
 use crate::fsize;
-use crate::geometry::geo_traits::Shape;
+use crate::geometry::geo_traits::{Shape, Transformable};
+use crate::geometry::offset;
 use crate::geometry::primitives::aa_rectangle::AARectangle;
 use crate::geometry::primitives::simple_polygon::SimplePolygon;
 use crate::geometry::transformation::Transformation;
+use crate::io::pretransform;
 use crate::util::config::CDEConfig;
+use crate::util::fnv::FnvIndexSet;
 
 /// A container in which items can be placed.
 #[derive(Clone, Debug)]
@@ -28,12 +32,57 @@ pub struct Bin {
     pub holes: Vec<Arc<SimplePolygon>>,
     /// Zones of different qualities in the bin, stored per quality.
     pub quality_zones: [Option<InferiorQualityZone>; N_QUALITIES],
+    /// Zones in the bin where placing items is discouraged, but not forbidden
+    pub soft_zones: Vec<Arc<SimplePolygon>>,
+    /// Distance from each edge of `outer` (edge `i` runs from point `i` to `i+1`) within which no items can be placed
+    pub edge_margins: Vec<fsize>,
+    /// The region in which items can be placed: `outer` shrunk by the margins
+    pub usable: Arc<SimplePolygon>,
     /// The starting state of the `CDEngine` for this bin.
     pub base_cde: Arc<CDEngine>,
     pub area: fsize,
 }
 
+/// Margins along the sides of a bin within which no items can be placed, for example the clamping area of a sheet
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BinMargin {
+    pub left: fsize,
+    pub right: fsize,
+    pub bottom: fsize,
+    pub top: fsize,
+}
+
+impl BinMargin {
+    pub fn uniform(margin: fsize) -> Self {
+        Self {
+            left: margin,
+            right: margin,
+            bottom: margin,
+            top: margin,
+        }
+    }
+
+    /// Margin of every edge of `outer`, determined by the side its outward normal points to
+    pub fn edge_margins(&self, outer: &SimplePolygon) -> Vec<fsize> {
+        outer
+            .edge_iter()
+            .map(|e| {
+                let (dx, dy) = (e.end.0 - e.start.0, e.end.1 - e.start.1);
+                //outward normal of a counterclockwise polygon
+                let (nx, ny) = (dy, -dx);
+                match nx.abs() >= ny.abs() {
+                    true if nx < 0.0 => self.left,
+                    true => self.right,
+                    false if ny < 0.0 => self.bottom,
+                    false => self.top,
+                }
+            })
+            .collect()
+    }
+}
+
 impl Bin {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         id: usize,
         outer: SimplePolygon,
@@ -41,13 +90,19 @@ impl Bin {
         pretransform: Transformation,
         holes: Vec<SimplePolygon>,
         quality_zones: Vec<InferiorQualityZone>,
+        soft_zones: Vec<SimplePolygon>,
         cde_config: CDEConfig,
     ) -> Self {
         let outer = Arc::new(outer);
         let holes = holes.into_iter().map(Arc::new).collect_vec();
+        let soft_zones = soft_zones.into_iter().map(Arc::new).collect_vec();
         assert_eq!(
             quality_zones.len(),
-            quality_zones.iter().map(|qz| qz.quality).unique().count(),
+            quality_zones
+                .iter()
+                .map(|qz| qz.quality)
+                .collect::<FnvIndexSet<_>>()
+                .len(),
             "Quality zones must have unique qualities"
         );
         assert!(
@@ -66,7 +121,9 @@ impl Bin {
             qz
         };
 
-        let bin_hazards = generate_bin_hazards(&outer, &holes, &quality_zones);
+        let usable = outer.clone();
+        let edge_margins = vec![0.0; outer.number_of_points()];
+        let bin_hazards = generate_bin_hazards(&usable, &holes, &quality_zones, &soft_zones);
 
         let base_cde = CDEngine::new(outer.bbox().inflate_to_square(), bin_hazards, cde_config);
         let base_cde = Arc::new(base_cde);
@@ -79,11 +136,62 @@ impl Bin {
             pretransform,
             holes,
             quality_zones,
+            soft_zones,
+            edge_margins,
+            usable,
             base_cde,
             area,
         }
     }
 
+    /// Returns a copy of the bin in which no items can be placed within `margin` of its sides.
+    /// Returns `None` if the margins leave no usable region, for example if they exceed half the width or height of the bin.
+    pub fn with_margin(&self, margin: &BinMargin) -> Option<Self> {
+        self.with_edge_margins(margin.edge_margins(&self.outer))
+    }
+
+    /// Returns a copy of the bin with a margin for every edge of `outer`, see [`Bin::edge_margins`].
+    /// The edges are offset inwards, `None` is returned if an edge collapses or the usable region would no longer be a simple polygon.
+    pub fn with_edge_margins(&self, edge_margins: Vec<fsize>) -> Option<Self> {
+        assert_eq!(edge_margins.len(), self.outer.number_of_points());
+        assert!(
+            edge_margins.iter().all(|m| *m >= 0.0),
+            "margins must be positive"
+        );
+        let usable = match edge_margins.iter().all(|m| *m == 0.0) {
+            true => self.outer.clone(),
+            false => {
+                let distances = edge_margins.iter().map(|m| -m).collect_vec();
+                Arc::new(offset::offset_edges(&self.outer, &distances)?)
+            }
+        };
+        let bin = Self {
+            edge_margins,
+            usable,
+            ..self.clone()
+        };
+        Some(bin.with_cde_config(self.base_cde.config()))
+    }
+
+    /// Returns a copy of the bin with its collision detection engine rebuilt using `cde_config`
+    pub fn with_cde_config(&self, cde_config: CDEConfig) -> Self {
+        let bin_hazards = generate_bin_hazards(
+            &self.usable,
+            &self.holes,
+            &self.quality_zones,
+            &self.soft_zones,
+        );
+        let base_cde = CDEngine::new(
+            self.outer.bbox().inflate_to_square(),
+            bin_hazards,
+            cde_config,
+        );
+        Self {
+            base_cde: Arc::new(base_cde),
+            ..self.clone()
+        }
+    }
+
     /// Create a new `Bin` for a strip-packing problem. Instead of a shape, the bin is always rectangular.
     pub fn from_strip(rect: AARectangle, cde_config: CDEConfig) -> Self {
         let id = 0;
@@ -93,21 +201,45 @@ impl Bin {
         let poly = SimplePolygon::from(rect);
         let value = poly.area() as u64;
 
-        Bin::new(id, poly, value, pretransform, vec![], vec![], cde_config)
+        Bin::new(
+            id,
+            poly,
+            value,
+            pretransform,
+            vec![],
+            vec![],
+            vec![],
+            cde_config,
+        )
     }
 
     pub fn bbox(&self) -> AARectangle {
         self.outer.bbox()
     }
+
+    /// Rotates the bin by `angle` radians, the bottom-left corner of its bounding box is kept in place.
+    /// The rotation is composed into the pretransform, so transformations relative to the original shape in the input file remain valid.
+    /// Returns the applied transformation.
+    pub fn rotate(&mut self, angle: fsize) -> Transformation {
+        let rotation = Transformation::from_rotation(angle);
+        let (bbox, rotated_bbox) = (self.bbox(), self.outer.transform_clone(&rotation).bbox());
+        let transf = rotation.translate((
+            bbox.x_min - rotated_bbox.x_min,
+            bbox.y_min - rotated_bbox.y_min,
+        ));
+        *self = pretransform::pretransform_bin(self, &transf);
+        transf
+    }
 }
 
 fn generate_bin_hazards(
-    outer: &Arc<SimplePolygon>,
+    usable: &Arc<SimplePolygon>,
     holes: &[Arc<SimplePolygon>],
     quality_zones: &[Option<InferiorQualityZone>],
+    soft_zones: &[Arc<SimplePolygon>],
 ) -> Vec<Hazard> {
-    //Hazard induced by the outside of the bin
-    let mut hazards = vec![Hazard::new(HazardEntity::BinExterior, outer.clone())];
+    //Hazard induced by the outside of the (usable region of the) bin
+    let mut hazards = vec![Hazard::new(HazardEntity::BinExterior, usable.clone())];
 
     //Hazard induced by any holes in the bin
     hazards.extend(holes.iter().enumerate().map(|(i, shape)| {
@@ -125,5 +257,11 @@ fn generate_bin_hazards(
             hazards.push(Hazard::new(haz_entity, shape.clone()));
         }
     }
+
+    //Hazards induced by soft zones
+    hazards.extend(soft_zones.iter().enumerate().map(|(i, shape)| {
+        let haz_entity = HazardEntity::SoftZone { id: i };
+        Hazard::new(haz_entity, shape.clone())
+    }));
     hazards
 }
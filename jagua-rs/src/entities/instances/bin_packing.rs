@@ -1,5 +1,7 @@
+use alloc::vec::Vec;
+
 use crate::entities::bin::Bin;
-use crate::entities::instances::instance_generic::InstanceGeneric;
+use crate::entities::instances::instance_generic::{InstanceGeneric, UNLIMITED_ITEM_QTY};
 use crate::entities::item::Item;
 use crate::fsize;
 use crate::geometry::geo_traits::Shape;
@@ -11,7 +13,7 @@ use crate::util::assertions;
 pub struct BPInstance {
     /// Items to be packed in the instance, along with their requested quantities
     pub items: Vec<(Item, usize)>,
-    /// Total area of all items in the instance
+    /// Total area of all items (with a limited demand) in the instance
     pub item_area: fsize,
     /// Set of bins available to pack the items, along with their quantities
     pub bins: Vec<(Bin, usize)>,
@@ -23,6 +25,7 @@ impl BPInstance {
 
         let item_area = items
             .iter()
+            .filter(|(_, qty)| *qty != UNLIMITED_ITEM_QTY)
             .map(|(item, qty)| item.shape.area() * *qty as fsize)
             .sum();
 
@@ -1,8 +1,29 @@
+use alloc::sync::Arc;
+use alloc::{vec, vec::Vec};
+use core::hash::Hasher;
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use itertools::Itertools;
+
+use crate::entities::bin::Bin;
 use crate::entities::instances::bin_packing::BPInstance;
 use crate::entities::instances::instance_generic::InstanceGeneric;
 use crate::entities::instances::strip_packing::SPInstance;
 use crate::entities::item::Item;
+use crate::entities::quality_zone::{InferiorQualityZone, N_QUALITIES};
 use crate::fsize;
+use crate::geometry::geo_enums::AllowedRotation;
+use crate::geometry::geo_traits::{Shape, Transformable};
+use crate::geometry::primitives::simple_polygon::SimplePolygon;
+use crate::geometry::transformation::Transformation;
+use crate::io::pretransform;
+use crate::util::config::{CDEConfig, SPSurrogateConfig};
+use crate::util::fnv::FnvHasher;
+
+/// Number of decimals coordinates are rounded to before hashing
+const HASH_DECIMALS: i32 = 3;
 
 /// An `Instance` is the static (unmodifiable) representation of a problem instance.
 /// This enum contains all variants of an instance.
@@ -13,6 +34,53 @@ pub enum Instance {
     BP(BPInstance),
 }
 
+impl Instance {
+    /// Hash of the contents of the instance: the (rounded) shapes and demands of the items and the geometry of the containers.
+    /// Independent of the order of items and bins and stable across process runs.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = StableHasher::new();
+        let item_hashes = self.items().iter().map(|(item, qty)| item_hash(item, *qty));
+        match self {
+            Instance::SP(spi) => {
+                hasher.write_u64(0);
+                hasher.write_fsize(spi.strip_height);
+                hasher.write_u64(spi.fixed_strip_width.is_some() as u64);
+                hasher.write_fsize(spi.fixed_strip_width.unwrap_or(0.0));
+            }
+            Instance::BP(bpi) => {
+                hasher.write_u64(1);
+                let bin_hashes = bpi.bins.iter().map(|(bin, qty)| bin_hash(bin, *qty));
+                bin_hashes.sorted().for_each(|h| hasher.write_u64(h));
+            }
+        }
+        item_hashes.sorted().for_each(|h| hasher.write_u64(h));
+        hasher.finish()
+    }
+
+    /// Returns a copy of the instance in which the items with the given ids are no longer demanded.
+    /// The items are kept with a demand of 0, so all ids remain valid, see [`Instance::with_demand_override`].
+    pub fn without_items(&self, ids: &[usize]) -> Instance {
+        let overrides = ids.iter().map(|&id| (id, 0)).collect_vec();
+        self.with_demand_override(&overrides)
+    }
+
+    /// Returns a copy of the instance with the demand of some items replaced, as `(item id, quantity)` pairs.
+    /// The shapes (and their surrogates) are shared with the original instance, only the item metadata is cloned.
+    pub fn with_demand_override(&self, overrides: &[(usize, usize)]) -> Instance {
+        let mut items = self.items().to_vec();
+        for &(id, qty) in overrides {
+            assert!(id < items.len(), "item {id} does not exist");
+            items[id].1 = qty;
+        }
+        match self {
+            Instance::SP(spi) => {
+                SPInstance::new(items, spi.strip_height, spi.fixed_strip_width).into()
+            }
+            Instance::BP(bpi) => BPInstance::new(items, bpi.bins.clone()).into(),
+        }
+    }
+}
+
 impl InstanceGeneric for Instance {
     fn items(&self) -> &[(Item, usize)] {
         match self {
@@ -40,3 +108,219 @@ impl From<BPInstance> for Instance {
         Instance::BP(instance)
     }
 }
+
+/// The container(s) of an instance built with [`InstanceBuilder`]
+#[derive(Debug, Clone)]
+pub enum InstanceContainer {
+    /// A strip of fixed height, with an optional fixed width
+    Strip {
+        height: fsize,
+        fixed_width: Option<fsize>,
+    },
+    /// Bins, as `(shape, stock)` pairs
+    Bins(Vec<(SimplePolygon, usize)>),
+}
+
+/// Constructs an [`Instance`] directly from shapes, without going through the JSON representation.
+/// Items and bins are created exactly like the [`Parser`](crate::io::parser::Parser) does: surrogates are generated and
+/// (unless disabled) all shapes are centered around the origin. Shapes are used as-is, no simplification is applied.
+/// ```ignore
+/// let instance = InstanceBuilder::new(container, cde_config)
+///     .add_item(shape, 10, AllowedRotation::Continuous)
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct InstanceBuilder {
+    container: InstanceContainer,
+    cde_config: CDEConfig,
+    center_polygons: bool,
+    items: Vec<(SimplePolygon, usize, AllowedRotation)>,
+}
+
+impl InstanceBuilder {
+    pub fn new(container: InstanceContainer, cde_config: CDEConfig) -> Self {
+        Self {
+            container,
+            cde_config,
+            center_polygons: true,
+            items: vec![],
+        }
+    }
+
+    /// Whether to center the shapes around the origin, enabled by default
+    pub fn center_polygons(mut self, center_polygons: bool) -> Self {
+        self.center_polygons = center_polygons;
+        self
+    }
+
+    /// Adds an item, its id is the number of items added before it.
+    /// Use [`UNLIMITED_ITEM_QTY`](crate::entities::instances::instance_generic::UNLIMITED_ITEM_QTY) for an unbounded demand.
+    pub fn add_item(
+        mut self,
+        shape: SimplePolygon,
+        demand: usize,
+        rotations: AllowedRotation,
+    ) -> Self {
+        self.items.push((shape, demand, rotations));
+        self
+    }
+
+    pub fn build(self) -> Instance {
+        let items = self
+            .items
+            .into_iter()
+            .enumerate()
+            .map(|(id, (shape, demand, rotations))| {
+                //same rule as the parser: tiny items are not worth a surrogate
+                let surrogate_config = match shape.area() < self.cde_config.item_surrogate_min_area
+                {
+                    true => SPSurrogateConfig::none(),
+                    false => self.cde_config.item_surrogate_config,
+                };
+                let item = Item::new(
+                    id,
+                    shape,
+                    rotations,
+                    None,
+                    0,
+                    Transformation::empty(),
+                    surrogate_config,
+                );
+                let item = match self.center_polygons {
+                    false => item,
+                    true => {
+                        let centering = pretransform::centering_transformation(&item.shape);
+                        pretransform::pretransform_item(&item, &centering.compose())
+                    }
+                };
+                (item, demand)
+            })
+            .collect_vec();
+
+        match self.container {
+            InstanceContainer::Strip {
+                height,
+                fixed_width,
+            } => SPInstance::new(items, height, fixed_width).into(),
+            InstanceContainer::Bins(bins) => {
+                let bins = bins
+                    .into_iter()
+                    .enumerate()
+                    .map(|(id, (shape, stock))| {
+                        let value = shape.area() as u64;
+                        //empty zone for every quality, like the parser, so both yield the same content hash
+                        let quality_zones = (0..N_QUALITIES)
+                            .map(|q| InferiorQualityZone::new(q, vec![]))
+                            .collect_vec();
+                        let bin = Bin::new(
+                            id,
+                            shape,
+                            value,
+                            Transformation::empty(),
+                            vec![],
+                            quality_zones,
+                            vec![],
+                            self.cde_config,
+                        );
+                        let bin = match self.center_polygons {
+                            false => bin,
+                            true => {
+                                let centering = pretransform::centering_transformation(&bin.outer);
+                                pretransform::pretransform_bin(&bin, &centering.compose())
+                            }
+                        };
+                        (bin, stock)
+                    })
+                    .collect_vec();
+                BPInstance::new(items, bins).into()
+            }
+        }
+    }
+}
+
+fn item_hash(item: &Item, qty: usize) -> u64 {
+    let mut hasher = StableHasher::new();
+    hasher.write_shape(
+        &item
+            .shape
+            .transform_clone(&item.pretransform.clone().inverse()),
+    );
+    hasher.write_u64(qty as u64);
+    hasher.write_u64(item.value);
+    hasher.write_u64(item.base_quality.map_or(0, |q| q as u64 + 1));
+    match &item.allowed_rotation {
+        AllowedRotation::None => hasher.write_u64(0),
+        AllowedRotation::Continuous => hasher.write_u64(1),
+        AllowedRotation::Discrete(rotations) => {
+            hasher.write_u64(2);
+            rotations.iter().for_each(|r| hasher.write_fsize(*r));
+        }
+    }
+    //only hashed when present, so instances without preferences keep their hash
+    if let Some(pr) = &item.preferred_region {
+        let r = &pr.rect;
+        [r.x_min, r.y_min, r.x_max, r.y_max, pr.weight]
+            .into_iter()
+            .for_each(|v| hasher.write_fsize(v));
+    }
+    hasher.finish()
+}
+
+fn bin_hash(bin: &Bin, qty: usize) -> u64 {
+    let inv_pretransform = bin.pretransform.clone().inverse();
+    let original = |shape: &Arc<SimplePolygon>| shape.transform_clone(&inv_pretransform);
+    let mut hasher = StableHasher::new();
+    hasher.write_shape(&original(&bin.outer));
+    hasher.write_u64(qty as u64);
+    hasher.write_u64(bin.value);
+    hasher.write_shapes_unordered(bin.holes.iter().map(original));
+    for qz in bin.quality_zones.iter().flatten() {
+        hasher.write_u64(qz.quality as u64);
+        hasher.write_shapes_unordered(qz.zones.iter().map(original));
+    }
+    hasher.write_shapes_unordered(bin.soft_zones.iter().map(original));
+    hasher.finish()
+}
+
+/// Hasher of instance contents, its output is guaranteed to be stable, see [`FnvHasher`]
+struct StableHasher(FnvHasher);
+
+impl StableHasher {
+    fn new() -> Self {
+        StableHasher(FnvHasher::default())
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.0.write(&value.to_le_bytes());
+    }
+
+    fn write_fsize(&mut self, value: fsize) {
+        let rounded = (value * (10.0 as fsize).powi(HASH_DECIMALS)).round() as i64;
+        self.write_u64(rounded as u64);
+    }
+
+    fn write_shape(&mut self, shape: &SimplePolygon) {
+        self.write_u64(shape.number_of_points() as u64);
+        for p in shape.points.iter() {
+            self.write_fsize(p.0);
+            self.write_fsize(p.1);
+        }
+    }
+
+    fn write_shapes_unordered(&mut self, shapes: impl Iterator<Item = SimplePolygon>) {
+        let shape_hashes = shapes
+            .map(|shape| {
+                let mut shape_hasher = StableHasher::new();
+                shape_hasher.write_shape(&shape);
+                shape_hasher.finish()
+            })
+            .sorted()
+            .collect_vec();
+        self.write_u64(shape_hashes.len() as u64);
+        shape_hashes.into_iter().for_each(|h| self.write_u64(h));
+    }
+
+    fn finish(&self) -> u64 {
+        self.0.finish()
+    }
+}
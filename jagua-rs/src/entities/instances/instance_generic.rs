@@ -1,6 +1,10 @@
 use crate::entities::item::Item;
 use crate::fsize;
 
+/// Quantity used to represent an item with an unlimited demand.
+/// Equal to `isize::MAX` so it can safely be used in signed quantity arithmetic.
+pub const UNLIMITED_ITEM_QTY: usize = isize::MAX as usize;
+
 /// Trait for shared functionality of all instance variants.
 pub trait InstanceGeneric {
     fn items(&self) -> &[(Item, usize)];
@@ -10,8 +14,17 @@ pub trait InstanceGeneric {
     fn item(&self, id: usize) -> &Item {
         &self.items()[id].0
     }
+    /// Whether the item has an unlimited demand
+    fn item_qty_unlimited(&self, id: usize) -> bool {
+        self.item_qty(id) == UNLIMITED_ITEM_QTY
+    }
+    /// Total quantity of all items, saturates at `UNLIMITED_ITEM_QTY` if any item has an unlimited demand
     fn total_item_qty(&self) -> usize {
-        self.items().iter().map(|(_, qty)| qty).sum()
+        self.items()
+            .iter()
+            .fold(0, |acc: usize, (_, qty)| acc.saturating_add(*qty))
+            .min(UNLIMITED_ITEM_QTY)
     }
+    /// Total area of all items with a limited demand
     fn item_area(&self) -> fsize;
 }
@@ -1,4 +1,6 @@
-use crate::entities::instances::instance_generic::InstanceGeneric;
+use alloc::vec::Vec;
+
+use crate::entities::instances::instance_generic::{InstanceGeneric, UNLIMITED_ITEM_QTY};
 use crate::entities::item::Item;
 use crate::fsize;
 use crate::geometry::geo_traits::Shape;
@@ -10,18 +12,25 @@ use crate::util::assertions;
 pub struct SPInstance {
     /// The items to be packed and their quantities
     pub items: Vec<(Item, usize)>,
-    /// The total area of the items
+    /// The total area of the items (with a limited demand)
     pub item_area: fsize,
     /// The (fixed) height of the strip
     pub strip_height: fsize,
+    /// The width of the strip, if it is fixed instead of variable
+    pub fixed_strip_width: Option<fsize>,
 }
 
 impl SPInstance {
-    pub fn new(items: Vec<(Item, usize)>, strip_height: fsize) -> Self {
+    pub fn new(
+        items: Vec<(Item, usize)>,
+        strip_height: fsize,
+        fixed_strip_width: Option<fsize>,
+    ) -> Self {
         assert!(assertions::instance_item_bin_ids_correct(&items, &[]));
 
         let item_area = items
             .iter()
+            .filter(|(_, qty)| *qty != UNLIMITED_ITEM_QTY)
             .map(|(item, qty)| item.shape.area() * *qty as fsize)
             .sum();
 
@@ -29,6 +38,7 @@ impl SPInstance {
             items,
             item_area,
             strip_height,
+            fixed_strip_width,
         }
     }
 }
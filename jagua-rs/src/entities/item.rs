@@ -1,10 +1,15 @@
-use std::sync::Arc;
+use alloc::sync::Arc;
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
 use crate::collision_detection::hazard_filter::QZHazardFilter;
 use crate::geometry::geo_enums::AllowedRotation;
+use crate::geometry::primitives::aa_rectangle::AARectangle;
 use crate::geometry::primitives::simple_polygon::SimplePolygon;
 use crate::geometry::transformation::Transformation;
 use crate::util::config::SPSurrogateConfig;
+use crate::{fsize, PI};
 
 /// Item to be placed in a Layout
 #[derive(Clone, Debug)]
@@ -23,6 +28,10 @@ pub struct Item {
     pub hazard_filter: Option<QZHazardFilter>,
     /// Configuration for the surrogate generation
     pub surrogate_config: SPSurrogateConfig,
+    /// Region of the container the item is preferably placed in, placements elsewhere remain allowed
+    pub preferred_region: Option<PreferredRegion>,
+    /// Arbitrary user data, not used by the library but attached to every placement of the item in the solution
+    pub metadata: Option<serde_json::Value>,
 }
 
 impl Item {
@@ -36,6 +45,32 @@ impl Item {
         surrogate_config: SPSurrogateConfig,
     ) -> Item {
         shape.generate_surrogate(surrogate_config);
+        Item::with_existing_surrogate(
+            id,
+            shape,
+            allowed_rotation,
+            base_quality,
+            value,
+            pretransform,
+            surrogate_config,
+        )
+    }
+
+    /// Like [`Item::new`], but keeps the surrogate already present on `shape` instead of generating one.
+    /// It should have been generated with `surrogate_config`, for example by [`SPSurrogate::new`](crate::geometry::fail_fast::sp_surrogate::SPSurrogate::new).
+    pub fn with_existing_surrogate(
+        id: usize,
+        shape: SimplePolygon,
+        allowed_rotation: AllowedRotation,
+        base_quality: Option<usize>,
+        value: u64,
+        pretransform: Transformation,
+        surrogate_config: SPSurrogateConfig,
+    ) -> Item {
+        assert!(
+            shape.surrogate.is_some(),
+            "shape of item {id} has no surrogate"
+        );
         let shape = Arc::new(shape);
         let hazard_filter = base_quality.map(QZHazardFilter);
         Item {
@@ -47,6 +82,85 @@ impl Item {
             pretransform,
             hazard_filter,
             surrogate_config,
+            preferred_region: None,
+            metadata: None,
         }
     }
+
+    /// Continuously rotatable item of full quality and without value, the shape is used as-is
+    pub fn from_shape(
+        id: usize,
+        shape: SimplePolygon,
+        surrogate_config: SPSurrogateConfig,
+    ) -> Item {
+        Item::new(
+            id,
+            shape,
+            AllowedRotation::Continuous,
+            None,
+            0,
+            Transformation::empty(),
+            surrogate_config,
+        )
+    }
+
+    /// Rectangular item of `width` by `height` with its bottom-left corner in the origin, see [`Item::from_shape`]
+    pub fn from_rect(
+        id: usize,
+        width: fsize,
+        height: fsize,
+        surrogate_config: SPSurrogateConfig,
+    ) -> Item {
+        assert!(
+            width > 0.0 && height > 0.0,
+            "invalid rectangle of {width} by {height}"
+        );
+        let shape = SimplePolygon::from(AARectangle::new(0.0, 0.0, width, height));
+        Item::from_shape(id, shape, surrogate_config)
+    }
+
+    /// Item shaped as a regular polygon around the origin, see [`SimplePolygon::regular`] and [`Item::from_shape`]
+    pub fn from_regular_polygon(
+        id: usize,
+        n_vertices: usize,
+        radius: fsize,
+        surrogate_config: SPSurrogateConfig,
+    ) -> Item {
+        Item::from_shape(
+            id,
+            SimplePolygon::regular(n_vertices, radius),
+            surrogate_config,
+        )
+    }
+
+    /// Circular item around the origin, approximated by a regular polygon of `n_segments` edges which encloses the circle
+    pub fn from_circle(
+        id: usize,
+        radius: fsize,
+        n_segments: usize,
+        surrogate_config: SPSurrogateConfig,
+    ) -> Item {
+        //the edges of the polygon touch the circle
+        let vertex_radius = radius / (PI / n_segments as fsize).cos();
+        Item::from_regular_polygon(id, n_segments, vertex_radius, surrogate_config)
+    }
+
+    pub fn with_preferred_region(mut self, preferred_region: PreferredRegion) -> Self {
+        self.preferred_region = Some(preferred_region);
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: serde_json::Value) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+}
+
+/// Soft constraint that attracts an item towards a region of the container
+#[derive(Clone, Debug)]
+pub struct PreferredRegion {
+    /// The region, in the coordinates of the container as defined in the input file
+    pub rect: AARectangle,
+    /// Importance of the preference relative to that of other items, conflicting preferences are traded off by weight
+    pub weight: fsize,
 }
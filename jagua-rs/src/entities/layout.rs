@@ -1,14 +1,45 @@
+use alloc::{boxed::Box, vec, vec::Vec};
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
 use crate::collision_detection::cd_engine::{CDESnapshot, CDEngine};
 use crate::collision_detection::hazard::{Hazard, HazardEntity};
+use crate::collision_detection::hazard_filter;
+use crate::collision_detection::hazard_filter::{CombinedHazardFilter, EntityHazardFilter};
 use crate::entities::bin::Bin;
+use crate::entities::instances::instance_generic::InstanceGeneric;
 use crate::entities::item::Item;
 use crate::entities::placed_item::{PItemKey, PlacedItem};
 use crate::fsize;
 use crate::geometry::d_transformation::DTransformation;
-use crate::geometry::geo_traits::Shape;
+use crate::geometry::geo_traits::{Shape, Transformable};
+use crate::geometry::primitives::point::Point;
+use crate::io::pretransform;
 use crate::util::assertions;
+use crate::util::fnv::FnvIndexSet;
+use crate::util::fpa::FPA;
+use crate::PI;
+use alloc::sync::Arc;
+use core::iter;
+use itertools::Itertools;
 use slotmap::SlotMap;
 
+/// Rotations within this many radians of an allowed increment are not snapped
+const SNAP_EPSILON: fsize = 1e-6;
+
+/// Distances, as fractions of the item's diameter, over which snapped items are nudged to resolve collisions
+const NUDGE_DISTANCES: [fsize; 4] = [0.001, 0.0025, 0.005, 0.01];
+
+/// Number of directions a snapped item is nudged in, for every distance
+const N_NUDGE_DIRECTIONS: usize = 8;
+
+/// Fractions of the tolerance over which protruding items are nudged in every direction when clamping them inside the container
+const CLAMP_DISTANCES: [fsize; 3] = [0.25, 0.5, 1.0];
+
+/// Distance, as a fraction of the bin's diameter, within which the edges of two items are considered coincident
+const SHARED_EDGE_TOLERANCE: fsize = 1e-5;
+
 ///A Layout is made out of a [Bin] with a set of [Item]s positioned inside of it in a specific way.
 ///It is a mutable representation, and can be modified by placing or removing items.
 ///
@@ -24,6 +55,37 @@ pub struct Layout {
     pub placed_items: SlotMap<PItemKey, PlacedItem>,
     /// The collision detection engine for this layout
     cde: CDEngine,
+    journal: Option<Vec<JournalEntry>>,
+}
+
+/// Operation on the placed items of a [Layout], recorded in its journal
+#[derive(Clone, Debug)]
+enum JournalEntry {
+    Placed(PItemKey),
+    Removed { key: PItemKey, pi: PlacedItem },
+    Moved { key: PItemKey, previous: PlacedItem },
+}
+
+impl JournalEntry {
+    /// Number of operations the entry accounts for in the journal of the [CDEngine]
+    fn n_cde_operations(&self) -> usize {
+        match self {
+            JournalEntry::Placed(_) | JournalEntry::Removed { .. } => 1,
+            JournalEntry::Moved { .. } => 2,
+        }
+    }
+
+    fn replace_key(&mut self, old: PItemKey, new: PItemKey) {
+        match self {
+            JournalEntry::Placed(key)
+            | JournalEntry::Removed { key, .. }
+            | JournalEntry::Moved { key, .. } => {
+                if *key == old {
+                    *key = new;
+                }
+            }
+        }
+    }
 }
 
 impl Layout {
@@ -34,6 +96,7 @@ impl Layout {
             bin,
             placed_items: SlotMap::with_key(),
             cde,
+            journal: None,
         }
     }
 
@@ -52,6 +115,10 @@ impl Layout {
             let hazard = Hazard::new(pi.into(), pi.shape.clone());
             self.cde.register_hazard(hazard);
         }
+        //the operations recorded so far do not apply to the new bin
+        if self.journal.is_some() {
+            self.start_journal();
+        }
     }
 
     pub fn create_snapshot(&mut self) -> LayoutSnapshot {
@@ -69,6 +136,9 @@ impl Layout {
 
         self.placed_items = layout_snapshot.placed_items.clone();
         self.cde.restore(&layout_snapshot.cde_snapshot);
+        if let Some(journal) = self.journal.as_mut() {
+            journal.clear();
+        }
 
         debug_assert!(assertions::layout_qt_matches_fresh_qt(self));
         debug_assert!(assertions::layouts_match(self, layout_snapshot))
@@ -84,6 +154,9 @@ impl Layout {
 
         let pik = self.placed_items.insert(pi);
         self.cde.register_hazard(hazard);
+        if let Some(journal) = self.journal.as_mut() {
+            journal.push(JournalEntry::Placed(pik));
+        }
 
         debug_assert!(assertions::layout_qt_matches_fresh_qt(self));
 
@@ -99,12 +172,90 @@ impl Layout {
         // update the collision detection engine
         self.cde
             .deregister_hazard(HazardEntity::from(&pi), commit_instant);
+        if let Some(journal) = self.journal.as_mut() {
+            journal.push(JournalEntry::Removed {
+                key,
+                pi: pi.clone(),
+            });
+        }
 
         debug_assert!(assertions::layout_qt_matches_fresh_qt(self));
 
         pi
     }
 
+    /// Moves a placed item of type `item` to a new transformation, keeping its key.
+    /// Only the item's own hazard in the CDE is updated, see [`CDEngine::update_hazard`].
+    pub fn move_item(&mut self, key: PItemKey, item: &Item, d_transformation: DTransformation) {
+        let pi = &mut self.placed_items[key];
+        assert_eq!(pi.item_id, item.id, "placed item is of another type");
+        let old_entity = HazardEntity::from(&*pi);
+        let previous = core::mem::replace(pi, PlacedItem::new(item, d_transformation));
+        let hazard = Hazard::new(HazardEntity::from(&*pi), pi.shape.clone());
+        self.cde.update_hazard(old_entity, hazard);
+        if let Some(journal) = self.journal.as_mut() {
+            journal.push(JournalEntry::Moved { key, previous });
+        }
+
+        debug_assert!(assertions::layout_qt_matches_fresh_qt(self));
+    }
+
+    /// Starts recording all placements, removals and moves of items, so they can be undone with [`Self::rollback`].
+    /// Both the placed items and the hazards in the [CDEngine] are recorded. Any previously recorded operations are discarded,
+    /// as are all recorded operations when the layout is restored or its bin is changed.
+    pub fn start_journal(&mut self) {
+        self.journal = Some(vec![]);
+        self.cde.start_journal();
+    }
+
+    /// Stops recording operations and discards the journal.
+    pub fn stop_journal(&mut self) {
+        self.journal = None;
+        self.cde.stop_journal();
+    }
+
+    /// Number of operations in the journal which can be rolled back.
+    pub fn journal_len(&self) -> usize {
+        self.journal.as_ref().map_or(0, |j| j.len())
+    }
+
+    /// Undoes the last `n` placements, removals and moves in the journal, most recent first.
+    /// Removed items are placed back under a new key, moved items keep their key.
+    /// Much cheaper than restoring a snapshot when only a few operations have to be undone.
+    pub fn rollback(&mut self, n: usize) {
+        let journal = self.journal.as_ref().expect("journal not started");
+        assert!(
+            n <= journal.len(),
+            "cannot roll back more operations than recorded"
+        );
+        let mut n_cde_operations = 0;
+        for _ in 0..n {
+            let entry = self.journal.as_mut().and_then(|j| j.pop()).unwrap();
+            n_cde_operations += entry.n_cde_operations();
+            match entry {
+                JournalEntry::Placed(key) => {
+                    self.placed_items
+                        .remove(key)
+                        .expect("key is not valid anymore");
+                }
+                JournalEntry::Removed { key, pi } => {
+                    //entries further back in the journal still refer to the old key
+                    let new_key = self.placed_items.insert(pi);
+                    self.journal
+                        .iter_mut()
+                        .flatten()
+                        .for_each(|e| e.replace_key(key, new_key));
+                }
+                JournalEntry::Moved { key, previous } => {
+                    self.placed_items[key] = previous;
+                }
+            }
+        }
+        self.cde.rollback(n_cde_operations);
+
+        debug_assert!(assertions::layout_qt_matches_fresh_qt(self));
+    }
+
     /// True if no items are placed
     pub fn is_empty(&self) -> bool {
         self.placed_items.is_empty()
@@ -121,6 +272,205 @@ impl Layout {
             .map(|(k, _)| k)
     }
 
+    /// Returns the keys of all placed items which lie (partially) within `radius` of `point`.
+    /// See [`CDEngine::items_within`].
+    pub fn items_within(&self, point: &Point, radius: fsize) -> Vec<PItemKey> {
+        let entities = self
+            .cde
+            .items_within(point, radius)
+            .into_iter()
+            .collect::<FnvIndexSet<_>>();
+        self.placed_items
+            .iter()
+            .filter(|(_, pi)| entities.contains(&HazardEntity::from(*pi)))
+            .map(|(k, _)| k)
+            .collect()
+    }
+
+    /// Rotates the entire layout (bin and placed items) by `angle` radians, see [`Bin::rotate`].
+    /// The rotation is composed into the bin's pretransform, so exported solutions remain expressed in the frame of the instance.
+    /// Returns whether the layout is still collision-free, rounding errors can introduce small collisions.
+    pub fn rotate(&mut self, angle: fsize) -> bool {
+        let mut rotated_bin = self.bin.clone();
+        let transf = rotated_bin.rotate(angle);
+
+        for (_, pi) in self.placed_items.iter_mut() {
+            pi.d_transf = pi.d_transf.compose().transform(&transf).decompose();
+            pi.shape = Arc::new(pi.shape.transform_clone(&transf));
+        }
+        self.change_bin(rotated_bin);
+
+        self.placed_items.values().all(|pi| !self.collides(pi))
+    }
+
+    /// Snaps the absolute rotation of every placed item to the nearest multiple of `increment` radians.
+    /// The items are rotated around the origin of their internal shape, which is their centroid for centered items.
+    /// If a snapped item collides, it is nudged over small distances in a few directions.
+    /// Items which cannot be snapped without a collision keep their original placement, their keys are returned.
+    /// Placed items are reinserted, so the keys of all snapped items change.
+    pub fn snap_rotations(
+        &mut self,
+        instance: &dyn InstanceGeneric,
+        increment: fsize,
+    ) -> Vec<PItemKey> {
+        assert!(increment > 0.0, "increment must be positive");
+        let mut unsnapped = vec![];
+        for pik in self.placed_items.keys().collect_vec() {
+            let pi = &self.placed_items[pik];
+            let item = instance.item(pi.item_id);
+            let abs_rotation = pretransform::internal_to_absolute_transform(
+                &pi.d_transf,
+                &item.pretransform,
+                &self.bin.pretransform,
+            )
+            .decompose()
+            .rotation();
+            let delta = (abs_rotation / increment).round() * increment - abs_rotation;
+            if delta.abs() < SNAP_EPSILON {
+                continue;
+            }
+            let original = pi.d_transf;
+            let rotation = original.rotation() + delta;
+            let (tx, ty) = original.translation();
+            let nudge = item.shape.diameter();
+
+            self.remove_item(pik, true);
+            let snapped = iter::once((0.0, 0.0))
+                .chain(NUDGE_DISTANCES.iter().flat_map(|d| {
+                    (0..N_NUDGE_DIRECTIONS).map(move |i| {
+                        let angle = 2.0 * PI * i as fsize / N_NUDGE_DIRECTIONS as fsize;
+                        (angle.cos() * d * nudge, angle.sin() * d * nudge)
+                    })
+                }))
+                .map(|(dx, dy)| DTransformation::new(rotation, (tx + dx, ty + dy)))
+                .find(|dt| !self.collides(&PlacedItem::new(item, *dt)));
+
+            match snapped {
+                Some(dt) => {
+                    self.place_item(item, dt);
+                }
+                None => unsnapped.push(self.place_item(item, original)),
+            }
+        }
+        unsnapped
+    }
+
+    /// Nudges placed items which protrude out of the container by no more than the tolerance of [`FPA`] back inside.
+    /// Such protrusions are floating point noise, for example from a round-trip through the JSON representation.
+    /// The tolerance is relative to the diameter of the bin, and items are never moved further than it.
+    /// Items are only moved to a position where they collide with nothing, so they can never end up overlapping another item.
+    /// Returns the keys of the items which still collide afterwards, including those colliding with other items, which are left untouched.
+    /// Placed items are reinserted, so the keys of all clamped items change.
+    pub fn clamp_to_container(&mut self, instance: &dyn InstanceGeneric) -> Vec<PItemKey> {
+        let tolerance = FPA::tolerance() * self.bin.outer.diameter();
+        let margin = self.cde.config().margin_for(&HazardEntity::BinExterior);
+        let container = {
+            let bbox = self.bin.usable.bbox();
+            (
+                bbox.x_min + margin,
+                bbox.y_min + margin,
+                bbox.x_max - margin,
+                bbox.y_max - margin,
+            )
+        };
+        let mut still_colliding = vec![];
+        for (pik, colliding) in self.collisions() {
+            if colliding.iter().any(|h| h.is_dynamic()) {
+                still_colliding.push(pik);
+                continue;
+            }
+            let pi = &self.placed_items[pik];
+            let item = instance.item(pi.item_id);
+            let original = pi.d_transf;
+            let (tx, ty) = original.translation();
+
+            //moving the bounding box back inside the container resolves most protrusions, otherwise nudge in a few directions
+            let bbox = pi.shape.bbox();
+            let clearance = tolerance * 0.1;
+            let into_range = |min: fsize, max: fsize, c_min: fsize, c_max: fsize| match (
+                min < c_min,
+                max > c_max,
+            ) {
+                (true, false) => c_min - min + clearance,
+                (false, true) => c_max - max - clearance,
+                _ => 0.0,
+            };
+            let into_container = (
+                into_range(bbox.x_min, bbox.x_max, container.0, container.2),
+                into_range(bbox.y_min, bbox.y_max, container.1, container.3),
+            );
+
+            self.remove_item(pik, true);
+            let clamped = iter::once(into_container)
+                .filter(|(dx, dy)| dx.abs() <= tolerance && dy.abs() <= tolerance)
+                .chain(CLAMP_DISTANCES.iter().flat_map(|d| {
+                    (0..N_NUDGE_DIRECTIONS).map(move |i| {
+                        let angle = 2.0 * PI * i as fsize / N_NUDGE_DIRECTIONS as fsize;
+                        (angle.cos() * d * tolerance, angle.sin() * d * tolerance)
+                    })
+                }))
+                .map(|(dx, dy)| DTransformation::new(original.rotation(), (tx + dx, ty + dy)))
+                .find(|dt| !self.collides(&PlacedItem::new(item, *dt)));
+
+            match clamped {
+                Some(dt) => {
+                    self.place_item(item, dt);
+                }
+                None => still_colliding.push(self.place_item(item, original)),
+            }
+        }
+        still_colliding
+    }
+
+    /// Hazards which the placed items collide with, other than themselves or the ones their filters ignore.
+    /// Only colliding items are included, so an empty result means the layout is feasible.
+    pub fn collisions(&self) -> Vec<(PItemKey, Vec<HazardEntity>)> {
+        self.placed_items
+            .iter()
+            .filter_map(|(pik, pi)| {
+                let mut detected = vec![];
+                self.cde.collect_poly_collisions(
+                    &pi.shape,
+                    &self.irrelevant_hazards(pi),
+                    &mut detected,
+                );
+                //copies of an item at exactly the same position share a hazard entity, and would hide each other
+                let entity = HazardEntity::from(pi);
+                let n_copies = self
+                    .placed_items
+                    .values()
+                    .filter(|other| HazardEntity::from(*other) == entity)
+                    .count();
+                if n_copies > 1 {
+                    detected.push(entity);
+                }
+                match detected.is_empty() {
+                    true => None,
+                    false => Some((pik, detected)),
+                }
+            })
+            .collect()
+    }
+
+    /// Whether a placed item collides with any hazard in the layout, other than itself or the ones its filter ignores
+    fn collides(&self, pi: &PlacedItem) -> bool {
+        self.cde
+            .poly_collides(&pi.shape, &self.irrelevant_hazards(pi))
+    }
+
+    fn irrelevant_hazards(&self, pi: &PlacedItem) -> Vec<HazardEntity> {
+        let ehf = EntityHazardFilter(vec![pi.into()]);
+        match &pi.hazard_filter {
+            None => hazard_filter::generate_irrelevant_hazards(&ehf, self.cde.all_hazards()),
+            Some(hf) => {
+                let combo_filter = CombinedHazardFilter {
+                    filters: vec![Box::new(&ehf), Box::new(hf)],
+                };
+                hazard_filter::generate_irrelevant_hazards(&combo_filter, self.cde.all_hazards())
+            }
+        }
+    }
+
     /// Returns the usage of the bin with the items placed.
     /// It is the ratio of the area of the items placed to the area of the bin.
     pub fn usage(&self) -> fsize {
@@ -134,6 +484,53 @@ impl Layout {
         item_area / bin_area
     }
 
+    /// Returns the perimeter of the union of all placed items, a measure of compactness:
+    /// at equal usage, a lower perimeter indicates a tighter, more connected packing.
+    /// Since placed items do not overlap, it is the total perimeter of the items minus twice the
+    /// length of the boundaries they share, i.e. collinear and overlapping edges of touching items.
+    pub fn used_perimeter(&self) -> fsize {
+        let total_perimeter = self
+            .placed_items
+            .values()
+            .flat_map(|pi| pi.shape.edge_iter())
+            .map(|e| e.diameter())
+            .sum::<fsize>();
+
+        total_perimeter - 2.0 * self.total_contact_length()
+    }
+
+    /// Returns the length of the boundary shared by two placed items, for example the length of a weld between them.
+    /// Multiple separate contact segments are summed, items which do not touch have a contact length of zero.
+    pub fn contact_length(&self, pk1: PItemKey, pk2: PItemKey) -> fsize {
+        let (s1, s2) = (&self.placed_items[pk1].shape, &self.placed_items[pk2].shape);
+        s1.contact_length(s2, self.contact_tolerance())
+    }
+
+    /// Returns the minimum distance between two placed items, zero if they collide or touch.
+    /// Gaps within the tolerance for coincident edges are reported as exactly zero.
+    pub fn separation_between(&self, pk1: PItemKey, pk2: PItemKey) -> fsize {
+        let (s1, s2) = (&self.placed_items[pk1].shape, &self.placed_items[pk2].shape);
+        match s1.separation_distance(s2) {
+            d if d <= self.contact_tolerance() => 0.0,
+            d => d,
+        }
+    }
+
+    /// Returns the total length of the boundaries shared by all pairs of placed items
+    pub fn total_contact_length(&self) -> fsize {
+        let tolerance = self.contact_tolerance();
+        self.placed_items
+            .values()
+            .tuple_combinations()
+            .map(|(pi1, pi2)| pi1.shape.contact_length(&pi2.shape, tolerance))
+            .sum()
+    }
+
+    /// Distance within which the edges of two items are considered coincident
+    fn contact_tolerance(&self) -> fsize {
+        self.bin.outer.diameter() * SHARED_EDGE_TOLERANCE
+    }
+
     pub fn id(&self) -> usize {
         self.id
     }
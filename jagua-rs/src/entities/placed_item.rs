@@ -3,8 +3,8 @@ use crate::entities::item::Item;
 use crate::geometry::d_transformation::DTransformation;
 use crate::geometry::geo_traits::Transformable;
 use crate::geometry::primitives::simple_polygon::SimplePolygon;
+use alloc::sync::Arc;
 use slotmap::new_key_type;
-use std::sync::Arc;
 
 new_key_type! {
     /// Unique key for each `PlacedItem` in a layout.
@@ -1,14 +1,30 @@
+use alloc::{format, string::ToString, vec, vec::Vec};
+use core::cmp::Reverse;
+use core::fmt::{Display, Formatter};
+
 use itertools::Itertools;
+use ordered_float::OrderedFloat;
 
+use crate::collision_detection::hazard::HazardEntity;
+use crate::collision_detection::hazard_filter;
 use crate::entities::instances::bin_packing::BPInstance;
-use crate::entities::instances::instance_generic::InstanceGeneric;
+use crate::entities::instances::instance_generic::{InstanceGeneric, UNLIMITED_ITEM_QTY};
+use crate::entities::item::Item;
 use crate::entities::layout::Layout;
 use crate::entities::placed_item::PItemKey;
 use crate::entities::placing_option::PlacingOption;
 use crate::entities::problems::problem_generic::private::ProblemGenericPrivate;
 use crate::entities::problems::problem_generic::{LayoutIndex, ProblemGeneric};
 use crate::entities::solution::Solution;
+use crate::fsize;
+use crate::geometry::d_transformation::DTransformation;
+use crate::geometry::geo_enums::AllowedRotation;
+use crate::geometry::geo_traits::{Transformable, TransformableFrom};
+use crate::geometry::primitives::point::Point;
+use crate::geometry::transformation::Transformation;
 use crate::util::assertions;
+use crate::util::fnv::FnvIndexSet;
+use crate::PI;
 
 /// Bin Packing Problem
 #[derive(Clone)]
@@ -59,10 +75,208 @@ impl BPProblem {
         }
     }
 
+    /// Creates a problem containing all layouts of `solution`, which may belong to another problem of the same instance,
+    /// or to one of an instance which has since been extended with [`BPProblem::add_item`].
+    pub fn from_solution(instance: BPInstance, solution: &Solution) -> Self {
+        let n_items = solution.placed_item_qtys.len();
+        let mut problem = BPProblem::new(instance);
+        assert!(
+            n_items <= problem.instance.items.len(),
+            "solution contains items which are not part of the instance"
+        );
+        //snapshots get new ids, the layouts are new to this problem
+        for ls in solution.layout_snapshots.iter() {
+            let mut ls = ls.clone();
+            ls.id = problem.next_layout_id();
+            problem.register_layout(Layout::from_snapshot(&ls));
+        }
+        problem
+    }
+
+    /// Adds a new item type to the instance, with the next available id, without modifying any of the layouts.
+    /// The item is demanded `demand` times, and is placed in the same way as all other items.
+    /// Returns the id of the item.
+    pub fn add_item(&mut self, mut item: Item, demand: usize) -> usize {
+        let id = self.instance.items.len();
+        item.id = id;
+        let mut items = self.instance.items.clone();
+        items.push((item, demand));
+        self.instance = BPInstance::new(items, self.instance.bins.clone());
+        self.missing_item_qtys.push(demand as isize);
+        id
+    }
+
     pub fn remove_layout(&mut self, layout_index: LayoutIndex) {
         self.deregister_layout(layout_index);
     }
 
+    /// Attempts to place a copy of item `item_id` with transformation `d_transf` in the layout at `layout_idx`.
+    /// Template layouts open a new bin. The item is only placed if the item and layout exist, it is still demanded,
+    /// the bin is available and the item does not collide with anything, otherwise the reason of the rejection is returned.
+    pub fn try_place_at(
+        &mut self,
+        item_id: usize,
+        layout_idx: LayoutIndex,
+        d_transf: DTransformation,
+    ) -> Result<(LayoutIndex, PItemKey), PlacementRejection> {
+        match self.missing_item_qtys.get(item_id) {
+            None => return Err(PlacementRejection::UnknownItem),
+            Some(qty) if *qty <= 0 => return Err(PlacementRejection::ItemNotDemanded),
+            Some(_) => {}
+        }
+        let layout = match layout_idx {
+            LayoutIndex::Real(i) => self
+                .layouts
+                .get(i)
+                .ok_or(PlacementRejection::UnknownLayout)?,
+            LayoutIndex::Template(i) => {
+                let template = self
+                    .template_layouts
+                    .get(i)
+                    .ok_or(PlacementRejection::UnknownLayout)?;
+                match self.bin_qtys[template.bin.id] {
+                    0 => return Err(PlacementRejection::BinUnavailable),
+                    _ => template,
+                }
+            }
+        };
+
+        let item = self.instance.item(item_id);
+        let shape = item.shape.transform_clone(&d_transf.compose());
+        let irrel_hazards = match item.hazard_filter.as_ref() {
+            None => vec![],
+            Some(hf) => hazard_filter::generate_irrelevant_hazards(hf, layout.cde().all_hazards()),
+        };
+        let mut colliding = vec![];
+        layout
+            .cde()
+            .collect_poly_collisions(&shape, &irrel_hazards, &mut colliding);
+
+        match colliding.is_empty() {
+            true => Ok(self.place_item(PlacingOption {
+                layout_idx,
+                item_id,
+                d_transf,
+            })),
+            false => {
+                let collisions = colliding
+                    .iter()
+                    .map(|haz| match haz {
+                        HazardEntity::PlacedItem { .. } => Collision::PlacedItem(
+                            layout
+                                .hazard_to_p_item_key(haz)
+                                .expect("colliding item not in layout"),
+                        ),
+                        HazardEntity::BinExterior => Collision::OutOfBounds,
+                        HazardEntity::BinHole { id } => Collision::BinHole(*id),
+                        HazardEntity::InferiorQualityZone { quality, id } => {
+                            Collision::QualityZone {
+                                quality: *quality,
+                                id: *id,
+                            }
+                        }
+                        HazardEntity::SoftZone { .. } => {
+                            unreachable!("soft zones are not considered for collisions")
+                        }
+                    })
+                    .collect_vec();
+                Err(PlacementRejection::Collides(collisions))
+            }
+        }
+    }
+
+    /// Ids of the items which are still demanded and of which a copy fits somewhere in a layout or an available bin.
+    /// Every item is probed with its pole of inaccessibility at the centers of the most spacious cells of the
+    /// hazard proximity grid, without modifying the problem.
+    /// Probing is heuristic: an item which is not returned might still fit at a position which was not probed.
+    pub fn placeable_items(&self) -> Vec<usize> {
+        let available_templates = self
+            .template_layouts
+            .iter()
+            .filter(|l| self.bin_qtys[l.bin.id] > 0);
+        let layouts = self.layouts.iter().chain(available_templates).collect_vec();
+
+        (0..self.instance.items.len())
+            .filter(|&id| self.missing_item_qtys[id] > 0)
+            .filter(|&id| {
+                let item = self.instance.item(id);
+                layouts.iter().any(|l| item_fits_in_layout(item, l))
+            })
+            .collect()
+    }
+
+    /// Extracts the remainder of the problem when the layouts in `committed` are fixed, for example because they are already produced.
+    /// The returned instance only demands the items which are not placed in the committed layouts,
+    /// and the bins used by the committed layouts are removed from its stock.
+    /// Items and bins keep their ids, so a solution to the remainder can be merged back with [`BPProblem::merge_residual_solution`].
+    pub fn residual_instance(&self, committed: &[LayoutIndex]) -> BPInstance {
+        let mut items = self.instance.items.clone();
+        let mut bins = self.instance.bins.clone();
+        for layout in self.committed_layouts(committed) {
+            bins[layout.bin.id].1 -= 1;
+            for pi in layout.placed_items().values() {
+                let qty = &mut items[pi.item_id].1;
+                if *qty != UNLIMITED_ITEM_QTY {
+                    *qty = qty.saturating_sub(1);
+                }
+            }
+        }
+        BPInstance::new(items, bins)
+    }
+
+    /// Replaces all layouts which are not `committed` with the layouts of `residual_solution`,
+    /// a solution to the [`BPProblem::residual_instance`] of the same committed layouts.
+    /// The committed layouts are kept as they are, but their indices can change.
+    /// Returns the indices of the merged layouts.
+    pub fn merge_residual_solution(
+        &mut self,
+        committed: &[LayoutIndex],
+        residual_solution: &Solution,
+    ) -> Vec<LayoutIndex> {
+        assert_eq!(
+            residual_solution.placed_item_qtys.len(),
+            self.instance.items.len(),
+            "solution does not belong to a residual instance of this problem"
+        );
+        let committed_ids = self
+            .committed_layouts(committed)
+            .map(|l| l.id())
+            .collect_vec();
+
+        //remove the uncommitted layouts, back to front to keep the indices valid
+        let uncommitted = (0..self.layouts.len())
+            .filter(|&i| !committed_ids.contains(&self.layouts[i].id()))
+            .collect_vec();
+        for i in uncommitted.into_iter().rev() {
+            self.deregister_layout(LayoutIndex::Real(i));
+        }
+
+        //add the layouts of the residual solution, under new ids to avoid clashes with those of this problem
+        residual_solution
+            .layout_snapshots
+            .iter()
+            .map(|ls| {
+                let mut ls = ls.clone();
+                ls.id = self.next_layout_id();
+                self.register_layout(Layout::from_snapshot(&ls))
+            })
+            .collect()
+    }
+
+    fn committed_layouts<'a>(
+        &'a self,
+        committed: &'a [LayoutIndex],
+    ) -> impl Iterator<Item = &'a Layout> + 'a {
+        assert!(
+            committed.iter().collect::<FnvIndexSet<_>>().len() == committed.len(),
+            "layouts can only be committed once"
+        );
+        committed.iter().map(|l_idx| match l_idx {
+            LayoutIndex::Real(i) => &self.layouts[*i],
+            LayoutIndex::Template(_) => panic!("template layouts cannot be committed"),
+        })
+    }
+
     pub fn register_layout(&mut self, layout: Layout) -> LayoutIndex {
         self.register_bin(layout.bin.id);
         layout
@@ -111,6 +325,49 @@ impl BPProblem {
     }
 }
 
+/// Maximum number of grid cells in which an item is probed, per layout
+const N_PROBE_CELLS: usize = 100;
+
+/// Number of rotations an item with continuous rotation is probed in
+const N_PROBE_ROTATIONS: usize = 8;
+
+fn item_fits_in_layout(item: &Item, layout: &Layout) -> bool {
+    //layouts with uncommitted changes to their grid cannot be probed
+    let Ok(hpg) = layout.cde().haz_prox_grid() else {
+        return false;
+    };
+    let irrel_hazards = match item.hazard_filter.as_ref() {
+        None => vec![],
+        Some(hf) => hazard_filter::generate_irrelevant_hazards(hf, layout.cde().all_hazards()),
+    };
+    let rotations = match &item.allowed_rotation {
+        AllowedRotation::None => vec![0.0],
+        AllowedRotation::Discrete(angles) => angles.clone(),
+        AllowedRotation::Continuous => (0..N_PROBE_ROTATIONS)
+            .map(|i| 2.0 * PI * i as fsize / N_PROBE_ROTATIONS as fsize)
+            .collect(),
+    };
+    let poi_center = item.shape.poi.center;
+    let mut buffer = (*item.shape).clone();
+
+    hpg.grid
+        .cells
+        .iter()
+        .flatten()
+        .filter(|c| c.could_accommodate_item(item))
+        .sorted_by_key(|c| Reverse(OrderedFloat(c.hazard_proximity(item.base_quality))))
+        .take(N_PROBE_CELLS)
+        .cartesian_product(rotations.iter())
+        .any(|(cell, &rotation)| {
+            let Point(px, py) =
+                poi_center.transform_clone(&Transformation::from_rotation(rotation));
+            let translation = (cell.centroid.0 - px, cell.centroid.1 - py);
+            let transf = DTransformation::new(rotation, translation).compose();
+            buffer.transform_from(&item.shape, &transf);
+            !layout.cde().poly_collides(&buffer, &irrel_hazards)
+        })
+}
+
 impl ProblemGeneric for BPProblem {
     fn place_item(&mut self, p_opt: PlacingOption) -> (LayoutIndex, PItemKey) {
         let layout_index = match &p_opt.layout_idx {
@@ -338,3 +595,54 @@ impl ProblemGenericPrivate for BPProblem {
         &mut self.missing_item_qtys
     }
 }
+
+/// Reason why [`BPProblem::try_place_at`] rejected a placement
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlacementRejection {
+    /// No item with this id exists in the instance
+    UnknownItem,
+    /// No layout exists at this index
+    UnknownLayout,
+    /// All demanded copies of the item are already placed
+    ItemNotDemanded,
+    /// No more stock of the template layout's bin is available
+    BinUnavailable,
+    /// The item collides with one or more hazards in the layout
+    Collides(Vec<Collision>),
+}
+
+/// Hazard with which a rejected placement collides
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Collision {
+    /// (Partially) outside the bin
+    OutOfBounds,
+    /// Overlaps with the hole of the bin with this id
+    BinHole(usize),
+    /// Overlaps with an already placed item
+    PlacedItem(PItemKey),
+    /// Overlaps with a zone of insufficient quality
+    QualityZone { quality: usize, id: usize },
+}
+
+impl Display for PlacementRejection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PlacementRejection::UnknownItem => write!(f, "item does not exist"),
+            PlacementRejection::UnknownLayout => write!(f, "layout does not exist"),
+            PlacementRejection::ItemNotDemanded => write!(f, "item is no longer demanded"),
+            PlacementRejection::BinUnavailable => write!(f, "no more stock of the bin"),
+            PlacementRejection::Collides(collisions) => {
+                write!(f, "collides with ")?;
+                let reasons = collisions.iter().map(|c| match c {
+                    Collision::OutOfBounds => "the bin exterior".to_string(),
+                    Collision::BinHole(id) => format!("hole {id} of the bin"),
+                    Collision::PlacedItem(pik) => format!("placed item {pik:?}"),
+                    Collision::QualityZone { quality, id } => {
+                        format!("zone {id} of quality {quality}")
+                    }
+                });
+                write!(f, "{}", reasons.format(", "))
+            }
+        }
+    }
+}
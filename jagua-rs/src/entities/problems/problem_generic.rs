@@ -1,4 +1,4 @@
-use std::borrow::Borrow;
+use core::borrow::Borrow;
 
 use crate::entities::instances::instance_generic::InstanceGeneric;
 use crate::entities::layout::Layout;
@@ -1,4 +1,5 @@
-use std::{iter, slice};
+use alloc::{vec, vec::Vec};
+use core::{iter, slice};
 
 use crate::collision_detection::hazard::HazardEntity;
 use crate::entities::bin::Bin;
@@ -14,6 +15,7 @@ use crate::entities::solution::Solution;
 use crate::fsize;
 use crate::geometry::geo_traits::{Shape, Transformable};
 use crate::geometry::primitives::aa_rectangle::AARectangle;
+use crate::geometry::primitives::simple_polygon::SimplePolygon;
 use crate::util::assertions;
 use crate::util::config::CDEConfig;
 use crate::util::fpa::FPA;
@@ -39,6 +41,7 @@ impl SPProblem {
             .map(|(_, qty)| *qty as isize)
             .collect_vec();
         let strip_rect = AARectangle::new(0.0, 0.0, strip_width, strip_height);
+        let cde_config = cde_config.for_container(instance.total_item_qty(), &strip_rect);
         let strip_bin = Bin::from_strip(strip_rect, cde_config);
         let layout_id_counter = 0;
         let layout = Layout::new(layout_id_counter, strip_bin);
@@ -147,7 +150,14 @@ impl SPProblem {
     pub fn fit_strip(&mut self) {
         let n_items_in_old_strip = self.layout.placed_items().len();
 
+        //the items have to remain clear of the walls of the strip by the container margin
+        let container_margin = self
+            .layout
+            .cde()
+            .config()
+            .margin_for(&HazardEntity::BinExterior);
         let fitted_width = self.occupied_width() * (1.0 + FPA::tolerance()); //add some tolerance to avoid rounding errors or false collision positives
+        let fitted_width = fitted_width + 2.0 * container_margin;
         self.modify_strip_centered(fitted_width);
 
         assert_eq!(
@@ -167,6 +177,16 @@ impl SPProblem {
         occupied_width(&self.layout)
     }
 
+    /// Returns the width the placed items would occupy if `shape` (already transformed) were placed as well.
+    /// If the shape lies within the currently occupied range, this is the unchanged [`SPProblem::occupied_width`].
+    pub fn occupied_width_with(&self, shape: &SimplePolygon) -> fsize {
+        let bbox = shape.bbox();
+        match self.occupied_range() {
+            None => bbox.width(),
+            Some((min_x, max_x)) => max_x.max(bbox.x_max) - min_x.min(bbox.x_min),
+        }
+    }
+
     pub fn strip_width(&self) -> fsize {
         self.layout.bin.outer.bbox().width()
     }
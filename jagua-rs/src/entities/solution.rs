@@ -1,10 +1,15 @@
+use alloc::{vec, vec::Vec};
+use core::cmp::Reverse;
+#[cfg(feature = "std")]
 use std::time::Instant;
 
 use itertools::Itertools;
+use ordered_float::OrderedFloat;
 
 use crate::entities::instances::instance::Instance;
 use crate::entities::instances::instance_generic::InstanceGeneric;
-use crate::entities::layout::LayoutSnapshot;
+use crate::entities::layout::{Layout, LayoutSnapshot};
+use crate::entities::placed_item::PItemKey;
 use crate::fsize;
 use crate::geometry::geo_traits::Shape;
 
@@ -24,7 +29,8 @@ pub struct Solution {
     pub target_item_qtys: Vec<usize>,
     /// Quantity of bins used for each type of bin
     pub bin_qtys: Vec<usize>,
-    /// Instant the solution was created
+    /// Instant the solution was created, only available with the feature **std**
+    #[cfg(feature = "std")]
     pub time_stamp: Instant,
 }
 
@@ -44,6 +50,7 @@ impl Solution {
             placed_item_qtys,
             target_item_qtys,
             bin_qtys,
+            #[cfg(feature = "std")]
             time_stamp: Instant::now(),
         }
     }
@@ -53,19 +60,25 @@ impl Solution {
         self.placed_item_qtys
             .iter()
             .enumerate()
-            .all(|(i, &qty)| qty >= instance.item_qty(i))
+            .all(|(i, &qty)| instance.item_qty_unlimited(i) || qty >= instance.item_qty(i))
     }
 
-    /// Ratio of included item area vs total demanded item area in the instance
+    /// Ratio of included item area vs total demanded item area in the instance.
+    /// Items with an unlimited demand are not considered, if all items have an unlimited demand the solution is complete (1.0).
     pub fn completeness(&self, instance: &Instance) -> fsize {
         let total_item_area = instance.item_area();
         let included_item_area = self
             .placed_item_qtys
             .iter()
             .enumerate()
+            .filter(|(i, _)| !instance.item_qty_unlimited(*i))
             .map(|(i, qty)| instance.item(i).shape.area() * *qty as fsize)
             .sum::<fsize>();
-        included_item_area / total_item_area
+        match total_item_area == 0.0 {
+            //nothing is demanded, consistent with `is_complete`
+            true => 1.0,
+            false => included_item_area / total_item_area,
+        }
     }
 
     /// Returns the quantities of the items that still need to be placed to reach a complete solution.
@@ -78,7 +91,74 @@ impl Solution {
             .collect_vec()
     }
 
+    /// Rotates every layout of the solution as a whole by `angle` radians, see [`Layout::rotate`].
+    /// Returns whether all layouts are still collision-free.
+    /// For strip packing, rotations other than multiples of 180° change the dimensions of the strip,
+    /// the resulting solution no longer matches the strip height of the instance.
+    pub fn rotate(&mut self, angle: fsize) -> bool {
+        let mut collision_free = true;
+        for ls in self.layout_snapshots.iter_mut() {
+            let mut layout = Layout::from_snapshot(ls);
+            collision_free &= layout.rotate(angle);
+            *ls = layout.create_snapshot();
+        }
+        collision_free
+    }
+
+    /// Snaps the rotation of every placed item to the nearest multiple of `increment` radians, see [`Layout::snap_rotations`].
+    /// Returns the items which kept their original rotation, as (index of the layout snapshot, key of the item).
+    pub fn snap_rotations(
+        &mut self,
+        instance: &dyn InstanceGeneric,
+        increment: fsize,
+    ) -> Vec<(usize, PItemKey)> {
+        let mut unsnapped = vec![];
+        for (i, ls) in self.layout_snapshots.iter_mut().enumerate() {
+            let mut layout = Layout::from_snapshot(ls);
+            let keys = layout.snap_rotations(instance, increment);
+            unsnapped.extend(keys.into_iter().map(|k| (i, k)));
+            *ls = layout.create_snapshot();
+        }
+        unsnapped
+    }
+
+    /// Nudges items which protrude out of their container by floating point noise back inside, see [`Layout::clamp_to_container`].
+    /// Returns the items which still collide, as (index of the layout snapshot, key of the item).
+    pub fn clamp_to_container(&mut self, instance: &dyn InstanceGeneric) -> Vec<(usize, PItemKey)> {
+        let mut still_colliding = vec![];
+        for (i, ls) in self.layout_snapshots.iter_mut().enumerate() {
+            let mut layout = Layout::from_snapshot(ls);
+            let keys = layout.clamp_to_container(instance);
+            still_colliding.extend(keys.into_iter().map(|k| (i, k)));
+            *ls = layout.create_snapshot();
+        }
+        still_colliding
+    }
+
+    /// Sum of the values of all placed items
+    pub fn placed_value(&self, instance: &dyn InstanceGeneric) -> u64 {
+        self.placed_item_qtys
+            .iter()
+            .enumerate()
+            .map(|(i, &qty)| instance.item(i).value * qty as u64)
+            .sum()
+    }
+
     pub fn n_items_placed(&self) -> usize {
         self.placed_item_qtys.iter().sum()
     }
+
+    /// Sorts the layouts by descending usage, ties are broken by their content (the sorted ids of the placed items) and then by bin id.
+    /// Solutions with the same layouts are numbered the same, regardless of the order in which the layouts were created.
+    pub fn sort_layouts(&mut self) {
+        self.layout_snapshots.sort_by_cached_key(|ls| {
+            let item_ids = ls
+                .placed_items
+                .values()
+                .map(|pi| pi.item_id)
+                .sorted()
+                .collect_vec();
+            (Reverse(OrderedFloat(ls.usage)), item_ids, ls.bin.id)
+        });
+    }
 }
@@ -1,3 +1,5 @@
+use alloc::{vec, vec::Vec};
+
 use crate::fsize;
 use crate::geometry::primitives::point::Point;
 use crate::geometry::primitives::simple_polygon::SimplePolygon;
@@ -1,5 +1,5 @@
-use std::borrow::Borrow;
-use std::fmt::Display;
+use core::borrow::Borrow;
+use core::fmt::Display;
 
 use ordered_float::NotNan;
 
@@ -57,7 +57,7 @@ where
 }
 
 impl Display for DTransformation {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "r: {:.3}°, t: ({:.3}, {:.3})",
@@ -1,5 +1,9 @@
+use alloc::{vec, vec::Vec};
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
 use itertools::{izip, Itertools};
-use ndarray::Array;
 use ordered_float::NotNan;
 use rand_distr::num_traits::FloatConst;
 
@@ -100,7 +104,7 @@ fn generate_ray_transformations(
         .map(|x| Transformation::from_translation((x, 0.0)))
         .collect_vec();
 
-    let angles = Array::linspace(0.0, fsize::PI(), n_angles + 1).to_vec();
+    let angles = linspace(0.0, fsize::PI(), n_angles + 1);
     let angles_slice = &angles[0..n_angles]; //skip the last angle, which is the same as the first
 
     //rotate the translations by each angle
@@ -147,14 +151,23 @@ fn clip(shape: &SimplePolygon, ray: &Edge) -> Vec<Edge> {
     clipped_lines
 }
 
+/// `n` evenly spaced values from `start` to `end` (inclusive)
+fn linspace(start: fsize, end: fsize, n: usize) -> Vec<fsize> {
+    let step = match n > 1 {
+        true => (end - start) / (n - 1) as fsize,
+        false => 0.0,
+    };
+    (0..n).map(|i| start + step * i as fsize).collect()
+}
+
 fn generate_unrepresented_point_grid(
     bbox: &AARectangle,
     shape: &SimplePolygon,
     poles: &[Circle],
     n_points_per_dimension: usize,
 ) -> Vec<Point> {
-    let x_range = Array::linspace(bbox.x_min, bbox.x_max, n_points_per_dimension);
-    let y_range = Array::linspace(bbox.y_min, bbox.y_max, n_points_per_dimension);
+    let x_range = linspace(bbox.x_min, bbox.x_max, n_points_per_dimension);
+    let y_range = linspace(bbox.y_min, bbox.y_max, n_points_per_dimension);
 
     x_range
         .iter()
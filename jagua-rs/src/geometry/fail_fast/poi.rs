@@ -1,5 +1,8 @@
-use std::collections::VecDeque;
+use alloc::collections::VecDeque;
+use alloc::{vec, vec::Vec};
 
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 use ordered_float::NotNan;
 
 use crate::fsize;
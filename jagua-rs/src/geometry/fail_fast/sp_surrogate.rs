@@ -1,3 +1,7 @@
+use alloc::{vec, vec::Vec};
+
+use serde::{Deserialize, Serialize};
+
 use crate::fsize;
 use crate::geometry::convex_hull;
 use crate::geometry::fail_fast::{piers, poi};
@@ -8,10 +12,10 @@ use crate::geometry::primitives::simple_polygon::SimplePolygon;
 use crate::geometry::transformation::Transformation;
 use crate::util::config::SPSurrogateConfig;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 /// Surrogate representation of a [SimplePolygon] for fail-fast purposes
 pub struct SPSurrogate {
-    /// Indices of the points in the [SimplePolygon] that form the convex hull
+    /// Indices of the points in the [SimplePolygon] that form the convex hull (all points for [SPSurrogate::bbox_only])
     pub convex_hull_indices: Vec<usize>,
     /// Set of poles
     pub poles: Vec<Circle>,
@@ -22,11 +26,15 @@ pub struct SPSurrogate {
     /// Number of poles that will be checked during fail-fast
     pub n_ff_poles: usize,
 
+    /// Area of the convex hull (of the bounding box for [SPSurrogate::bbox_only])
     pub convex_hull_area: fsize,
 }
 
 impl SPSurrogate {
     pub fn new(simple_poly: &SimplePolygon, config: SPSurrogateConfig) -> Self {
+        if config == SPSurrogateConfig::none() {
+            return Self::bbox_only(simple_poly);
+        }
         let convex_hull_indices = convex_hull::convex_hull_indices(simple_poly);
         let convex_hull_area = SimplePolygon::new(
             convex_hull_indices
@@ -57,6 +65,20 @@ impl SPSurrogate {
         }
     }
 
+    /// Surrogate without fail-fast poles or piers, the shape is only tested exactly (after its bounding box).
+    /// Skips the generation of the convex hull and additional poles, only the pole of inaccessibility of the shape is kept for the hazard proximity grid.
+    pub fn bbox_only(simple_poly: &SimplePolygon) -> Self {
+        let poi = simple_poly.poi.clone();
+        Self {
+            convex_hull_indices: (0..simple_poly.number_of_points()).collect(),
+            poles: vec![poi.clone()],
+            poles_bounding_circle: poi,
+            piers: vec![],
+            n_ff_poles: 0,
+            convex_hull_area: simple_poly.bbox().area(),
+        }
+    }
+
     pub fn ff_poles(&self) -> &[Circle] {
         &self.poles[0..self.n_ff_poles]
     }
@@ -1,4 +1,10 @@
+use alloc::vec::Vec;
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
 use crate::fsize;
+use crate::util::fpa::FPA;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum GeoPosition {
@@ -6,6 +12,58 @@ pub enum GeoPosition {
     Interior,
 }
 
+/// Position of something relative to the border of a shape, as reported by distance queries
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BorderPosition {
+    Exterior,
+    Interior,
+    /// On the border, within [`FPA`] tolerance relative to the size of the shape
+    Boundary,
+}
+
+impl BorderPosition {
+    /// Position of something at squared distance `sq_distance` from the border of a shape with diameter `diameter`,
+    /// [`BorderPosition::Boundary`] if the distance is zero within [`FPA`] tolerance, scaled by the diameter
+    pub fn from_sq_border_distance(inside: bool, sq_distance: fsize, diameter: fsize) -> Self {
+        let tolerance = FPA::tolerance() * diameter;
+        match (sq_distance <= tolerance.powi(2), inside) {
+            (true, _) => BorderPosition::Boundary,
+            (false, true) => BorderPosition::Interior,
+            (false, false) => BorderPosition::Exterior,
+        }
+    }
+
+    /// Whether this lies in the region of `position`, the boundary belongs to both the interior and the exterior
+    pub fn is_in(self, position: GeoPosition) -> bool {
+        matches!(
+            (self, position),
+            (BorderPosition::Boundary, _)
+                | (BorderPosition::Interior, GeoPosition::Interior)
+                | (BorderPosition::Exterior, GeoPosition::Exterior)
+        )
+    }
+}
+
+/// Outcome of a query which cannot always be decided
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Tribool {
+    True,
+    False,
+    Indeterminate,
+}
+
+impl TryFrom<Tribool> for bool {
+    type Error = ();
+
+    fn try_from(value: Tribool) -> Result<Self, Self::Error> {
+        match value {
+            Tribool::True => Ok(true),
+            Tribool::False => Ok(false),
+            Tribool::Indeterminate => Err(()),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 /// Possible relations between two geometric entities A and B.
 /// A is `GeoRelation` to B
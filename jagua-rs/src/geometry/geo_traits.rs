@@ -1,5 +1,5 @@
 use crate::fsize;
-use crate::geometry::geo_enums::GeoPosition;
+use crate::geometry::geo_enums::BorderPosition;
 use crate::geometry::primitives::aa_rectangle::AARectangle;
 use crate::geometry::primitives::point::Point;
 use crate::geometry::transformation::Transformation;
@@ -16,6 +16,12 @@ pub trait AlmostCollidesWith<T> {
     fn almost_collides_with(&self, other: &T) -> bool;
 }
 
+/// Trait for geometric primitives that can be compared while allowing for floating point imprecision.
+/// All coordinates are compared using [FPA](crate::util::fpa::FPA).
+pub trait ApproxEq {
+    fn approx_eq(&self, other: &Self) -> bool;
+}
+
 /// Trait for geometric primitives that can calculate distances to other primitives.
 pub trait DistanceFrom<T> {
     /// Squared distance between the two primitives.
@@ -25,10 +31,10 @@ pub trait DistanceFrom<T> {
     fn distance(&self, other: &T) -> fsize;
 
     /// Distance from `other` to the boundary of `self` and whether `other` is in the interior or exterior of self.
-    fn distance_from_border(&self, other: &T) -> (GeoPosition, fsize);
+    fn distance_from_border(&self, other: &T) -> (BorderPosition, fsize);
 
     /// Squared distance from `other` to the boundary of `self` and whether `other` is in the interior or exterior of self.
-    fn sq_distance_from_border(&self, other: &T) -> (GeoPosition, fsize);
+    fn sq_distance_from_border(&self, other: &T) -> (BorderPosition, fsize);
 }
 
 /// Trait for types that can be transformed by a Transformation.
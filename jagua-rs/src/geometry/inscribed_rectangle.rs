@@ -0,0 +1,178 @@
+use alloc::{vec, vec::Vec};
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use itertools::Itertools;
+
+use crate::fsize;
+use crate::geometry::geo_traits::{CollidesWith, Shape};
+use crate::geometry::primitives::aa_rectangle::AARectangle;
+use crate::geometry::primitives::point::Point;
+use crate::geometry::primitives::simple_polygon::SimplePolygon;
+
+/// Number of bisection steps used to expand each side of the rectangle
+const N_EXPANSION_STEPS: usize = 20;
+
+/// Margin, as a fraction of the largest dimension of the polygon, within which the rectangle may touch edges
+const TOUCH_MARGIN: fsize = 1e-5;
+
+/// Approximates the largest axis-aligned rectangle which fits inside `outer` without overlapping any of the `holes`.
+/// To find the largest free rectangle in a layout, pass the bin as `outer` and its holes and placed items as `holes`.
+///
+/// The bounding box of `outer` is divided into a `resolution` x `resolution` grid.
+/// The largest rectangle consisting only of free cells is found exactly, after which each of its sides
+/// is pushed outwards as far as possible.
+/// Higher resolutions give better results, at a quadratic cost.
+/// Returns `None` if no cell of the grid is entirely free.
+pub fn largest_inscribed_rectangle(
+    outer: &SimplePolygon,
+    holes: &[&SimplePolygon],
+    resolution: usize,
+) -> Option<AARectangle> {
+    assert!(resolution > 0, "resolution must be positive");
+    let bbox = outer.bbox();
+    let edges = outer
+        .edge_iter()
+        .chain(holes.iter().flat_map(|h| h.edge_iter()))
+        .collect_vec();
+
+    let (cell_w, cell_h) = (
+        bbox.width() / resolution as fsize,
+        bbox.height() / resolution as fsize,
+    );
+    let cell = |col: usize, row: usize| {
+        AARectangle::new(
+            bbox.x_min + col as fsize * cell_w,
+            bbox.y_min + row as fsize * cell_h,
+            bbox.x_min + (col + 1) as fsize * cell_w,
+            bbox.y_min + (row + 1) as fsize * cell_h,
+        )
+    };
+
+    //cells intersected by an edge are blocked, only the cells within the bounding box of the edge have to be checked
+    let mut free = vec![vec![true; resolution]; resolution];
+    let to_index = |v: fsize, min: fsize, size: fsize| {
+        (((v - min) / size).floor().max(0.0) as usize).min(resolution - 1)
+    };
+    for edge in &edges {
+        let (c_min, c_max) = (
+            to_index(edge.x_min(), bbox.x_min, cell_w),
+            to_index(edge.x_max(), bbox.x_min, cell_w),
+        );
+        let (r_min, r_max) = (
+            to_index(edge.y_min(), bbox.y_min, cell_h),
+            to_index(edge.y_max(), bbox.y_min, cell_h),
+        );
+        for (row, free_row) in free.iter_mut().enumerate().take(r_max + 1).skip(r_min) {
+            for (col, is_free) in free_row.iter_mut().enumerate().take(c_max + 1).skip(c_min) {
+                if *is_free && cell(col, row).collides_with(edge) {
+                    *is_free = false;
+                }
+            }
+        }
+    }
+
+    //the remaining cells are either entirely inside or outside the free region, their centroid decides
+    for (row, free_row) in free.iter_mut().enumerate() {
+        for (col, is_free) in free_row.iter_mut().enumerate() {
+            if *is_free {
+                *is_free = point_is_free(&cell(col, row).centroid(), outer, holes);
+            }
+        }
+    }
+
+    let (col_min, row_min, col_max, row_max) = largest_free_block(&free)?;
+    let mut rect = AARectangle::new(
+        cell(col_min, row_min).x_min,
+        cell(col_min, row_min).y_min,
+        cell(col_max, row_max).x_max,
+        cell(col_max, row_max).y_max,
+    );
+
+    //push every side outwards, the largest feasible distance is found by bisection.
+    //the rectangle is allowed to touch the edges, so it is shrunk by a small margin before testing
+    let margin = fsize::max(bbox.width(), bbox.height()) * TOUCH_MARGIN;
+    let shrink = |r: &AARectangle| AARectangle {
+        x_min: r.x_min + margin,
+        y_min: r.y_min + margin,
+        x_max: r.x_max - margin,
+        y_max: r.y_max - margin,
+    };
+    let fits = |r: &AARectangle| {
+        let shrunk = shrink(r);
+        !edges.iter().any(|e| shrunk.collides_with(e))
+            && point_is_free(&shrunk.centroid(), outer, holes)
+    };
+    for side in 0..4 {
+        let max_distance = match side {
+            0 => rect.x_min - bbox.x_min,
+            1 => bbox.x_max - rect.x_max,
+            2 => rect.y_min - bbox.y_min,
+            _ => bbox.y_max - rect.y_max,
+        };
+        let expand = |d: fsize| {
+            let mut r = rect.clone();
+            match side {
+                0 => r.x_min -= d,
+                1 => r.x_max += d,
+                2 => r.y_min -= d,
+                _ => r.y_max += d,
+            }
+            r
+        };
+        let (mut feasible, mut infeasible) = (0.0, max_distance);
+        for _ in 0..N_EXPANSION_STEPS {
+            let d = (feasible + infeasible) / 2.0;
+            match fits(&expand(d)) {
+                true => feasible = d,
+                false => infeasible = d,
+            }
+        }
+        rect = expand(feasible);
+    }
+
+    Some(shrink(&rect))
+}
+
+fn point_is_free(point: &Point, outer: &SimplePolygon, holes: &[&SimplePolygon]) -> bool {
+    outer.collides_with(point) && !holes.iter().any(|h| h.collides_with(point))
+}
+
+/// Largest rectangular block of free cells, as (col_min, row_min, col_max, row_max).
+/// Uses the maximal rectangle in a histogram algorithm for every row, in O(n²).
+fn largest_free_block(free: &[Vec<bool>]) -> Option<(usize, usize, usize, usize)> {
+    let n_cols = free.first()?.len();
+    let mut heights = vec![0; n_cols];
+    let mut best: Option<(usize, (usize, usize, usize, usize))> = None;
+
+    for (row, free_row) in free.iter().enumerate() {
+        //number of consecutive free cells ending in this row, for every column
+        for (h, &is_free) in heights.iter_mut().zip(free_row) {
+            *h = match is_free {
+                true => *h + 1,
+                false => 0,
+            };
+        }
+
+        //stack of column indices with increasing heights
+        let mut stack: Vec<usize> = vec![];
+        for col in 0..=n_cols {
+            let h = heights.get(col).copied().unwrap_or(0);
+            while let Some(&top) = stack.last() {
+                if heights[top] < h {
+                    break;
+                }
+                stack.pop();
+                let height = heights[top];
+                let start = stack.last().map_or(0, |&s| s + 1);
+                let area = height * (col - start);
+                if area > 0 && best.is_none_or(|(best_area, _)| area > best_area) {
+                    best = Some((area, (start, row + 1 - height, col - 1, row)));
+                }
+            }
+            stack.push(col);
+        }
+    }
+    best.map(|(_, block)| block)
+}
@@ -0,0 +1,56 @@
+use alloc::vec::Vec;
+
+use ordered_float::OrderedFloat;
+
+use crate::geometry::primitives::point::Point;
+use crate::geometry::primitives::simple_polygon::SimplePolygon;
+
+/// Computes the [Minkowski sum](https://en.wikipedia.org/wiki/Minkowski_addition) of two convex polygons,
+/// by merging their edges in order of polar angle, in O(n + m).
+/// Both inputs have to be convex, non-convex shapes should first be split with [`convex_decompose`](crate::geometry::shape_modification::convex_decompose),
+/// after which the union of the pairwise sums forms the sum of the original shapes.
+/// <br>
+/// The no-fit polygon of `b` around `a` is the Minkowski sum of `a` and `b` mirrored around its reference point, see [`no_fit_polygon`](crate::geometry::nfp::no_fit_polygon).
+pub fn minkowski_sum(a: &SimplePolygon, b: &SimplePolygon) -> SimplePolygon {
+    //both polygons are counterclockwise, starting from their bottom-left vertex their edges are sorted by polar angle
+    let (pa, pb) = (start_at_bottom(&a.points), start_at_bottom(&b.points));
+    let (n, m) = (pa.len(), pb.len());
+    let edge = |points: &[Point], i: usize| {
+        let (Point(x1, y1), Point(x2, y2)) =
+            (points[i % points.len()], points[(i + 1) % points.len()]);
+        (x2 - x1, y2 - y1)
+    };
+
+    let mut points = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        let (Point(xa, ya), Point(xb, yb)) = (pa[i % n], pb[j % m]);
+        points.push(Point(xa + xb, ya + yb));
+
+        //advance along the edge with the smallest polar angle, or along both if they are parallel
+        let ((ax, ay), (bx, by)) = (edge(&pa, i), edge(&pb, j));
+        let cross = ax * by - ay * bx;
+        if j >= m || (i < n && cross > 0.0) {
+            i += 1;
+        } else if i >= n || cross < 0.0 {
+            j += 1;
+        } else {
+            i += 1;
+            j += 1;
+        }
+    }
+
+    SimplePolygon::new(points)
+}
+
+/// Rotates the points such that the lowest (and leftmost, in case of ties) point is first
+fn start_at_bottom(points: &[Point]) -> Vec<Point> {
+    let start = (0..points.len())
+        .min_by_key(|&i| (OrderedFloat(points[i].1), OrderedFloat(points[i].0)))
+        .unwrap();
+    points[start..]
+        .iter()
+        .chain(&points[..start])
+        .copied()
+        .collect()
+}
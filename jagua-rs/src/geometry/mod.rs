@@ -4,5 +4,11 @@ pub mod d_transformation;
 pub mod fail_fast;
 pub mod geo_enums;
 pub mod geo_traits;
+pub mod inscribed_rectangle;
+pub mod minkowski;
+pub mod nfp;
+pub mod offset;
 pub mod primitives;
+pub mod shape_modification;
+pub mod simplicity;
 pub mod transformation;
@@ -0,0 +1,82 @@
+use alloc::vec::Vec;
+
+use itertools::Itertools;
+
+use crate::fsize;
+use crate::geometry::minkowski::minkowski_sum;
+use crate::geometry::primitives::point::Point;
+use crate::geometry::primitives::simple_polygon::SimplePolygon;
+use crate::geometry::shape_modification::convex_decompose;
+
+/// The no-fit polygon (NFP) of an orbiting polygon around a stationary one:
+/// the region of positions of the orbiting polygon's reference point (its origin) at which both polygons overlap.
+/// Positions on the boundary of the NFP are touching placements, positions outside of it are free of overlap.
+#[derive(Clone, Debug)]
+pub enum NoFitPolygon {
+    /// Both polygons are convex, the NFP is a single convex polygon
+    Convex(SimplePolygon),
+    /// The NFP is the union of these (possibly overlapping) convex polygons, one for every pair of convex parts of both polygons.
+    /// Holes and zero-width slits in the union, positions in which the orbiting polygon fits (exactly), are covered by none of the parts
+    Union(Vec<SimplePolygon>),
+}
+
+impl NoFitPolygon {
+    /// The convex polygons whose union forms the NFP
+    pub fn parts(&self) -> &[SimplePolygon] {
+        match self {
+            NoFitPolygon::Convex(part) => core::slice::from_ref(part),
+            NoFitPolygon::Union(parts) => parts,
+        }
+    }
+}
+
+/// Computes the no-fit polygon of `orbiting` around `stationary`, both in their own (untransformed) coordinates.
+/// It is the Minkowski sum of `stationary` and `orbiting` mirrored around its origin.
+/// Two convex polygons are summed directly, otherwise both are first split with [`convex_decompose`].
+/// Collinear edges of the sums are merged into a single edge.
+pub fn no_fit_polygon(stationary: &SimplePolygon, orbiting: &SimplePolygon) -> NoFitPolygon {
+    let mirrored = SimplePolygon::new(
+        orbiting
+            .points
+            .iter()
+            .map(|&Point(x, y)| Point(-x, -y))
+            .collect(),
+    );
+    let stationary_parts = convex_decompose(stationary);
+    let orbiting_parts = convex_decompose(&mirrored);
+
+    let sum = |a: &SimplePolygon, b: &SimplePolygon| merge_collinear(minkowski_sum(a, b));
+    match (stationary_parts.as_slice(), orbiting_parts.as_slice()) {
+        ([s], [o]) => NoFitPolygon::Convex(sum(s, o)),
+        _ => NoFitPolygon::Union(
+            stationary_parts
+                .iter()
+                .cartesian_product(orbiting_parts.iter())
+                .map(|(s, o)| sum(s, o))
+                .collect(),
+        ),
+    }
+}
+
+/// Removes all vertices of a convex polygon at which both adjacent edges are (nearly) collinear
+fn merge_collinear(polygon: SimplePolygon) -> SimplePolygon {
+    let points = &polygon.points;
+    let n = points.len();
+    let is_collinear = |i: usize| {
+        let (Point(x0, y0), Point(x1, y1), Point(x2, y2)) =
+            (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+        let (d1, d2) = ((x1 - x0, y1 - y0), (x2 - x1, y2 - y1));
+        let cross = d1.0 * d2.1 - d1.1 * d2.0;
+        cross.abs() <= fsize::EPSILON * (d1.0.abs() + d1.1.abs()) * (d2.0.abs() + d2.1.abs())
+    };
+    let collinear = (0..n).filter(|&i| is_collinear(i)).collect_vec();
+    match collinear.is_empty() {
+        true => polygon,
+        false => SimplePolygon::new(
+            (0..n)
+                .filter(|i| !collinear.contains(i))
+                .map(|i| points[i])
+                .collect(),
+        ),
+    }
+}
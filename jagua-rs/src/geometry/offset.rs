@@ -0,0 +1,122 @@
+use alloc::{vec, vec::Vec};
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use itertools::Itertools;
+
+use crate::geometry::primitives::point::Point;
+use crate::geometry::primitives::simple_polygon::SimplePolygon;
+use crate::geometry::simplicity::is_simple;
+use crate::util::fnv::FnvIndexSet;
+use crate::{fsize, PI};
+
+/// How the offset edges are joined at the vertices where they move apart
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OffsetJoin {
+    /// Extend both edges until they intersect
+    Miter,
+    /// Connect both edges with a circular arc around the original vertex.
+    /// `n_segments` is the number of segments used to approximate a full circle, arcs get a proportional number
+    Round { n_segments: usize },
+}
+
+/// Offsets the polygon by `distance`: positive grows the polygon, negative shrinks it.
+/// At the vertices where the offset edges move apart (convex vertices when growing, reflex vertices when shrinking), they are joined with `join`.
+/// Returns `None` if the result is not a simple polygon: when an edge collapses, the polygon splits
+/// (shrinking past its medial axis) or parts of it grow into each other. These cases are not resolved.
+pub fn offset_polygon(
+    polygon: &SimplePolygon,
+    distance: fsize,
+    join: OffsetJoin,
+) -> Option<SimplePolygon> {
+    let distances = vec![distance; polygon.number_of_points()];
+    offset(polygon, &distances, join)
+}
+
+/// Offsets every edge of the polygon (edge `i` runs from point `i` to `i+1`) by its own distance, positive outwards, and miters them
+pub fn offset_edges(polygon: &SimplePolygon, distances: &[fsize]) -> Option<SimplePolygon> {
+    assert_eq!(distances.len(), polygon.number_of_points());
+    offset(polygon, distances, OffsetJoin::Miter)
+}
+
+fn offset(polygon: &SimplePolygon, distances: &[fsize], join: OffsetJoin) -> Option<SimplePolygon> {
+    let n = polygon.number_of_points();
+    //offset start point and direction of every edge
+    let offset_edges = polygon
+        .edge_iter()
+        .zip(distances)
+        .map(|(e, d)| {
+            let (dx, dy) = (e.end.0 - e.start.0, e.end.1 - e.start.1);
+            let length = (dx * dx + dy * dy).sqrt();
+            //outward normal of a counterclockwise polygon
+            let (nx, ny) = (dy / length * d, -dx / length * d);
+            (Point(e.start.0 + nx, e.start.1 + ny), (dx, dy))
+        })
+        .collect_vec();
+
+    //points joining the previous edge to edge `j`
+    let joins = (0..n)
+        .map(|j| {
+            let (Point(x1, y1), (dx1, dy1)) = offset_edges[(j + n - 1) % n];
+            let (Point(x2, y2), (dx2, dy2)) = offset_edges[j];
+            let cross = dx1 * dy2 - dy1 * dx2;
+            let parallel =
+                cross.abs() <= fsize::EPSILON * (dx1.abs() + dy1.abs()) * (dx2.abs() + dy2.abs());
+            //intersection of both offset edges
+            let miter = match parallel {
+                //the offset start of the second one is an intersection
+                true => Point(x2, y2),
+                false => {
+                    let t = ((x2 - x1) * dy2 - (y2 - y1) * dx2) / cross;
+                    Point(x1 + t * dx1, y1 + t * dy1)
+                }
+            };
+            match join {
+                OffsetJoin::Round { n_segments } if !parallel && cross * distances[j] > 0.0 => {
+                    let end_of_prev = Point(x1 + dx1, y1 + dy1);
+                    arc(polygon.get_point(j), end_of_prev, Point(x2, y2), n_segments)
+                }
+                _ => vec![miter],
+            }
+        })
+        .collect_vec();
+
+    //every offset edge has to keep its direction, otherwise it collapsed
+    let edges_preserved = (0..n).all(|i| {
+        let (start, end) = (*joins[i].last().unwrap(), joins[(i + 1) % n][0]);
+        let (_, (dx, dy)) = offset_edges[i];
+        (end.0 - start.0) * dx + (end.1 - start.1) * dy > 0.0
+    });
+    let points = joins.into_iter().flatten().collect_vec();
+    match edges_preserved
+        && points.iter().collect::<FnvIndexSet<_>>().len() == points.len()
+        && SimplePolygon::calculate_area(&points) > 0.0
+        && is_simple(&points)
+    {
+        true => Some(SimplePolygon::new(points)),
+        false => None,
+    }
+}
+
+/// Points on the shortest circular arc around `center` from `start` to `end`, both included
+fn arc(center: Point, start: Point, end: Point, n_segments: usize) -> Vec<Point> {
+    let (Point(cx, cy), Point(sx, sy), Point(ex, ey)) = (center, start, end);
+    let (a, b) = ((sx - cx, sy - cy), (ex - cx, ey - cy));
+    let radius = (a.0 * a.0 + a.1 * a.1).sqrt();
+    let (start_angle, sweep) = (
+        a.1.atan2(a.0),
+        (a.0 * b.1 - a.1 * b.0).atan2(a.0 * b.0 + a.1 * b.1),
+    );
+    let n_steps = ((sweep.abs() / (2.0 * PI) * n_segments as fsize).ceil() as usize).max(1);
+    (0..=n_steps)
+        .map(|k| match k {
+            0 => start,
+            k if k == n_steps => end,
+            k => {
+                let angle = start_angle + sweep * k as fsize / n_steps as fsize;
+                Point(cx + radius * angle.cos(), cy + radius * angle.sin())
+            }
+        })
+        .collect()
+}
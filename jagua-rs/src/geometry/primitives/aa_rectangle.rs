@@ -1,11 +1,17 @@
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
 use crate::fsize;
-use crate::geometry::geo_enums::{GeoPosition, GeoRelation};
-use crate::geometry::geo_traits::{AlmostCollidesWith, CollidesWith, DistanceFrom, Shape};
+use crate::geometry::geo_enums::{BorderPosition, GeoRelation};
+use crate::geometry::geo_traits::{
+    AlmostCollidesWith, ApproxEq, CollidesWith, DistanceFrom, Shape,
+};
 use crate::geometry::primitives::edge::Edge;
 use crate::geometry::primitives::point::Point;
 use crate::util::fpa::FPA;
+use crate::PI;
+use core::cmp::Ordering;
 use ordered_float::{NotNan, OrderedFloat};
-use std::cmp::Ordering;
 
 ///Geometric primitive representing an axis-aligned rectangle
 #[derive(Clone, Debug, PartialEq)]
@@ -34,6 +40,25 @@ impl AARectangle {
         }
     }
 
+    /// Smallest rectangle containing all `points`, `None` if there are none.
+    /// The rectangle is degenerate (zero width or height) if all points share the same x or y coordinate.
+    pub fn from_points(points: &[Point]) -> Option<Self> {
+        let (first, rest) = points.split_first()?;
+        let mut bbox = AARectangle {
+            x_min: first.0,
+            y_min: first.1,
+            x_max: first.0,
+            y_max: first.1,
+        };
+        for &Point(x, y) in rest {
+            bbox.x_min = bbox.x_min.min(x);
+            bbox.y_min = bbox.y_min.min(y);
+            bbox.x_max = bbox.x_max.max(x);
+            bbox.y_max = bbox.y_max.max(y);
+        }
+        Some(bbox)
+    }
+
     /// Returns the relation between self and another AARectangle
     pub fn relation_to(&self, other: &AARectangle) -> GeoRelation {
         if self.collides_with(other) {
@@ -100,6 +125,16 @@ impl AARectangle {
         )
     }
 
+    /// Returns the rectangle grown by `d` on every side
+    pub fn inflate(&self, d: fsize) -> AARectangle {
+        AARectangle::new(
+            self.x_min - d,
+            self.y_min - d,
+            self.x_max + d,
+            self.y_max + d,
+        )
+    }
+
     pub fn scale(mut self, factor: fsize) -> Self {
         let dx = (self.x_max - self.x_min) * (factor - 1.0) / 2.0;
         let dy = (self.y_max - self.y_min) * (factor - 1.0) / 2.0;
@@ -158,6 +193,29 @@ impl AARectangle {
         self.y_max - self.y_min
     }
 
+    /// Returns the tight bounding box of the rectangle after rotating it by `angle` (in radians) around the origin.
+    /// Multiples of 90° are detected and handled exactly, by swapping and mirroring the bounds.
+    pub fn rotated_bbox(&self, angle: fsize) -> AARectangle {
+        let quarter_turns = (angle % (2.0 * PI) + 2.0 * PI) % (2.0 * PI) / (PI / 2.0);
+        let rounded = quarter_turns.round();
+        let (x_min, y_min, x_max, y_max) = (self.x_min, self.y_min, self.x_max, self.y_max);
+        match (quarter_turns - rounded).abs() <= 8.0 * fsize::EPSILON {
+            true => match rounded as usize % 4 {
+                0 => self.clone(),
+                1 => AARectangle::new(-y_max, x_min, -y_min, x_max),
+                2 => AARectangle::new(-x_max, -y_max, -x_min, -y_min),
+                _ => AARectangle::new(y_min, -x_max, y_max, -x_min),
+            },
+            false => {
+                let (sin, cos) = angle.sin_cos();
+                let rotated = self
+                    .corners()
+                    .map(|Point(x, y)| Point(x * cos - y * sin, x * sin + y * cos));
+                AARectangle::from_points(&rotated).expect("a rectangle has four corners")
+            }
+        }
+    }
+
     pub fn from_intersection(a: &AARectangle, b: &AARectangle) -> Option<AARectangle> {
         let x_min = fsize::max(a.x_min, b.x_min);
         let y_min = fsize::max(a.y_min, b.y_min);
@@ -202,6 +260,15 @@ impl Shape for AARectangle {
     }
 }
 
+impl ApproxEq for AARectangle {
+    fn approx_eq(&self, other: &Self) -> bool {
+        FPA(self.x_min) == FPA(other.x_min)
+            && FPA(self.y_min) == FPA(other.y_min)
+            && FPA(self.x_max) == FPA(other.x_max)
+            && FPA(self.y_max) == FPA(other.y_max)
+    }
+}
+
 impl CollidesWith<AARectangle> for AARectangle {
     fn collides_with(&self, other: &AARectangle) -> bool {
         fsize::max(self.x_min, other.x_min) <= fsize::min(self.x_max, other.x_max)
@@ -304,14 +371,20 @@ impl DistanceFrom<Point> for AARectangle {
         self.sq_distance(point).sqrt()
     }
 
-    fn distance_from_border(&self, point: &Point) -> (GeoPosition, fsize) {
+    fn distance_from_border(&self, point: &Point) -> (BorderPosition, fsize) {
         let (position, sq_distance) = self.sq_distance_from_border(point);
         (position, sq_distance.sqrt())
     }
 
-    fn sq_distance_from_border(&self, point: &Point) -> (GeoPosition, fsize) {
+    fn sq_distance_from_border(&self, point: &Point) -> (BorderPosition, fsize) {
         match self.collides_with(point) {
-            false => (GeoPosition::Exterior, self.sq_distance(point)),
+            false => {
+                let sq_distance = self.sq_distance(point);
+                (
+                    BorderPosition::from_sq_border_distance(false, sq_distance, self.diameter()),
+                    sq_distance,
+                )
+            }
             true => {
                 let Point(x, y) = *point;
                 let min_distance = [
@@ -323,7 +396,11 @@ impl DistanceFrom<Point> for AARectangle {
                 .into_iter()
                 .min_by_key(|&d| OrderedFloat(d))
                 .unwrap();
-                (GeoPosition::Interior, min_distance.powi(2))
+                let sq_distance = min_distance.powi(2);
+                (
+                    BorderPosition::from_sq_border_distance(true, sq_distance, self.diameter()),
+                    sq_distance,
+                )
             }
         }
     }
@@ -0,0 +1,70 @@
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use crate::geometry::geo_traits::{CollidesWith, Shape};
+use crate::geometry::primitives::aa_rectangle::AARectangle;
+use crate::geometry::primitives::edge::Edge;
+use crate::geometry::primitives::point::Point;
+use crate::{fsize, PI};
+
+/// Geometric primitive representing a capsule: all points closer than `radius` to an edge.
+/// Used to query for hazards within a certain distance of an edge.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Capsule {
+    pub edge: Edge,
+    pub radius: fsize,
+}
+
+impl Capsule {
+    pub fn new(edge: Edge, radius: fsize) -> Self {
+        debug_assert!(
+            radius.is_finite() && radius >= 0.0,
+            "invalid capsule radius: {}",
+            radius
+        );
+        Self { edge, radius }
+    }
+}
+
+impl Shape for Capsule {
+    fn centroid(&self) -> Point {
+        self.edge.centroid()
+    }
+
+    fn area(&self) -> fsize {
+        2.0 * self.radius * self.edge.diameter() + PI * self.radius.powi(2)
+    }
+
+    fn bbox(&self) -> AARectangle {
+        //built from the extremes of the edge, its own bbox is degenerate for axis-aligned edges
+        AARectangle::new(
+            self.edge.x_min() - self.radius,
+            self.edge.y_min() - self.radius,
+            self.edge.x_max() + self.radius,
+            self.edge.y_max() + self.radius,
+        )
+    }
+
+    fn diameter(&self) -> fsize {
+        self.edge.diameter() + 2.0 * self.radius
+    }
+}
+
+impl CollidesWith<Edge> for Capsule {
+    fn collides_with(&self, edge: &Edge) -> bool {
+        self.edge.sq_distance_to_edge(edge) < self.radius.powi(2)
+    }
+}
+
+impl CollidesWith<AARectangle> for Capsule {
+    fn collides_with(&self, rect: &AARectangle) -> bool {
+        match self.bbox().collides_with(rect) {
+            false => false,
+            true => {
+                //either the edge itself touches the rectangle, or one of the sides of the rectangle is within reach
+                rect.collides_with(&self.edge)
+                    || rect.edges().iter().any(|side| self.collides_with(side))
+            }
+        }
+    }
+}
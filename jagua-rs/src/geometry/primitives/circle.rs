@@ -1,6 +1,9 @@
-use std::cmp::Ordering;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
-use crate::geometry::geo_enums::GeoPosition;
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::geo_enums::BorderPosition;
 use crate::geometry::geo_traits::{
     CollidesWith, DistanceFrom, Shape, Transformable, TransformableFrom,
 };
@@ -11,7 +14,7 @@ use crate::geometry::transformation::Transformation;
 use crate::{fsize, PI};
 
 /// Geometric primitive representing a circle
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Circle {
     pub center: Point,
     pub radius: fsize,
@@ -130,17 +133,19 @@ impl DistanceFrom<Point> for Circle {
         }
     }
 
-    fn distance_from_border(&self, point: &Point) -> (GeoPosition, fsize) {
+    fn distance_from_border(&self, point: &Point) -> (BorderPosition, fsize) {
         let Point(x, y) = point;
         let Point(cx, cy) = self.center;
         let d_center = fsize::sqrt((x - cx).powi(2) + (y - cy).powi(2));
-        match d_center.partial_cmp(&self.radius).unwrap() {
-            Ordering::Less | Ordering::Equal => (GeoPosition::Interior, self.radius - d_center),
-            Ordering::Greater => (GeoPosition::Exterior, d_center - self.radius),
-        }
+        let distance = (d_center - self.radius).abs();
+        let inside = d_center <= self.radius;
+        (
+            BorderPosition::from_sq_border_distance(inside, distance.powi(2), self.diameter()),
+            distance,
+        )
     }
 
-    fn sq_distance_from_border(&self, point: &Point) -> (GeoPosition, fsize) {
+    fn sq_distance_from_border(&self, point: &Point) -> (BorderPosition, fsize) {
         let (pos, distance) = self.distance_from_border(point);
         (pos, distance.powi(2))
     }
@@ -153,24 +158,26 @@ impl DistanceFrom<Circle> for Circle {
 
     fn distance(&self, other: &Circle) -> fsize {
         match self.distance_from_border(other) {
-            (GeoPosition::Interior, _) => 0.0,
-            (GeoPosition::Exterior, d) => d,
+            (BorderPosition::Exterior, d) => d,
+            (BorderPosition::Interior | BorderPosition::Boundary, _) => 0.0,
         }
     }
 
-    fn distance_from_border(&self, other: &Circle) -> (GeoPosition, fsize) {
+    fn distance_from_border(&self, other: &Circle) -> (BorderPosition, fsize) {
         let sq_center_dist = self.center.sq_distance(other.center);
         let sq_radii_sum = (self.radius + other.radius).powi(2);
-        if sq_center_dist < sq_radii_sum {
-            let dist = sq_radii_sum.sqrt() - sq_center_dist.sqrt();
-            (GeoPosition::Interior, dist)
-        } else {
-            let dist = sq_center_dist.sqrt() - sq_radii_sum.sqrt();
-            (GeoPosition::Exterior, dist)
-        }
-    }
-
-    fn sq_distance_from_border(&self, other: &Circle) -> (GeoPosition, fsize) {
+        let dist = (sq_center_dist.sqrt() - sq_radii_sum.sqrt()).abs();
+        (
+            BorderPosition::from_sq_border_distance(
+                sq_center_dist < sq_radii_sum,
+                dist.powi(2),
+                self.diameter(),
+            ),
+            dist,
+        )
+    }
+
+    fn sq_distance_from_border(&self, other: &Circle) -> (BorderPosition, fsize) {
         let (pos, distance) = self.distance_from_border(other);
         (pos, distance.powi(2))
     }
@@ -183,21 +190,25 @@ impl DistanceFrom<Edge> for Circle {
 
     fn distance(&self, e: &Edge) -> fsize {
         match self.distance_from_border(e) {
-            (GeoPosition::Interior, _) => 0.0,
-            (GeoPosition::Exterior, d) => d,
+            (BorderPosition::Exterior, d) => d,
+            (BorderPosition::Interior | BorderPosition::Boundary, _) => 0.0,
         }
     }
 
-    fn distance_from_border(&self, e: &Edge) -> (GeoPosition, fsize) {
+    fn distance_from_border(&self, e: &Edge) -> (BorderPosition, fsize) {
         let distance_to_center = e.distance(&self.center);
-        if distance_to_center < self.radius {
-            (GeoPosition::Interior, self.radius - distance_to_center)
-        } else {
-            (GeoPosition::Exterior, distance_to_center - self.radius)
-        }
-    }
-
-    fn sq_distance_from_border(&self, e: &Edge) -> (GeoPosition, fsize) {
+        let distance = (distance_to_center - self.radius).abs();
+        (
+            BorderPosition::from_sq_border_distance(
+                distance_to_center < self.radius,
+                distance.powi(2),
+                self.diameter(),
+            ),
+            distance,
+        )
+    }
+
+    fn sq_distance_from_border(&self, e: &Edge) -> (BorderPosition, fsize) {
         let (pos, distance) = self.distance_from_border(e);
         (pos, distance.powi(2))
     }
@@ -1,14 +1,19 @@
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use serde::{Deserialize, Serialize};
+
 use crate::fsize;
-use crate::geometry::geo_enums::GeoPosition;
+use crate::geometry::geo_enums::BorderPosition;
 use crate::geometry::geo_traits::{
-    CollidesWith, DistanceFrom, Shape, Transformable, TransformableFrom,
+    ApproxEq, CollidesWith, DistanceFrom, Shape, Transformable, TransformableFrom,
 };
 use crate::geometry::primitives::aa_rectangle::AARectangle;
 use crate::geometry::primitives::point::Point;
 use crate::geometry::transformation::Transformation;
 
 /// Geometric primitive representing a line segment
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Edge {
     pub start: Point,
     pub end: Point,
@@ -41,6 +46,19 @@ impl Edge {
         self
     }
 
+    /// Moves the edge perpendicularly by distance d, to its left.
+    /// For the edges of a (counterclockwise) [SimplePolygon](crate::geometry::primitives::simple_polygon::SimplePolygon), this is towards the interior
+    pub fn offset_left(mut self, d: fsize) -> Self {
+        let (dx, dy) = (self.end.0 - self.start.0, self.end.1 - self.start.1);
+        let l = self.diameter();
+        let (nx, ny) = (-dy * (d / l), dx * (d / l));
+        self.start.0 += nx;
+        self.start.1 += ny;
+        self.end.0 += nx;
+        self.end.1 += ny;
+        self
+    }
+
     pub fn scale(mut self, factor: fsize) -> Self {
         let (dx, dy) = (self.end.0 - self.start.0, self.end.1 - self.start.1);
         self.start.0 -= dx * (factor - 1.0) / 2.0;
@@ -51,7 +69,7 @@ impl Edge {
     }
 
     pub fn reverse(mut self) -> Self {
-        std::mem::swap(&mut self.start, &mut self.end);
+        core::mem::swap(&mut self.start, &mut self.end);
         self
     }
 
@@ -91,6 +109,41 @@ impl Edge {
         Point(xx, yy)
     }
 
+    /// Length over which `other` coincides with this edge: zero unless both endpoints of `other` lie within `tolerance` of the line through this edge
+    pub fn overlap_length(&self, other: &Edge, tolerance: fsize) -> fsize {
+        let length = self.diameter();
+        let (Point(x1, y1), Point(x2, y2)) = (self.start, self.end);
+        let (dx, dy) = ((x2 - x1) / length, (y2 - y1) / length);
+        let dist_to_line = |Point(x, y): Point| (dx * (y - y1) - dy * (x - x1)).abs();
+        match dist_to_line(other.start) <= tolerance && dist_to_line(other.end) <= tolerance {
+            false => 0.0,
+            true => {
+                //overlap of the projections of both edges on the line through this edge
+                let project = |Point(x, y): Point| dx * (x - x1) + dy * (y - y1);
+                let (p_start, p_end) = (project(other.start), project(other.end));
+                let overlap =
+                    fsize::min(length, p_start.max(p_end)) - fsize::max(0.0, p_start.min(p_end));
+                overlap.max(0.0)
+            }
+        }
+    }
+
+    /// Squared distance between the closest points of both edges, zero if they intersect
+    pub fn sq_distance_to_edge(&self, other: &Edge) -> fsize {
+        match self.collides_with(other) {
+            true => 0.0,
+            //the closest pair of points of two disjoint edges always includes one of the endpoints
+            false => [
+                self.sq_distance(&other.start),
+                self.sq_distance(&other.end),
+                other.sq_distance(&self.start),
+                other.sq_distance(&self.end),
+            ]
+            .into_iter()
+            .fold(fsize::MAX, fsize::min),
+        }
+    }
+
     pub fn x_min(&self) -> fsize {
         fsize::min(self.start.0, self.end.0)
     }
@@ -108,6 +161,12 @@ impl Edge {
     }
 }
 
+impl ApproxEq for Edge {
+    fn approx_eq(&self, other: &Self) -> bool {
+        self.start.approx_eq(&other.start) && self.end.approx_eq(&other.end)
+    }
+}
+
 impl Transformable for Edge {
     fn transform(&mut self, t: &Transformation) -> &mut Self {
         let Edge { start, end } = self;
@@ -162,12 +221,20 @@ impl DistanceFrom<Point> for Edge {
         fsize::sqrt(self.sq_distance(point))
     }
 
-    fn distance_from_border(&self, point: &Point) -> (GeoPosition, fsize) {
-        (GeoPosition::Exterior, self.distance(point))
+    fn distance_from_border(&self, point: &Point) -> (BorderPosition, fsize) {
+        let sq_distance = self.sq_distance(point);
+        (
+            BorderPosition::from_sq_border_distance(false, sq_distance, self.diameter()),
+            sq_distance.sqrt(),
+        )
     }
 
-    fn sq_distance_from_border(&self, point: &Point) -> (GeoPosition, fsize) {
-        (GeoPosition::Exterior, self.sq_distance(point))
+    fn sq_distance_from_border(&self, point: &Point) -> (BorderPosition, fsize) {
+        let sq_distance = self.sq_distance(point);
+        (
+            BorderPosition::from_sq_border_distance(false, sq_distance, self.diameter()),
+            sq_distance,
+        )
     }
 }
 
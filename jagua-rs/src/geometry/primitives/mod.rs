@@ -1,4 +1,5 @@
 pub mod aa_rectangle;
+pub mod capsule;
 pub mod circle;
 pub mod edge;
 pub mod point;
@@ -1,11 +1,17 @@
-use std::hash::{Hash, Hasher};
+use core::hash::{Hash, Hasher};
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use serde::{Deserialize, Serialize};
 
 use crate::fsize;
-use crate::geometry::geo_traits::{CollidesWith, Transformable, TransformableFrom};
+use crate::geometry::geo_traits::{ApproxEq, CollidesWith, Transformable, TransformableFrom};
 use crate::geometry::transformation::Transformation;
+use crate::util::fpa::FPA;
 
 /// Geometric primitive representing a point: (x, y)
-#[derive(Debug, Clone, PartialEq, Copy)]
+#[derive(Debug, Clone, PartialEq, Copy, Serialize, Deserialize)]
 pub struct Point(pub fsize, pub fsize);
 
 impl Transformable for Point {
@@ -61,6 +67,12 @@ impl Hash for Point {
     }
 }
 
+impl ApproxEq for Point {
+    fn approx_eq(&self, other: &Self) -> bool {
+        FPA(self.0) == FPA(other.0) && FPA(self.1) == FPA(other.1)
+    }
+}
+
 impl From<Point> for (fsize, fsize) {
     fn from(p: Point) -> Self {
         (p.0, p.1)
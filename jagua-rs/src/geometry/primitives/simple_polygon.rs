@@ -1,14 +1,18 @@
-use std::borrow::Borrow;
+use alloc::{vec, vec::Vec};
+use core::borrow::Borrow;
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
 use itertools::Itertools;
+use log::Level;
 use num_integer::Integer;
 use ordered_float::NotNan;
 
-use crate::fsize;
 use crate::geometry::convex_hull::convex_hull_from_points;
 use crate::geometry::fail_fast::poi;
 use crate::geometry::fail_fast::sp_surrogate::SPSurrogate;
-use crate::geometry::geo_enums::GeoPosition;
+use crate::geometry::geo_enums::BorderPosition;
 use crate::geometry::geo_traits::{
     CollidesWith, DistanceFrom, Shape, Transformable, TransformableFrom,
 };
@@ -17,18 +21,23 @@ use crate::geometry::primitives::circle::Circle;
 use crate::geometry::primitives::edge::Edge;
 use crate::geometry::primitives::point::Point;
 use crate::geometry::transformation::Transformation;
+use crate::util::assertions;
 use crate::util::config::SPSurrogateConfig;
+use crate::util::fnv::FnvIndexSet;
 use crate::util::fpa::FPA;
+use crate::util::trace::PhaseSpan;
+use crate::{fsize, PI};
 
 /// Geometric primitive representing a simple polygon: <https://en.wikipedia.org/wiki/Simple_polygon>
 #[derive(Clone, Debug)]
 pub struct SimplePolygon {
     /// Set of bounds describing the polygon
     pub points: Vec<Point>,
-    /// Bounding box
+    /// Bounding box, regenerated on every transformation
     pub bbox: AARectangle,
+    /// Area, invariant under (rigid) transformations
     pub area: fsize,
-    /// Maximum distance between any two points in the polygon
+    /// Maximum distance between any two points in the polygon, invariant under (rigid) transformations
     pub diameter: fsize,
     /// Pole of inaccessibility
     pub poi: Circle,
@@ -44,7 +53,7 @@ impl SimplePolygon {
             "simple polygon must have at least 3 points"
         );
         assert_eq!(
-            points.iter().unique().count(),
+            points.iter().collect::<FnvIndexSet<_>>().len(),
             points.len(),
             "simple polygon should not contain duplicate points: {:?}",
             points
@@ -74,8 +83,28 @@ impl SimplePolygon {
         }
     }
 
+    /// Regular polygon with `n_vertices` vertices at distance `radius` from the origin, the first one on the positive x-axis
+    pub fn regular(n_vertices: usize, radius: fsize) -> Self {
+        assert!(n_vertices >= 3, "regular polygon needs at least 3 vertices");
+        assert!(
+            radius > 0.0 && radius.is_finite(),
+            "invalid radius: {}",
+            radius
+        );
+        let points = (0..n_vertices)
+            .map(|i| {
+                let angle = 2.0 * PI * i as fsize / n_vertices as fsize;
+                Point(radius * angle.cos(), radius * angle.sin())
+            })
+            .collect_vec();
+        SimplePolygon::new(points)
+    }
+
     pub fn generate_surrogate(&mut self, config: SPSurrogateConfig) {
-        self.surrogate = Some(SPSurrogate::new(self, config));
+        let _span = PhaseSpan::enter_with_level("surrogate generation", Level::Trace);
+        let surrogate = SPSurrogate::new(self, config);
+        debug_assert!(assertions::surrogate_poles_inside(self, &surrogate));
+        self.surrogate = Some(surrogate);
     }
 
     pub fn get_point(&self, i: usize) -> Point {
@@ -95,6 +124,43 @@ impl SimplePolygon {
         self.points.len()
     }
 
+    /// Total length of the boundary shared with `other`: the sum of all overlaps of collinear edges within `tolerance`.
+    /// Separate contact segments are summed, only meaningful if both polygons do not overlap.
+    pub fn contact_length(&self, other: &SimplePolygon, tolerance: fsize) -> fsize {
+        match self.bbox().inflate(tolerance).collides_with(&other.bbox()) {
+            false => 0.0,
+            true => self
+                .edge_iter()
+                .cartesian_product(other.edge_iter().collect_vec())
+                .map(|(e1, e2)| e1.overlap_length(&e2, tolerance))
+                .sum(),
+        }
+    }
+
+    /// Minimum distance between the boundaries of both polygons, zero if they overlap or touch.
+    /// If both have a surrogate, overlapping poles are used to detect overlap before comparing the edges.
+    pub fn separation_distance(&self, other: &SimplePolygon) -> fsize {
+        let poles_overlap = match (&self.surrogate, &other.surrogate) {
+            (Some(s1), Some(s2)) => s1
+                .poles
+                .iter()
+                .cartesian_product(s2.poles.iter())
+                .any(|(p1, p2)| p1.collides_with(p2)),
+            _ => false,
+        };
+        let contained =
+            || self.collides_with(&other.get_point(0)) || other.collides_with(&self.get_point(0));
+        match poles_overlap || contained() {
+            true => 0.0,
+            false => self
+                .edge_iter()
+                .cartesian_product(other.edge_iter().collect_vec())
+                .map(|(e1, e2)| e1.sq_distance_to_edge(&e2))
+                .fold(fsize::MAX, fsize::min)
+                .sqrt(),
+        }
+    }
+
     pub fn surrogate(&self) -> &SPSurrogate {
         self.surrogate.as_ref().expect("surrogate not generated")
     }
@@ -115,16 +181,7 @@ impl SimplePolygon {
     }
 
     pub fn generate_bounding_box(points: &[Point]) -> AARectangle {
-        let (mut x_min, mut y_min) = (fsize::MAX, fsize::MAX);
-        let (mut x_max, mut y_max) = (fsize::MIN, fsize::MIN);
-
-        for point in points.iter() {
-            x_min = x_min.min(point.0);
-            y_min = y_min.min(point.1);
-            x_max = x_max.max(point.0);
-            y_max = y_max.max(point.1);
-        }
-        AARectangle::new(x_min, y_min, x_max, y_max)
+        AARectangle::from_points(points).expect("polygon has no points")
     }
 
     //https://en.wikipedia.org/wiki/Shoelace_formula
@@ -173,6 +230,21 @@ impl SimplePolygon {
 
         (self, transformation)
     }
+
+    /// Centroid of the region enclosed by the polygon, minus the `holes`.
+    /// Every hole subtracts its area-weighted centroid, so the result shifts away from the holes.
+    pub fn centroid_with_holes(&self, holes: &[&SimplePolygon]) -> Point {
+        let (mut area, Point(mut c_x, mut c_y)) = (self.area(), self.centroid());
+        c_x *= area;
+        c_y *= area;
+        for hole in holes {
+            let (h_area, Point(h_x, h_y)) = (hole.area(), hole.centroid());
+            area -= h_area;
+            c_x -= h_x * h_area;
+            c_y -= h_y * h_area;
+        }
+        Point(c_x / area, c_y / area)
+    }
 }
 
 impl Shape for SimplePolygon {
@@ -251,12 +323,17 @@ impl TransformableFrom for SimplePolygon {
         let SimplePolygon {
             points,
             bbox,
-            area: _,
-            diameter: _,
+            area,
+            diameter,
             poi,
             surrogate,
         } = self;
 
+        assert_eq!(
+            points.len(),
+            reference.points.len(),
+            "reference has a different number of points"
+        );
         for (p, ref_p) in points.iter_mut().zip(&reference.points) {
             p.transform_from(ref_p, t);
         }
@@ -267,8 +344,10 @@ impl TransformableFrom for SimplePolygon {
         if let Some(surrogate) = surrogate.as_mut() {
             surrogate.transform_from(reference.surrogate(), t);
         }
-        //regenerate bounding box
+        //regenerate bounding box, the invariants are copied in case self was not a clone of the reference
         *bbox = SimplePolygon::generate_bounding_box(points);
+        *area = reference.area;
+        *diameter = reference.diameter;
 
         self
     }
@@ -326,22 +405,26 @@ impl DistanceFrom<Point> for SimplePolygon {
         self.sq_distance(point).sqrt()
     }
 
-    fn distance_from_border(&self, point: &Point) -> (GeoPosition, fsize) {
+    fn distance_from_border(&self, point: &Point) -> (BorderPosition, fsize) {
         let (position, sq_distance) = self.sq_distance_from_border(point);
         (position, sq_distance.sqrt())
     }
 
-    fn sq_distance_from_border(&self, point: &Point) -> (GeoPosition, fsize) {
+    fn sq_distance_from_border(&self, point: &Point) -> (BorderPosition, fsize) {
         let distance_to_border = self
             .edge_iter()
             .map(|edge| edge.sq_distance(point))
             .min_by(|a, b| a.partial_cmp(b).unwrap())
             .unwrap();
 
-        match self.collides_with(point) {
-            true => (GeoPosition::Interior, distance_to_border),
-            false => (GeoPosition::Exterior, distance_to_border),
-        }
+        (
+            BorderPosition::from_sq_border_distance(
+                self.collides_with(point),
+                distance_to_border,
+                self.diameter(),
+            ),
+            distance_to_border,
+        )
     }
 }
 
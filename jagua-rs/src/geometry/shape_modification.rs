@@ -0,0 +1,138 @@
+use alloc::{vec, vec::Vec};
+
+use itertools::Itertools;
+
+use crate::fsize;
+use crate::geometry::primitives::point::Point;
+use crate::geometry::primitives::simple_polygon::SimplePolygon;
+
+/// Decomposes a [SimplePolygon] into a set of convex polygons which together cover the original shape.
+/// The decomposition is approximately minimal: at most four times the optimal number of parts.
+/// Uses ear clipping to triangulate, followed by [Hertel-Mehlhorn](https://doi.org/10.1007/3-540-12689-9_105) to merge the triangles.
+/// If the triangulation gets stuck on a numerical degeneracy (no ear or collinear vertex left to remove),
+/// the remainder is kept as a single part, which is the only part that can be non-convex.
+/// Only the outer ring is decomposed, [SimplePolygon] has no holes.
+pub fn convex_decompose(shape: &SimplePolygon) -> Vec<SimplePolygon> {
+    let points = &shape.points;
+    if is_convex(points, &(0..points.len()).collect_vec()) {
+        return vec![SimplePolygon::new(points.clone())];
+    }
+
+    let mut parts = triangulate(points);
+
+    //Hertel-Mehlhorn: remove diagonals as long as the merged part remains convex
+    let mut merged = true;
+    while merged {
+        merged = false;
+        'search: for i in 0..parts.len() {
+            for j in (i + 1)..parts.len() {
+                if let Some(m) = merge_along_shared_edge(&parts[i], &parts[j]) {
+                    if is_convex(points, &m) {
+                        parts[i] = m;
+                        parts.swap_remove(j);
+                        merged = true;
+                        break 'search;
+                    }
+                }
+            }
+        }
+    }
+
+    parts
+        .into_iter()
+        .map(|p| SimplePolygon::new(p.into_iter().map(|i| points[i]).collect()))
+        .collect()
+}
+
+/// Ear clipping triangulation of a counterclockwise polygon, returns the triangles as indices into `points`
+fn triangulate(points: &[Point]) -> Vec<Vec<usize>> {
+    let mut remaining = (0..points.len()).collect_vec();
+    let mut triangles = vec![];
+
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let ear = (0..n).find(|&i| {
+            let (a, b, c) = (
+                remaining[(i + n - 1) % n],
+                remaining[i],
+                remaining[(i + 1) % n],
+            );
+            cross(points[a], points[b], points[c]) > 0.0
+                && remaining
+                    .iter()
+                    .filter(|&&k| k != a && k != b && k != c)
+                    .all(|&k| !in_triangle(points[k], points[a], points[b], points[c]))
+        });
+        match ear {
+            Some(i) => {
+                let (a, b, c) = (
+                    remaining[(i + n - 1) % n],
+                    remaining[i],
+                    remaining[(i + 1) % n],
+                );
+                triangles.push(vec![a, b, c]);
+                remaining.remove(i);
+            }
+            None => {
+                //no valid ear found, remove a collinear vertex if there is one
+                let collinear = (0..n).find(|&i| {
+                    let (a, b, c) = (
+                        remaining[(i + n - 1) % n],
+                        remaining[i],
+                        remaining[(i + 1) % n],
+                    );
+                    cross(points[a], points[b], points[c]) == 0.0
+                });
+                match collinear {
+                    Some(i) => {
+                        remaining.remove(i);
+                    }
+                    None => break, //numerical degeneracy, keep the (possibly non-convex) remainder as a single part
+                }
+            }
+        }
+    }
+    //skip the final triangle if it is degenerate
+    if remaining.len() > 3
+        || cross(
+            points[remaining[0]],
+            points[remaining[1]],
+            points[remaining[2]],
+        ) != 0.0
+    {
+        triangles.push(remaining);
+    }
+    triangles
+}
+
+/// Merges two counterclockwise polygons (as indices) if they share an edge
+fn merge_along_shared_edge(p1: &[usize], p2: &[usize]) -> Option<Vec<usize>> {
+    let (n1, n2) = (p1.len(), p2.len());
+    for i in 0..n1 {
+        let (a, b) = (p1[i], p1[(i + 1) % n1]);
+        //a shared edge is traversed in the opposite direction in the other polygon
+        if let Some(j) = (0..n2).find(|&j| p2[j] == b && p2[(j + 1) % n2] == a) {
+            //walk p1 from b to a, followed by the interior points of p2 from a to b
+            let mut merged = (0..n1).map(|k| p1[(i + 1 + k) % n1]).collect_vec();
+            merged.extend((2..n2).map(|k| p2[(j + k) % n2]));
+            return Some(merged);
+        }
+    }
+    None
+}
+
+fn is_convex(points: &[Point], indices: &[usize]) -> bool {
+    let n = indices.len();
+    (0..n).all(|i| {
+        let (a, b, c) = (indices[i], indices[(i + 1) % n], indices[(i + 2) % n]);
+        cross(points[a], points[b], points[c]) >= 0.0
+    })
+}
+
+fn in_triangle(p: Point, a: Point, b: Point, c: Point) -> bool {
+    cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0
+}
+
+fn cross(a: Point, b: Point, c: Point) -> fsize {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
@@ -0,0 +1,174 @@
+use alloc::collections::BTreeSet;
+use core::cmp::Ordering;
+use core::ops::Bound::{Excluded, Unbounded};
+
+use itertools::Itertools;
+
+use crate::fsize;
+use crate::geometry::primitives::point::Point;
+use crate::util::fnv::FnvIndexSet;
+
+/// Checks whether the polygon defined by `points` is simple: no two edges intersect, except for consecutive edges sharing a vertex.
+/// A closing vertex equal to the first one is ignored.
+/// Consecutive collinear edges are allowed, as long as they do not fold back onto each other.
+/// Uses the [Shamos-Hoey](https://doi.org/10.1109/SFCS.1976.16) sweep-line algorithm, running in O(n log n).
+pub fn is_simple(points: &[Point]) -> bool {
+    let points = match points {
+        [first, .., last] if first == last => &points[..points.len() - 1],
+        _ => points,
+    };
+    let n = points.len();
+    if n < 3 || points.iter().collect::<FnvIndexSet<_>>().len() != n {
+        return false;
+    }
+
+    let segments = (0..n)
+        .map(|i| Segment::new(i, points[i], points[(i + 1) % n]))
+        .collect_vec();
+
+    //at the same point, segments are inserted before others are removed, so touching segments are always compared
+    let mut events = segments
+        .iter()
+        .flat_map(|s| [(s.left, false, s.id), (s.right, true, s.id)])
+        .collect_vec();
+    events.sort_by(|(p1, end1, _), (p2, end2, _)| lexicographic(p1, p2).then(end1.cmp(end2)));
+
+    let mut sweep_line = BTreeSet::new();
+    for (_, is_end, id) in events {
+        let segment = segments[id];
+        let below = sweep_line.range(..segment).next_back().copied();
+        let above = sweep_line
+            .range((Excluded(segment), Unbounded))
+            .next()
+            .copied();
+        match is_end {
+            false => {
+                let intersecting = [below, above]
+                    .into_iter()
+                    .flatten()
+                    .any(|other| intersect(&segment, &other, n));
+                if intersecting {
+                    return false;
+                }
+                sweep_line.insert(segment);
+            }
+            true => {
+                if !sweep_line.remove(&segment) {
+                    //the order of the sweep line can only become inconsistent when segments cross
+                    return false;
+                }
+                if let (Some(below), Some(above)) = (below, above) {
+                    if intersect(&below, &above, n) {
+                        return false;
+                    }
+                }
+            }
+        }
+    }
+    true
+}
+
+/// Edge of the polygon, with its endpoints sorted from left to right
+#[derive(Clone, Copy, Debug)]
+struct Segment {
+    id: usize,
+    left: Point,
+    right: Point,
+}
+
+impl Segment {
+    fn new(id: usize, start: Point, end: Point) -> Self {
+        let (left, right) = match lexicographic(&start, &end) {
+            Ordering::Greater => (end, start),
+            _ => (start, end),
+        };
+        Self { id, left, right }
+    }
+
+    /// Vertical order of `self` relative to `other`, where `self` starts before (or at the same point as) `other`
+    fn vertical_order(&self, other: &Segment) -> Ordering {
+        let orientation = match orientation(self.left, self.right, other.left) {
+            0.0 => orientation(self.left, self.right, other.right),
+            o => o,
+        };
+        match orientation.partial_cmp(&0.0) {
+            Some(Ordering::Greater) => Ordering::Less,
+            Some(Ordering::Less) => Ordering::Greater,
+            _ => self.id.cmp(&other.id),
+        }
+    }
+}
+
+impl PartialEq for Segment {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Segment {}
+
+impl PartialOrd for Segment {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Segment {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.id == other.id {
+            return Ordering::Equal;
+        }
+        match lexicographic(&self.left, &other.left) {
+            Ordering::Greater => other.vertical_order(self).reverse(),
+            _ => self.vertical_order(other),
+        }
+    }
+}
+
+/// Whether two edges of a polygon with `n` edges intersect, consecutive edges are allowed to share their common vertex
+fn intersect(s1: &Segment, s2: &Segment, n: usize) -> bool {
+    let consecutive = (s1.id + 1) % n == s2.id || (s2.id + 1) % n == s1.id;
+    match consecutive {
+        true => {
+            //only a problem if the edges are collinear and fold back onto each other
+            let shared = match s1.left == s2.left || s1.left == s2.right {
+                true => s1.left,
+                false => s1.right,
+            };
+            let other_1 = if s1.left == shared { s1.right } else { s1.left };
+            let other_2 = if s2.left == shared { s2.right } else { s2.left };
+            let dot = (other_1.0 - shared.0) * (other_2.0 - shared.0)
+                + (other_1.1 - shared.1) * (other_2.1 - shared.1);
+            orientation(shared, other_1, other_2) == 0.0 && dot > 0.0
+        }
+        false => {
+            let (a, b, c, d) = (s1.left, s1.right, s2.left, s2.right);
+            let (o1, o2) = (orientation(a, b, c), orientation(a, b, d));
+            let (o3, o4) = (orientation(c, d, a), orientation(c, d, b));
+            let proper = o1 * o2 < 0.0 && o3 * o4 < 0.0;
+            proper
+                || (o1 == 0.0 && on_segment(c, s1))
+                || (o2 == 0.0 && on_segment(d, s1))
+                || (o3 == 0.0 && on_segment(a, s2))
+                || (o4 == 0.0 && on_segment(b, s2))
+        }
+    }
+}
+
+/// Whether a point collinear with the segment lies within its bounds
+fn on_segment(p: Point, s: &Segment) -> bool {
+    let (x_min, x_max) = (s.left.0, s.right.0);
+    let (y_min, y_max) = (
+        fsize::min(s.left.1, s.right.1),
+        fsize::max(s.left.1, s.right.1),
+    );
+    (x_min..=x_max).contains(&p.0) && (y_min..=y_max).contains(&p.1)
+}
+
+fn orientation(a: Point, b: Point, c: Point) -> fsize {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+fn lexicographic(p1: &Point, p2: &Point) -> Ordering {
+    p1.0.total_cmp(&p2.0).then(p1.1.total_cmp(&p2.1))
+}
@@ -1,5 +1,8 @@
-use std::borrow::Borrow;
-use std::ops::{Add, Div, Mul, Sub};
+use core::borrow::Borrow;
+use core::ops::{Add, Div, Mul, Sub};
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
 use ordered_float::NotNan;
 
@@ -64,6 +67,15 @@ impl Transformation {
         self
     }
 
+    /// Composition of `self` and `other`: the resulting transformation applies `other` first, followed by `self`.
+    /// Composition is associative and [`Transformation::empty`] is its identity,
+    /// `a.compose(&b)` is equivalent to `b.clone().transform(&a)`.
+    pub fn compose(&self, other: &Self) -> Self {
+        Self {
+            matrix: dot_prod(&self.matrix, &other.matrix),
+        }
+    }
+
     pub fn transform_from_decomposed(self, other: &DTransformation) -> Self {
         self.rotate_translate(other.rotation(), other.translation())
     }
@@ -0,0 +1,96 @@
+use std::fmt::Write;
+
+use crate::entities::instances::instance::Instance;
+use crate::entities::instances::instance_generic::InstanceGeneric;
+use crate::entities::solution::Solution;
+use crate::io::pretransform::internal_to_absolute_transform;
+
+/// Creates a CSV report with a row for every placed item: its id, the index of its layout in the solution,
+/// the id of the bin, and its absolute placement.
+///
+/// The placement is expressed in the coordinates of the original input, the same as the JSON solution and SVG export:
+/// the item's shape, as defined in the input, is first rotated around its origin by `rotation` (in degrees, counterclockwise)
+/// and then translated by (`x`, `y`) within the bin, as defined in the input.
+pub fn placement_report_csv(solution: &Solution, instance: &Instance) -> String {
+    let mut csv = String::from("item_id,layout_index,bin_id,x,y,rotation\n");
+    for (layout_index, sl) in solution.layout_snapshots.iter().enumerate() {
+        for pi in sl.placed_items.values() {
+            let abs_transf = internal_to_absolute_transform(
+                &pi.d_transf,
+                &instance.item(pi.item_id).pretransform,
+                &sl.bin.pretransform,
+            )
+            .decompose();
+            let (x, y) = abs_transf.translation();
+            writeln!(
+                csv,
+                "{},{},{},{},{},{}",
+                pi.item_id,
+                layout_index,
+                sl.bin.id,
+                x,
+                y,
+                abs_transf.rotation().to_degrees()
+            )
+            .expect("writing to a string cannot fail");
+        }
+    }
+    csv
+}
+
+/// Creates a solution in the XML format of the [ESICUP](https://www.euro-online.org/websites/esicup/data-sets/) nesting datasets,
+/// to compare against solutions of other solvers.
+///
+/// Pieces and boards are referred to as `piece{id}` and `board{id}`, boards of a solution are numbered from 1.
+/// Placements follow the conventions of the format: the piece, as defined in the input, is rotated around its origin
+/// by `angle` (in degrees, counterclockwise, within [0, 360)) and then translated by (`x`, `y`) from the origin of the board.
+/// For strip packing, the used length of the strip is included as well.
+pub fn esicup_solution_xml(solution: &Solution, instance: &Instance, name: &str) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<nesting xmlns=\"http://globalnest.fe.up.pt/nesting\">\n");
+    writeln!(xml, "  <name>{}</name>", escape_xml(name)).expect("writing to a string cannot fail");
+    xml.push_str("  <solutions>\n    <solution>\n");
+    if let Instance::SP(_) = instance {
+        for sl in solution.layout_snapshots.iter() {
+            writeln!(xml, "      <length>{}</length>", sl.bin.bbox().width())
+                .expect("writing to a string cannot fail");
+        }
+    }
+    for (layout_index, sl) in solution.layout_snapshots.iter().enumerate() {
+        for pi in sl.placed_items.values() {
+            let abs_transf = internal_to_absolute_transform(
+                &pi.d_transf,
+                &instance.item(pi.item_id).pretransform,
+                &sl.bin.pretransform,
+            )
+            .decompose();
+            let (x, y) = abs_transf.translation();
+            //-0.0 and rounding errors around 360° are mapped to 0°
+            let angle = match abs_transf.rotation().to_degrees().rem_euclid(360.0) {
+                a if 360.0 - a < 1e-3 => 0.0,
+                a => a + 0.0,
+            };
+            writeln!(
+                xml,
+                "      <placement boardNumber=\"{}\" idBoard=\"board{}\" idPiece=\"piece{}\" angle=\"{}\" mirror=\"none\" x=\"{}\" y=\"{}\"/>",
+                layout_index + 1,
+                sl.bin.id,
+                pi.item_id,
+                angle,
+                x,
+                y
+            )
+            .expect("writing to a string cannot fail");
+        }
+    }
+    xml.push_str("    </solution>\n  </solutions>\n</nesting>\n");
+    xml
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
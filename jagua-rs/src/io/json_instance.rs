@@ -1,3 +1,5 @@
+use alloc::{string::String, vec::Vec};
+
 use serde::{Deserialize, Serialize};
 
 use crate::fsize;
@@ -34,20 +36,46 @@ pub struct JsonBin {
     /// A list of zones with different quality levels
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub zones: Vec<JsonQualityZone>,
+    /// A list of zones where placing items is discouraged, but not forbidden
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub soft_zones: Vec<JsonShape>,
+    /// Margin along the sides of the bin in which no items can be placed
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub margin: Option<JsonMargin>,
+}
+
+/// The JSON representation of the margins of a bin
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum JsonMargin {
+    /// The same margin along all sides
+    Uniform(fsize),
+    /// A separate margin for each side
+    #[serde(rename_all = "PascalCase")]
+    PerSide {
+        left: fsize,
+        right: fsize,
+        bottom: fsize,
+        top: fsize,
+    },
 }
 
-/// The JSON representation of a strip with fixed height and variable width
+/// The JSON representation of a strip with fixed height and (by default) variable width
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "PascalCase")]
 pub struct JsonStrip {
     pub height: fsize,
+    /// Fixes the width of the strip, if not present the width is variable
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub width: Option<fsize>,
 }
 
 /// The JSON representation of an item
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "PascalCase")]
 pub struct JsonItem {
-    /// Number of times this item should be produced
+    /// Number of times this item should be produced, 0 (or not present) means unlimited
+    #[serde(default)]
     pub demand: u64,
     /// List of allowed orientations angles (in degrees). If none any orientation is allowed
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -58,6 +86,29 @@ pub struct JsonItem {
     pub value: Option<u64>,
     /// The quality required for the entire item, if not defined maximum quality is required
     pub base_quality: Option<usize>,
+    /// Region of the container the item is preferably placed in, without forbidding other placements
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub preferred_region: Option<JsonPreferredRegion>,
+    /// Arbitrary user data (part numbers, customer ids...), copied to every placement of the item in the solution
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// The JSON representation of a preferred region of an item
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub struct JsonPreferredRegion {
+    pub x_min: fsize,
+    pub y_min: fsize,
+    pub x_max: fsize,
+    pub y_max: fsize,
+    /// Importance of the preference, relative to those of other items
+    #[serde(default = "default_preference_weight")]
+    pub weight: fsize,
+}
+
+fn default_preference_weight() -> fsize {
+    1.0
 }
 
 /// Different ways to represent a shape
@@ -67,6 +118,8 @@ pub struct JsonItem {
 pub enum JsonShape {
     /// Axis-aligned rectangle. With the left-bottom corner at (0, 0)
     Rectangle { width: fsize, height: fsize },
+    /// Circle, approximated by a regular polygon. With the left-bottom corner of its bounding box at (0, 0)
+    Circle { radius: fsize },
     /// Polygon with a single outer boundary
     SimplePolygon(JsonSimplePoly),
     /// Polygon with a single outer boundary and a list of holes
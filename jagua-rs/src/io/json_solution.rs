@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 use serde::{Deserialize, Serialize};
 
 use crate::fsize;
@@ -34,6 +36,9 @@ pub struct JsonPlacedItem {
     pub index: usize,
     /// The transformation applied to the item to place it in the container
     pub transformation: JsonTransformation,
+    /// The metadata of the item in the instance, if any
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub metadata: Option<serde_json::Value>,
 }
 
 /// Represents a proper rigid transformation defined as a rotation followed by translation
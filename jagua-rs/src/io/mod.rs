@@ -1,3 +1,12 @@
+#[cfg(feature = "std")]
+pub mod export;
 pub mod json_instance;
 pub mod json_solution;
+#[cfg(feature = "std")]
+pub mod parse_error;
+#[cfg(feature = "std")]
 pub mod parser;
+/// Conversions between the internal (pretransformed) and absolute coordinates of items and bins
+pub mod pretransform;
+#[cfg(feature = "std")]
+pub mod surrogate_cache;
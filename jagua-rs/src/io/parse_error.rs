@@ -0,0 +1,119 @@
+use std::fmt::{Display, Formatter};
+
+use crate::fsize;
+
+/// Error type for all failures that can occur when parsing a JSON instance or solution.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// Both bins and a strip are specified, has to be one or the other
+    AmbiguousContainer,
+    /// Neither bins nor a strip are specified
+    MissingContainer,
+    /// The type of shape is not supported in this context
+    UnsupportedShape(&'static str),
+    /// A shape of the instance is not a valid polygon
+    InvalidPolygon {
+        owner: ShapeOwner,
+        defect: PolygonDefect,
+    },
+    /// A polygon of the instance has self-intersecting edges
+    NonSimplePolygon { owner: ShapeOwner },
+    /// Quality level of a zone is not below [`N_QUALITIES`](crate::entities::quality_zone::N_QUALITIES)
+    InvalidQuality(usize),
+    /// The solution does not match the instance
+    SolutionMismatch(String),
+    /// The margins of a bin are negative or leave no usable region
+    InvalidMargin(String),
+    /// The preferred region of an item is empty or has an invalid weight
+    InvalidPreferredRegion(String),
+    /// An item does not fit in any container, see [`Parser::reject_unfit_items`](crate::io::parser::Parser::reject_unfit_items)
+    ItemTooLarge { ids: Vec<usize> },
+}
+
+/// The part of the instance a shape belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShapeOwner {
+    /// The shape of the item with this id
+    Item(usize),
+    /// The outer shape, a hole or a zone of the bin with this id
+    Bin(usize),
+}
+
+/// Reason why a shape cannot be converted to a valid polygon
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PolygonDefect {
+    /// Fewer than 3 distinct points
+    TooFewPoints(usize),
+    /// At least one of the coordinates is NaN or infinite
+    NonFiniteCoordinates,
+    /// The same point occurs more than once
+    DuplicatePoints,
+    /// The points enclose no area
+    ZeroArea,
+    /// The width or height of a rectangle is not strictly positive
+    InvalidRectangle { width: fsize, height: fsize },
+    /// The radius of a circle is not strictly positive and finite
+    InvalidRadius(fsize),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::AmbiguousContainer => write!(
+                f,
+                "both bins and strip packing specified, has to be one or the other"
+            ),
+            ParseError::MissingContainer => write!(f, "neither bins or strips specified"),
+            ParseError::UnsupportedShape(shape_type) => {
+                write!(f, "no support for {shape_type} shapes here (yet)")
+            }
+            ParseError::InvalidPolygon { owner, defect } => {
+                write!(f, "invalid polygon for {owner}: {defect}")
+            }
+            ParseError::NonSimplePolygon { owner } => {
+                write!(f, "polygon for {owner} has self-intersecting edges")
+            }
+            ParseError::InvalidQuality(quality) => {
+                write!(f, "quality {quality} must be less than N_QUALITIES")
+            }
+            ParseError::SolutionMismatch(reason) => {
+                write!(f, "solution does not match instance: {reason}")
+            }
+            ParseError::InvalidMargin(reason) => write!(f, "invalid margin: {reason}"),
+            ParseError::InvalidPreferredRegion(reason) => {
+                write!(f, "invalid preferred region: {reason}")
+            }
+            ParseError::ItemTooLarge { ids } => {
+                write!(f, "items {ids:?} do not fit in any container")
+            }
+        }
+    }
+}
+
+impl Display for ShapeOwner {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShapeOwner::Item(id) => write!(f, "item {id}"),
+            ShapeOwner::Bin(id) => write!(f, "bin {id}"),
+        }
+    }
+}
+
+impl Display for PolygonDefect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolygonDefect::TooFewPoints(n) => {
+                write!(f, "{n} distinct points, at least 3 required")
+            }
+            PolygonDefect::NonFiniteCoordinates => write!(f, "non-finite coordinates"),
+            PolygonDefect::DuplicatePoints => write!(f, "duplicate points"),
+            PolygonDefect::ZeroArea => write!(f, "zero area"),
+            PolygonDefect::InvalidRectangle { width, height } => {
+                write!(f, "rectangle of {width} by {height}")
+            }
+            PolygonDefect::InvalidRadius(radius) => write!(f, "circle of radius {radius}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -1,12 +1,14 @@
+use std::fmt;
+use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::Instant;
 
-use crate::entities::bin::Bin;
+use crate::entities::bin::{Bin, BinMargin};
 use crate::entities::instances::bin_packing::BPInstance;
 use crate::entities::instances::instance::Instance;
-use crate::entities::instances::instance_generic::InstanceGeneric;
+use crate::entities::instances::instance_generic::{InstanceGeneric, UNLIMITED_ITEM_QTY};
 use crate::entities::instances::strip_packing::SPInstance;
-use crate::entities::item::Item;
+use crate::entities::item::{Item, PreferredRegion};
 use crate::entities::placing_option::PlacingOption;
 use crate::entities::problems::bin_packing::BPProblem;
 use crate::entities::problems::problem_generic::{LayoutIndex, ProblemGeneric, STRIP_LAYOUT_IDX};
@@ -14,32 +16,52 @@ use crate::entities::problems::strip_packing::SPProblem;
 use crate::entities::quality_zone::InferiorQualityZone;
 use crate::entities::quality_zone::N_QUALITIES;
 use crate::entities::solution::Solution;
-use crate::fsize;
 use crate::geometry::d_transformation::DTransformation;
 use crate::geometry::geo_enums::AllowedRotation;
 use crate::geometry::geo_traits::{Shape, Transformable};
 use crate::geometry::primitives::aa_rectangle::AARectangle;
 use crate::geometry::primitives::point::Point;
 use crate::geometry::primitives::simple_polygon::SimplePolygon;
+use crate::geometry::simplicity::is_simple;
 use crate::geometry::transformation::Transformation;
-use crate::io::json_instance::{JsonBin, JsonInstance, JsonItem, JsonShape, JsonSimplePoly};
+use crate::io::json_instance::{
+    JsonBin, JsonInstance, JsonItem, JsonMargin, JsonShape, JsonSimplePoly, JsonStrip,
+};
 use crate::io::json_solution::{
     JsonContainer, JsonLayout, JsonLayoutStats, JsonPlacedItem, JsonSolution, JsonTransformation,
 };
-use crate::util::config::CDEConfig;
+use crate::io::parse_error::{ParseError, PolygonDefect, ShapeOwner};
+pub use crate::io::pretransform::{
+    absolute_to_internal_transform, centering_transformation, internal_to_absolute_transform,
+    pretransform_bin, pretransform_item,
+};
+use crate::io::surrogate_cache::SurrogateCache;
+use crate::util::config::{CDEConfig, SPSurrogateConfig};
 use crate::util::polygon_simplification;
 use crate::util::polygon_simplification::{PolySimplConfig, PolySimplMode};
+use crate::util::trace::PhaseSpan;
+use crate::{fsize, PI};
 use itertools::Itertools;
 use log::{log, Level};
 use rayon::iter::IndexedParallelIterator;
 use rayon::iter::ParallelIterator;
 use rayon::prelude::IntoParallelRefIterator;
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+/// Number of vertices of the regular polygon used to approximate circles
+const N_CIRCLE_VERTICES: usize = 64;
+
+/// Number of rotations sampled per quarter turn to check whether continuously rotatable items fit in a container
+const N_FIT_ROTATION_SAMPLES: usize = 180;
 
 /// Parses a `JsonInstance` into an `Instance`.
 pub struct Parser {
     poly_simpl_config: PolySimplConfig,
     cde_config: CDEConfig,
     center_polygons: bool,
+    reject_unfit_items: bool,
+    surrogate_cache: Option<SurrogateCache>,
 }
 
 impl Parser {
@@ -52,59 +74,64 @@ impl Parser {
             poly_simpl_config,
             cde_config,
             center_polygons,
+            reject_unfit_items: false,
+            surrogate_cache: None,
         }
     }
 
+    /// Whether to fail with [`ParseError::ItemTooLarge`] when an item does not fit in any container.
+    /// Disabled by default, in which case only a warning is logged per item.
+    pub fn reject_unfit_items(mut self, reject: bool) -> Self {
+        self.reject_unfit_items = reject;
+        self
+    }
+
+    /// Reads the surrogates of the items from an on-disk cache, and stores the ones which are missing.
+    /// Disabled by default, in which case all surrogates are generated.
+    pub fn surrogate_cache(mut self, cache: SurrogateCache) -> Self {
+        self.surrogate_cache = Some(cache);
+        self
+    }
+
     /// Parses a `JsonInstance` into an `Instance`.
-    pub fn parse(&self, json_instance: &JsonInstance) -> Instance {
+    pub fn parse(&self, json_instance: &JsonInstance) -> Result<Instance, ParseError> {
+        let _span = PhaseSpan::enter("import");
         let items = json_instance
             .items
             .par_iter()
             .enumerate()
             .map(|(item_id, json_item)| self.parse_item(json_item, item_id))
-            .collect();
+            .collect::<Result<Vec<_>, _>>()?;
 
-        let instance: Instance = match (json_instance.bins.as_ref(), json_instance.strip.as_ref()) {
-            (Some(json_bins), None) => {
-                let bins: Vec<(Bin, usize)> = json_bins
+        let bins = match json_instance.bins.as_ref() {
+            Some(json_bins) => Some(
+                json_bins
                     .par_iter()
                     .enumerate()
                     .map(|(bin_id, json_bin)| self.parse_bin(json_bin, bin_id))
-                    .collect();
-                BPInstance::new(items, bins).into()
-            }
-            (None, Some(json_strip)) => SPInstance::new(items, json_strip.height).into(),
-            (Some(_), Some(_)) => {
-                panic!("Both bins and strip packing specified, has to be one or the other")
-            }
-            (None, None) => panic!("Neither bins or strips specified"),
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            None => None,
         };
 
-        match &instance {
-            Instance::SP(spi) => {
-                log!(
-                    Level::Info,
-                    "[PARSE] strip packing instance \"{}\": {} items ({} unique), {} strip height",
-                    json_instance.name,
-                    spi.total_item_qty(),
-                    spi.items.len(),
-                    spi.strip_height
-                );
-            }
-            Instance::BP(bpi) => {
-                log!(
-                    Level::Info,
-                    "[PARSE] bin packing instance \"{}\": {} items ({} unique), {} bins ({} unique)",
-                    json_instance.name,
-                    bpi.total_item_qty(),
-                    bpi.items.len(),
-                    bpi.bins.iter().map(|(_, qty)| *qty).sum::<usize>(),
-                    bpi.bins.len()
-                );
-            }
-        }
+        build_instance(
+            &json_instance.name,
+            items,
+            bins,
+            json_instance.strip.as_ref(),
+            self.reject_unfit_items,
+            self.cde_config,
+        )
+    }
 
-        instance
+    /// Parses an instance directly from a deserializer, without materializing the entire `JsonInstance` first.
+    /// Items and bins are converted one by one as they are deserialized, keeping memory usage low for very large instances.
+    pub fn parse_streaming<'de, D: Deserializer<'de>>(
+        &self,
+        deserializer: D,
+    ) -> Result<Instance, D::Error> {
+        let _span = PhaseSpan::enter("streaming import");
+        deserializer.deserialize_map(InstanceVisitor { parser: self })
     }
 
     /// Parses a `JsonInstance` and accompanying `JsonLayout`s into an `Instance` and `Solution`.
@@ -112,28 +139,32 @@ impl Parser {
         &self,
         json_instance: &JsonInstance,
         json_layouts: &[JsonLayout],
-    ) -> (Instance, Solution) {
-        let instance = Arc::new(self.parse(json_instance));
-        let solution = build_solution_from_json(instance.as_ref(), json_layouts, self.cde_config);
+    ) -> Result<(Instance, Solution), ParseError> {
+        let instance = Arc::new(self.parse(json_instance)?);
+        let solution = build_solution_from_json(instance.as_ref(), json_layouts, self.cde_config)?;
         let instance =
             Arc::try_unwrap(instance).expect("Cannot unwrap instance, strong references present");
-        (instance, solution)
+        Ok((instance, solution))
     }
 
-    fn parse_item(&self, json_item: &JsonItem, item_id: usize) -> (Item, usize) {
+    fn parse_item(
+        &self,
+        json_item: &JsonItem,
+        item_id: usize,
+    ) -> Result<(Item, usize), ParseError> {
+        let owner = ShapeOwner::Item(item_id);
         let shape = match &json_item.shape {
             JsonShape::Rectangle { width, height } => {
-                SimplePolygon::from(AARectangle::new(0.0, 0.0, *width, *height))
-            }
-            JsonShape::SimplePolygon(sp) => {
-                convert_json_simple_poly(sp, self.poly_simpl_config, PolySimplMode::Inflate)
+                convert_json_rectangle(*width, *height, owner)?
             }
-            JsonShape::Polygon(_) => {
-                unimplemented!("No support for polygon shapes yet")
+            JsonShape::Circle { radius } => {
+                convert_json_circle(*radius, PolySimplMode::Inflate, owner)?
             }
-            JsonShape::MultiPolygon(_) => {
-                unimplemented!("No support for multipolygon shapes yet")
+            JsonShape::SimplePolygon(sp) => {
+                convert_json_simple_poly(sp, self.poly_simpl_config, PolySimplMode::Inflate, owner)?
             }
+            JsonShape::Polygon(_) => return Err(ParseError::UnsupportedShape("polygon")),
+            JsonShape::MultiPolygon(_) => return Err(ParseError::UnsupportedShape("multipolygon")),
         };
 
         let item_value = json_item.value.unwrap_or(0);
@@ -150,64 +181,133 @@ impl Parser {
             None => AllowedRotation::Continuous,
         };
 
-        let base_item = Item::new(
-            item_id,
-            shape,
-            allowed_orientations,
-            base_quality,
-            item_value,
-            Transformation::empty(),
-            self.cde_config.item_surrogate_config,
-        );
+        //generating a surrogate for tiny items costs more than it saves during collision detection
+        let surrogate_config = match shape.area() < self.cde_config.item_surrogate_min_area {
+            true => SPSurrogateConfig::none(),
+            false => self.cde_config.item_surrogate_config,
+        };
 
-        let item = match self.center_polygons {
-            false => base_item,
+        //the shape is centered before the item is created, so its surrogate is only generated once
+        let (shape, pretransform) = match self.center_polygons {
+            false => (shape, Transformation::empty()),
             true => {
-                let centering_transform = centering_transformation(&base_item.shape);
-                pretransform_item(&base_item, &centering_transform.compose())
+                let centering_transform = centering_transformation(&shape).compose();
+                (
+                    shape.transform_clone(&centering_transform),
+                    Transformation::empty().transform(&centering_transform),
+                )
+            }
+        };
+
+        let base_item = match &self.surrogate_cache {
+            None => Item::new(
+                item_id,
+                shape,
+                allowed_orientations,
+                base_quality,
+                item_value,
+                pretransform,
+                surrogate_config,
+            ),
+            Some(cache) => {
+                let mut shape = shape;
+                shape.surrogate = Some(cache.surrogate(&shape, surrogate_config));
+                Item::with_existing_surrogate(
+                    item_id,
+                    shape,
+                    allowed_orientations,
+                    base_quality,
+                    item_value,
+                    pretransform,
+                    surrogate_config,
+                )
             }
         };
 
-        (item, json_item.demand as usize)
+        let base_item = match &json_item.preferred_region {
+            None => base_item,
+            Some(jpr) => {
+                let rect = AARectangle::new(jpr.x_min, jpr.y_min, jpr.x_max, jpr.y_max);
+                if !(rect.width() > 0.0 && rect.height() > 0.0) {
+                    return Err(ParseError::InvalidPreferredRegion(format!(
+                        "region of item {item_id} has no area: {rect:?}"
+                    )));
+                }
+                if !(jpr.weight.is_finite() && jpr.weight >= 0.0) {
+                    return Err(ParseError::InvalidPreferredRegion(format!(
+                        "weight of item {item_id} must be positive: {}",
+                        jpr.weight
+                    )));
+                }
+                base_item.with_preferred_region(PreferredRegion {
+                    rect,
+                    weight: jpr.weight,
+                })
+            }
+        };
+
+        let item = match &json_item.metadata {
+            None => base_item,
+            Some(metadata) => base_item.with_metadata(metadata.clone()),
+        };
+
+        let demand = match json_item.demand {
+            0 => UNLIMITED_ITEM_QTY,
+            demand => demand as usize,
+        };
+
+        Ok((item, demand))
     }
 
-    fn parse_bin(&self, json_bin: &JsonBin, bin_id: usize) -> (Bin, usize) {
+    fn parse_bin(&self, json_bin: &JsonBin, bin_id: usize) -> Result<(Bin, usize), ParseError> {
+        let owner = ShapeOwner::Bin(bin_id);
         let bin_outer = match &json_bin.shape {
             JsonShape::Rectangle { width, height } => {
-                SimplePolygon::from(AARectangle::new(0.0, 0.0, *width, *height))
-            }
-            JsonShape::SimplePolygon(jsp) => {
-                convert_json_simple_poly(jsp, self.poly_simpl_config, PolySimplMode::Deflate)
-            }
-            JsonShape::Polygon(jp) => {
-                convert_json_simple_poly(&jp.outer, self.poly_simpl_config, PolySimplMode::Deflate)
+                convert_json_rectangle(*width, *height, owner)?
             }
-            JsonShape::MultiPolygon(_) => {
-                unimplemented!("No support for multipolygon shapes yet")
+            JsonShape::Circle { radius } => {
+                convert_json_circle(*radius, PolySimplMode::Deflate, owner)?
             }
+            JsonShape::SimplePolygon(jsp) => convert_json_simple_poly(
+                jsp,
+                self.poly_simpl_config,
+                PolySimplMode::Deflate,
+                owner,
+            )?,
+            JsonShape::Polygon(jp) => convert_json_simple_poly(
+                &jp.outer,
+                self.poly_simpl_config,
+                PolySimplMode::Deflate,
+                owner,
+            )?,
+            JsonShape::MultiPolygon(_) => return Err(ParseError::UnsupportedShape("multipolygon")),
         };
 
         let bin_holes = match &json_bin.shape {
-            JsonShape::SimplePolygon(_) | JsonShape::Rectangle { .. } => vec![],
+            JsonShape::SimplePolygon(_)
+            | JsonShape::Rectangle { .. }
+            | JsonShape::Circle { .. } => vec![],
             JsonShape::Polygon(jp) => jp
                 .inner
                 .iter()
                 .map(|jsp| {
-                    convert_json_simple_poly(jsp, self.poly_simpl_config, PolySimplMode::Inflate)
+                    convert_json_simple_poly(
+                        jsp,
+                        self.poly_simpl_config,
+                        PolySimplMode::Inflate,
+                        owner,
+                    )
                 })
-                .collect_vec(),
-            JsonShape::MultiPolygon(_) => {
-                unimplemented!("No support for multipolygon shapes yet")
-            }
+                .collect::<Result<_, _>>()?,
+            JsonShape::MultiPolygon(_) => return Err(ParseError::UnsupportedShape("multipolygon")),
         };
 
         let material_value =
             (bin_outer.area() - bin_holes.iter().map(|hole| hole.area()).sum::<fsize>()) as u64;
 
-        assert!(
-            json_bin.zones.iter().all(|zone| zone.quality < N_QUALITIES),
-            "Quality must be less than N_QUALITIES"
-        );
+        if let Some(zone) = json_bin.zones.iter().find(|z| z.quality >= N_QUALITIES) {
+            return Err(ParseError::InvalidQuality(zone.quality));
+        }
 
         let quality_zones = (0..N_QUALITIES)
             .map(|quality| {
@@ -217,24 +317,47 @@ impl Parser {
                     .filter(|zone| zone.quality == quality)
                     .map(|zone| match &zone.shape {
                         JsonShape::Rectangle { width, height } => {
-                            SimplePolygon::from(AARectangle::new(0.0, 0.0, *width, *height))
+                            convert_json_rectangle(*width, *height, owner)
+                        }
+                        JsonShape::Circle { radius } => {
+                            convert_json_circle(*radius, PolySimplMode::Inflate, owner)
                         }
                         JsonShape::SimplePolygon(jsp) => convert_json_simple_poly(
                             jsp,
                             self.poly_simpl_config,
                             PolySimplMode::Inflate,
+                            owner,
                         ),
-                        JsonShape::Polygon(_) => {
-                            unimplemented!("No support for polygon to simplepolygon conversion yet")
-                        }
+                        JsonShape::Polygon(_) => Err(ParseError::UnsupportedShape("polygon")),
                         JsonShape::MultiPolygon(_) => {
-                            unimplemented!("No support for multipolygon shapes yet")
+                            Err(ParseError::UnsupportedShape("multipolygon"))
                         }
                     })
-                    .collect_vec();
-                InferiorQualityZone::new(quality, zones)
+                    .collect::<Result<_, _>>()?;
+                Ok(InferiorQualityZone::new(quality, zones))
             })
-            .collect_vec();
+            .collect::<Result<_, _>>()?;
+
+        let soft_zones = json_bin
+            .soft_zones
+            .iter()
+            .map(|shape| match shape {
+                JsonShape::Rectangle { width, height } => {
+                    convert_json_rectangle(*width, *height, owner)
+                }
+                JsonShape::Circle { radius } => {
+                    convert_json_circle(*radius, PolySimplMode::Inflate, owner)
+                }
+                JsonShape::SimplePolygon(jsp) => convert_json_simple_poly(
+                    jsp,
+                    self.poly_simpl_config,
+                    PolySimplMode::Inflate,
+                    owner,
+                ),
+                JsonShape::Polygon(_) => Err(ParseError::UnsupportedShape("polygon")),
+                JsonShape::MultiPolygon(_) => Err(ParseError::UnsupportedShape("multipolygon")),
+            })
+            .collect::<Result<_, _>>()?;
 
         let base_bin = Bin::new(
             bin_id,
@@ -243,6 +366,7 @@ impl Parser {
             Transformation::empty(),
             bin_holes,
             quality_zones,
+            soft_zones,
             self.cde_config,
         );
 
@@ -254,9 +378,259 @@ impl Parser {
             }
         };
 
+        let bin = match &json_bin.margin {
+            None => bin,
+            Some(json_margin) => {
+                let margin = match json_margin {
+                    JsonMargin::Uniform(m) => BinMargin::uniform(*m),
+                    JsonMargin::PerSide {
+                        left,
+                        right,
+                        bottom,
+                        top,
+                    } => BinMargin {
+                        left: *left,
+                        right: *right,
+                        bottom: *bottom,
+                        top: *top,
+                    },
+                };
+                let sides = [margin.left, margin.right, margin.bottom, margin.top];
+                if sides.iter().any(|m| m.is_nan() || *m < 0.0) {
+                    return Err(ParseError::InvalidMargin(format!(
+                        "margins of bin {bin_id} must be positive: {margin:?}"
+                    )));
+                }
+                bin.with_margin(&margin).ok_or_else(|| {
+                    ParseError::InvalidMargin(format!(
+                        "margins of bin {bin_id} leave no usable region: {margin:?}"
+                    ))
+                })?
+            }
+        };
+
         let stock = json_bin.stock.unwrap_or(u64::MAX) as usize;
 
-        (bin, stock)
+        Ok((bin, stock))
+    }
+}
+
+fn build_instance(
+    name: &str,
+    items: Vec<(Item, usize)>,
+    bins: Option<Vec<(Bin, usize)>>,
+    json_strip: Option<&JsonStrip>,
+    reject_unfit_items: bool,
+    cde_config: CDEConfig,
+) -> Result<Instance, ParseError> {
+    let instance: Instance = match (bins, json_strip) {
+        (Some(bins), None) => {
+            let mut bpi = BPInstance::new(items, bins);
+            if cde_config.quadtree_depth_auto {
+                //the depth of every bin's quadtree depends on the number of items, only known now
+                let n_items = bpi.total_item_qty();
+                for (bin, _) in bpi.bins.iter_mut() {
+                    *bin = bin.with_cde_config(cde_config.auto(n_items, &bin.bbox()));
+                }
+            }
+            bpi.into()
+        }
+        (None, Some(json_strip)) => {
+            SPInstance::new(items, json_strip.height, json_strip.width).into()
+        }
+        (Some(_), Some(_)) => return Err(ParseError::AmbiguousContainer),
+        (None, None) => return Err(ParseError::MissingContainer),
+    };
+
+    match &instance {
+        Instance::SP(spi) => {
+            log!(
+                Level::Info,
+                "[PARSE] strip packing instance \"{}\": {} items ({} unique), {} strip height",
+                name,
+                spi.total_item_qty(),
+                spi.items.len(),
+                spi.strip_height
+            );
+        }
+        Instance::BP(bpi) => {
+            log!(
+                Level::Info,
+                "[PARSE] bin packing instance \"{}\": {} items ({} unique), {} bins ({} unique)",
+                name,
+                bpi.total_item_qty(),
+                bpi.items.len(),
+                bpi.bins.iter().map(|(_, qty)| *qty).sum::<usize>(),
+                bpi.bins.len()
+            );
+        }
+    }
+
+    let unfit_items = unfit_items(&instance);
+    for item_id in &unfit_items {
+        log!(
+            Level::Warn,
+            "[PARSE] item {} does not fit in any container",
+            item_id
+        );
+    }
+    match (reject_unfit_items, unfit_items.is_empty()) {
+        (true, false) => Err(ParseError::ItemTooLarge { ids: unfit_items }),
+        _ => Ok(instance),
+    }
+}
+
+/// Ids of the demanded items whose bounding box does not fit in any container, for any of their allowed rotations.
+/// Continuous rotations are sampled, with some slack so that items which do fit are never reported.
+fn unfit_items(instance: &Instance) -> Vec<usize> {
+    let containers = match instance {
+        Instance::SP(spi) => vec![(
+            spi.fixed_strip_width.unwrap_or(fsize::INFINITY),
+            spi.strip_height,
+        )],
+        Instance::BP(bpi) => bpi
+            .bins
+            .iter()
+            .filter(|(_, stock)| *stock > 0)
+            .map(|(bin, _)| {
+                let bbox = bin.usable.bbox();
+                (bbox.width(), bbox.height())
+            })
+            .collect_vec(),
+    };
+    let fits = |(w, h): (fsize, fsize), slack: fsize| {
+        containers
+            .iter()
+            .any(|&(c_w, c_h)| w - slack <= c_w && h - slack <= c_h)
+    };
+
+    instance
+        .items()
+        .iter()
+        .filter(|(_, qty)| *qty > 0)
+        .map(|(item, _)| item)
+        .filter(|item| match &item.allowed_rotation {
+            AllowedRotation::None => !fits(rotated_bbox_dims(&item.shape, 0.0), 0.0),
+            AllowedRotation::Discrete(rotations) => !rotations
+                .iter()
+                .any(|&r| fits(rotated_bbox_dims(&item.shape, r), 0.0)),
+            AllowedRotation::Continuous => {
+                //a quarter turn swaps width and height, so only the first quadrant has to be sampled.
+                //between two samples, the dimensions change by at most the diameter times the step
+                let step = PI / 2.0 / N_FIT_ROTATION_SAMPLES as fsize;
+                let slack = item.shape.diameter() * step;
+                !(0..N_FIT_ROTATION_SAMPLES).any(|i| {
+                    let (w, h) = rotated_bbox_dims(&item.shape, i as fsize * step);
+                    fits((w, h), slack) || fits((h, w), slack)
+                })
+            }
+        })
+        .map(|item| item.id)
+        .collect_vec()
+}
+
+/// Width and height of the bounding box of the shape, rotated by `angle` radians
+fn rotated_bbox_dims(shape: &SimplePolygon, angle: fsize) -> (fsize, fsize) {
+    let bbox = shape
+        .transform_clone(&Transformation::from_rotation(angle))
+        .bbox();
+    (bbox.width(), bbox.height())
+}
+
+/// Visits the fields of a JSON instance, see [`Parser::parse_streaming`]
+struct InstanceVisitor<'a> {
+    parser: &'a Parser,
+}
+
+impl<'de> Visitor<'de> for InstanceVisitor<'_> {
+    type Value = Instance;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON instance")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Instance, A::Error> {
+        let parser = self.parser;
+        let (mut name, mut items, mut bins, mut strip) = (None, None, None, None);
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "Name" => name = Some(map.next_value::<String>()?),
+                "Items" => {
+                    items = Some(map.next_value_seed(ConvertingSeq::new(
+                        |json_item: &JsonItem, item_id| parser.parse_item(json_item, item_id),
+                    ))?)
+                }
+                "Objects" => {
+                    bins = Some(map.next_value_seed(ConvertingSeq::new(
+                        |json_bin: &JsonBin, bin_id| parser.parse_bin(json_bin, bin_id),
+                    ))?)
+                }
+                "Strip" => strip = Some(map.next_value::<JsonStrip>()?),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        let name = name.ok_or_else(|| de::Error::missing_field("Name"))?;
+        let items = items.ok_or_else(|| de::Error::missing_field("Items"))?;
+
+        build_instance(
+            &name,
+            items,
+            bins,
+            strip.as_ref(),
+            parser.reject_unfit_items,
+            parser.cde_config,
+        )
+        .map_err(de::Error::custom)
+    }
+}
+
+/// Deserializes a sequence of `T`s, converting every element as soon as it is deserialized
+struct ConvertingSeq<T, F> {
+    convert: F,
+    _marker: PhantomData<T>,
+}
+
+impl<T, F> ConvertingSeq<T, F> {
+    fn new(convert: F) -> Self {
+        Self {
+            convert,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T, U, F> DeserializeSeed<'de> for ConvertingSeq<T, F>
+where
+    T: Deserialize<'de>,
+    F: FnMut(&T, usize) -> Result<U, ParseError>,
+{
+    type Value = Vec<U>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Vec<U>, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, T, U, F> Visitor<'de> for ConvertingSeq<T, F>
+where
+    T: Deserialize<'de>,
+    F: FnMut(&T, usize) -> Result<U, ParseError>,
+{
+    type Value = Vec<U>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<Vec<U>, A::Error> {
+        let mut converted = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(element) = seq.next_element::<T>()? {
+            let index = converted.len();
+            converted.push((self.convert)(&element, index).map_err(de::Error::custom)?);
+        }
+        Ok(converted)
     }
 }
 
@@ -265,13 +639,16 @@ pub fn build_solution_from_json(
     instance: &Instance,
     json_layouts: &[JsonLayout],
     cde_config: CDEConfig,
-) -> Solution {
+) -> Result<Solution, ParseError> {
     match instance {
         Instance::BP(bp_i) => build_bin_packing_solution(bp_i, json_layouts),
-        Instance::SP(sp_i) => {
-            assert_eq!(json_layouts.len(), 1);
-            build_strip_packing_solution(sp_i, &json_layouts[0], cde_config)
-        }
+        Instance::SP(sp_i) => match json_layouts {
+            [json_layout] => build_strip_packing_solution(sp_i, json_layout, cde_config),
+            _ => Err(ParseError::SolutionMismatch(format!(
+                "strip packing solution should contain exactly one layout, found {}",
+                json_layouts.len()
+            ))),
+        },
     }
 }
 
@@ -279,10 +656,12 @@ pub fn build_strip_packing_solution(
     instance: &SPInstance,
     json_layout: &JsonLayout,
     cde_config: CDEConfig,
-) -> Solution {
+) -> Result<Solution, ParseError> {
     let mut problem = match json_layout.container {
         JsonContainer::Bin { .. } => {
-            panic!("Strip packing solution should not contain layouts with references to an Object")
+            return Err(ParseError::SolutionMismatch(
+                "strip packing solution should not contain layouts with references to a bin".into(),
+            ))
         }
         JsonContainer::Strip { width, height: _ } => {
             SPProblem::new(instance.clone(), width, cde_config)
@@ -290,7 +669,13 @@ pub fn build_strip_packing_solution(
     };
 
     for json_item in json_layout.placed_items.iter() {
-        let item = instance.item(json_item.index);
+        let item = instance
+            .items()
+            .get(json_item.index)
+            .map(|(item, _)| item)
+            .ok_or_else(|| {
+                ParseError::SolutionMismatch(format!("no item with index {}", json_item.index))
+            })?;
         let json_rotation = json_item.transformation.rotation;
         let json_translation = json_item.transformation.translation;
 
@@ -313,19 +698,31 @@ pub fn build_strip_packing_solution(
         problem.flush_changes();
     }
 
-    problem.create_solution(None)
+    Ok(problem.create_solution(None))
 }
 
-pub fn build_bin_packing_solution(instance: &BPInstance, json_layouts: &[JsonLayout]) -> Solution {
+pub fn build_bin_packing_solution(
+    instance: &BPInstance,
+    json_layouts: &[JsonLayout],
+) -> Result<Solution, ParseError> {
     let mut problem = BPProblem::new(instance.clone());
 
     for json_layout in json_layouts {
-        let bin = match json_layout.container {
-            JsonContainer::Bin { index } => &instance.bins[index].0,
-            JsonContainer::Strip { .. } => {
-                panic!("Bin packing solution should not contain layouts with references to a Strip")
-            }
-        };
+        let bin =
+            match json_layout.container {
+                JsonContainer::Bin { index } => match instance.bins.get(index) {
+                    Some((bin, _)) => bin,
+                    None => {
+                        return Err(ParseError::SolutionMismatch(format!(
+                            "no bin with index {index}"
+                        )))
+                    }
+                },
+                JsonContainer::Strip { .. } => return Err(ParseError::SolutionMismatch(
+                    "bin packing solution should not contain layouts with references to a strip"
+                        .into(),
+                )),
+            };
         //Create the layout by inserting the first item
 
         //Find the template layout matching the bin id in the JSON solution
@@ -333,13 +730,24 @@ pub fn build_bin_packing_solution(instance: &BPInstance, json_layouts: &[JsonLay
             .template_layouts()
             .iter()
             .position(|tl| tl.bin.id == bin.id)
-            .expect("no template layout found for bin");
+            .ok_or_else(|| {
+                ParseError::SolutionMismatch(format!("no template layout found for bin {}", bin.id))
+            })?;
 
         let json_first_item = json_layout
             .placed_items
             .first()
-            .expect("no items in layout");
-        let first_item = instance.item(json_first_item.index);
+            .ok_or_else(|| ParseError::SolutionMismatch("no items in layout".into()))?;
+        let first_item = instance
+            .items()
+            .get(json_first_item.index)
+            .map(|(item, _)| item)
+            .ok_or_else(|| {
+                ParseError::SolutionMismatch(format!(
+                    "no item with index {}",
+                    json_first_item.index
+                ))
+            })?;
         let abs_transform = DTransformation::new(
             json_first_item.transformation.rotation,
             json_first_item.transformation.translation,
@@ -362,7 +770,13 @@ pub fn build_bin_packing_solution(instance: &BPInstance, json_layouts: &[JsonLay
 
         //Insert the rest of the items
         for json_item in json_layout.placed_items.iter().skip(1) {
-            let item = instance.item(json_item.index);
+            let item = instance
+                .items()
+                .get(json_item.index)
+                .map(|(item, _)| item)
+                .ok_or_else(|| {
+                    ParseError::SolutionMismatch(format!("no item with index {}", json_item.index))
+                })?;
             let json_rotation = json_item.transformation.rotation;
             let json_translation = json_item.transformation.translation;
 
@@ -385,7 +799,7 @@ pub fn build_bin_packing_solution(instance: &BPInstance, json_layouts: &[JsonLay
         }
     }
 
-    problem.create_solution(None)
+    Ok(problem.create_solution(None))
 }
 
 /// Composes a `JsonSolution` from a `Solution` and an `Instance`.
@@ -401,7 +815,13 @@ pub fn compose_json_solution(
             let container = match &instance {
                 Instance::BP(_bpi) => JsonContainer::Bin { index: sl.bin.id },
                 Instance::SP(spi) => JsonContainer::Strip {
-                    width: sl.bin.bbox().width(),
+                    //width of the strip in the frame of the instance, it might have been rotated
+                    width: sl
+                        .bin
+                        .outer
+                        .transform_clone(&sl.bin.pretransform.clone().inverse())
+                        .bbox()
+                        .width(),
                     height: spi.strip_height,
                 },
             };
@@ -426,6 +846,7 @@ pub fn compose_json_solution(
                             rotation: abs_transf.rotation(),
                             translation: abs_transf.translation(),
                         },
+                        metadata: item.metadata.clone(),
                     }
                 })
                 .collect::<Vec<JsonPlacedItem>>();
@@ -449,8 +870,9 @@ fn convert_json_simple_poly(
     s_json_shape: &JsonSimplePoly,
     simpl_config: PolySimplConfig,
     simpl_mode: PolySimplMode,
-) -> SimplePolygon {
-    let shape = SimplePolygon::new(json_simple_poly_to_points(s_json_shape));
+    owner: ShapeOwner,
+) -> Result<SimplePolygon, ParseError> {
+    let shape = SimplePolygon::new(json_simple_poly_to_points(s_json_shape, owner)?);
 
     let shape = match simpl_config {
         PolySimplConfig::Enabled { tolerance } => {
@@ -459,110 +881,83 @@ fn convert_json_simple_poly(
         PolySimplConfig::Disabled => shape,
     };
 
-    shape
+    Ok(shape)
 }
 
-fn json_simple_poly_to_points(jsp: &JsonSimplePoly) -> Vec<Point> {
-    //Strip the last vertex if it is the same as the first one
-    let n_vertices = match jsp.0[0] == jsp.0[jsp.0.len() - 1] {
-        true => jsp.0.len() - 1,
-        false => jsp.0.len(),
-    };
-
-    (0..n_vertices).map(|i| Point::from(jsp.0[i])).collect_vec()
-}
-
-pub fn internal_to_absolute_transform(
-    placed_item_transf: &DTransformation,
-    item_pretransf: &Transformation,
-    bin_pretransf: &Transformation,
-) -> Transformation {
-    //1. apply the item pretransform
-    //2. apply the placement transformation
-    //3. undo the bin pretransformation
-
-    Transformation::empty()
-        .transform(item_pretransf)
-        .transform_from_decomposed(placed_item_transf)
-        .transform(&bin_pretransf.clone().inverse())
-}
-
-pub fn absolute_to_internal_transform(
-    abs_transf: &DTransformation,
-    item_pretransf: &Transformation,
-    bin_pretransf: &Transformation,
-) -> Transformation {
-    //1. undo the item pretransform
-    //2. do the absolute transformation
-    //3. apply the bin pretransform
-
-    Transformation::empty()
-        .transform(&item_pretransf.clone().inverse())
-        .transform_from_decomposed(abs_transf)
-        .transform(bin_pretransf)
-}
-
-pub fn pretransform_bin(bin: &Bin, extra_pretransf: &Transformation) -> Bin {
-    let Bin {
-        id,
-        outer,
-        value,
-        pretransform,
-        holes,
-        quality_zones,
-        ..
-    } = bin;
-
-    Bin::new(
-        *id,
-        outer.transform_clone(&extra_pretransf),
-        *value,
-        pretransform.clone().transform(&extra_pretransf),
-        holes
-            .iter()
-            .map(|h| h.transform_clone(&extra_pretransf))
-            .collect(),
-        quality_zones
-            .iter()
-            .flatten()
-            .map(|qz| {
-                InferiorQualityZone::new(
-                    qz.quality,
-                    qz.zones
-                        .iter()
-                        .map(|z| z.transform_clone(&extra_pretransf))
-                        .collect(),
-                )
-            })
-            .collect(),
-        bin.base_cde.config(),
-    )
+fn convert_json_rectangle(
+    width: fsize,
+    height: fsize,
+    owner: ShapeOwner,
+) -> Result<SimplePolygon, ParseError> {
+    match width > 0.0 && height > 0.0 {
+        true => Ok(SimplePolygon::from(AARectangle::new(
+            0.0, 0.0, width, height,
+        ))),
+        false => Err(ParseError::InvalidPolygon {
+            owner,
+            defect: PolygonDefect::InvalidRectangle { width, height },
+        }),
+    }
 }
 
-pub fn pretransform_item(item: &Item, extra_pretransf: &Transformation) -> Item {
-    let Item {
-        id,
-        shape,
-        allowed_rotation,
-        base_quality,
-        value,
-        pretransform,
-        surrogate_config,
-        ..
-    } = item;
-
-    Item::new(
-        *id,
-        shape.transform_clone(extra_pretransf),
-        allowed_rotation.clone(),
-        *base_quality,
-        *value,
-        pretransform.clone().transform(extra_pretransf),
-        *surrogate_config,
-    )
+/// Approximates a circle by a regular polygon, with the bounding box of the circle starting at (0, 0).
+/// [`PolySimplMode::Inflate`] returns a polygon enclosing the circle, [`PolySimplMode::Deflate`] one enclosed by it.
+fn convert_json_circle(
+    radius: fsize,
+    mode: PolySimplMode,
+    owner: ShapeOwner,
+) -> Result<SimplePolygon, ParseError> {
+    if radius <= 0.0 || !radius.is_finite() {
+        return Err(ParseError::InvalidPolygon {
+            owner,
+            defect: PolygonDefect::InvalidRadius(radius),
+        });
+    }
+    let n = N_CIRCLE_VERTICES;
+    let vertex_radius = match mode {
+        PolySimplMode::Inflate => radius / (PI / n as fsize).cos(),
+        PolySimplMode::Deflate => radius,
+    };
+    let points = (0..n)
+        .map(|i| {
+            let angle = 2.0 * PI * i as fsize / n as fsize;
+            Point(
+                radius + vertex_radius * angle.cos(),
+                radius + vertex_radius * angle.sin(),
+            )
+        })
+        .collect_vec();
+    Ok(SimplePolygon::new(points))
 }
 
-pub fn centering_transformation(shape: &SimplePolygon) -> DTransformation {
-    let Point(cx, cy) = shape.centroid();
-    DTransformation::new(0.0, (-cx, -cy))
+/// Extracts the points of a JSON polygon, checking the preconditions of [`SimplePolygon::new`] and that no edges intersect
+fn json_simple_poly_to_points(
+    jsp: &JsonSimplePoly,
+    owner: ShapeOwner,
+) -> Result<Vec<Point>, ParseError> {
+    //Strip the last vertex if it is the same as the first one
+    let n_vertices = match jsp.0.first() == jsp.0.last() {
+        true => jsp.0.len().saturating_sub(1),
+        false => jsp.0.len(),
+    };
+    let points = (0..n_vertices).map(|i| Point::from(jsp.0[i])).collect_vec();
+
+    let defect = if points.len() < 3 {
+        Some(PolygonDefect::TooFewPoints(points.len()))
+    } else if points.iter().any(|p| !p.0.is_finite() || !p.1.is_finite()) {
+        Some(PolygonDefect::NonFiniteCoordinates)
+    } else if points.iter().unique().count() != points.len() {
+        Some(PolygonDefect::DuplicatePoints)
+    } else if SimplePolygon::calculate_area(&points) == 0.0 {
+        Some(PolygonDefect::ZeroArea)
+    } else {
+        None
+    };
+    match defect {
+        Some(defect) => Err(ParseError::InvalidPolygon { owner, defect }),
+        None => match is_simple(&points) {
+            true => Ok(points),
+            false => Err(ParseError::NonSimplePolygon { owner }),
+        },
+    }
 }
@@ -0,0 +1,121 @@
+use crate::entities::bin::Bin;
+use crate::entities::item::Item;
+use crate::entities::quality_zone::InferiorQualityZone;
+use crate::geometry::d_transformation::DTransformation;
+use crate::geometry::geo_traits::{Shape, Transformable};
+use crate::geometry::primitives::point::Point;
+use crate::geometry::primitives::simple_polygon::SimplePolygon;
+use crate::geometry::transformation::Transformation;
+
+pub fn internal_to_absolute_transform(
+    placed_item_transf: &DTransformation,
+    item_pretransf: &Transformation,
+    bin_pretransf: &Transformation,
+) -> Transformation {
+    //1. apply the item pretransform
+    //2. apply the placement transformation
+    //3. undo the bin pretransformation
+
+    Transformation::empty()
+        .transform(item_pretransf)
+        .transform_from_decomposed(placed_item_transf)
+        .transform(&bin_pretransf.clone().inverse())
+}
+
+pub fn absolute_to_internal_transform(
+    abs_transf: &DTransformation,
+    item_pretransf: &Transformation,
+    bin_pretransf: &Transformation,
+) -> Transformation {
+    //1. undo the item pretransform
+    //2. do the absolute transformation
+    //3. apply the bin pretransform
+
+    Transformation::empty()
+        .transform(&item_pretransf.clone().inverse())
+        .transform_from_decomposed(abs_transf)
+        .transform(bin_pretransf)
+}
+
+pub fn pretransform_bin(bin: &Bin, extra_pretransf: &Transformation) -> Bin {
+    let Bin {
+        id,
+        outer,
+        value,
+        pretransform,
+        holes,
+        quality_zones,
+        soft_zones,
+        ..
+    } = bin;
+
+    Bin::new(
+        *id,
+        outer.transform_clone(extra_pretransf),
+        *value,
+        pretransform.clone().transform(extra_pretransf),
+        holes
+            .iter()
+            .map(|h| h.transform_clone(extra_pretransf))
+            .collect(),
+        quality_zones
+            .iter()
+            .flatten()
+            .map(|qz| {
+                InferiorQualityZone::new(
+                    qz.quality,
+                    qz.zones
+                        .iter()
+                        .map(|z| z.transform_clone(extra_pretransf))
+                        .collect(),
+                )
+            })
+            .collect(),
+        soft_zones
+            .iter()
+            .map(|z| z.transform_clone(extra_pretransf))
+            .collect(),
+        bin.base_cde.config(),
+    )
+    .with_edge_margins(bin.edge_margins.clone())
+    .expect("margins remain valid under rigid transformations")
+}
+
+pub fn pretransform_item(item: &Item, extra_pretransf: &Transformation) -> Item {
+    let Item {
+        id,
+        shape,
+        allowed_rotation,
+        base_quality,
+        value,
+        pretransform,
+        surrogate_config,
+        preferred_region,
+        metadata,
+        ..
+    } = item;
+
+    //the preferred region is defined in the coordinates of the container, unaffected by the pretransform of the item
+    let pretransformed = Item::new(
+        *id,
+        shape.transform_clone(extra_pretransf),
+        allowed_rotation.clone(),
+        *base_quality,
+        *value,
+        pretransform.clone().transform(extra_pretransf),
+        *surrogate_config,
+    );
+    let pretransformed = match metadata {
+        None => pretransformed,
+        Some(metadata) => pretransformed.with_metadata(metadata.clone()),
+    };
+    match preferred_region {
+        None => pretransformed,
+        Some(pr) => pretransformed.with_preferred_region(pr.clone()),
+    }
+}
+
+pub fn centering_transformation(shape: &SimplePolygon) -> DTransformation {
+    let Point(cx, cy) = shape.centroid();
+    DTransformation::new(0.0, (-cx, -cy))
+}
@@ -0,0 +1,97 @@
+use std::fs;
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::geometry::fail_fast::sp_surrogate::SPSurrogate;
+use crate::geometry::primitives::simple_polygon::SimplePolygon;
+use crate::util::config::SPSurrogateConfig;
+use crate::util::fnv::FnvHasher;
+
+/// Counter to give every temporary file written by this process a unique name
+static N_WRITES: AtomicUsize = AtomicUsize::new(0);
+
+/// On-disk cache of the surrogates of shapes, see [`Parser::surrogate_cache`](crate::io::parser::Parser::surrogate_cache).
+/// Surrogate generation is deterministic, so repeated imports of the same items can reuse the surrogates of a previous run.
+/// Every entry is a JSON file in the cache directory, keyed by the points of the shape, the [`SPSurrogateConfig`] and the version of the crate.
+/// Entries generated with a different configuration are never reused.
+#[derive(Clone, Debug)]
+pub struct SurrogateCache {
+    dir: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    config: SPSurrogateConfig,
+    surrogate: SPSurrogate,
+}
+
+impl SurrogateCache {
+    /// Caches the surrogates in `dir`, which is created if it does not exist yet
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the surrogate of `shape`, read from the cache if present, otherwise it is generated and stored.
+    /// Failures to read or write the cache are logged, after which the surrogate is generated as usual.
+    pub fn surrogate(&self, shape: &SimplePolygon, config: SPSurrogateConfig) -> SPSurrogate {
+        let path = self
+            .dir
+            .join(format!("{:016x}.json", entry_key(shape, config)));
+        if path.exists() {
+            let entry = fs::read(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| {
+                    serde_json::from_slice::<CacheEntry>(&bytes).map_err(|e| e.to_string())
+                });
+            match entry {
+                Ok(entry) if entry.config == config => return entry.surrogate,
+                Ok(_) => warn!(
+                    "[PARSE] surrogate cache entry {path:?} has a different config, regenerating"
+                ),
+                Err(e) => warn!("[PARSE] could not read surrogate cache entry {path:?}: {e}"),
+            }
+        }
+
+        let surrogate = SPSurrogate::new(shape, config);
+        let entry = CacheEntry { config, surrogate };
+        if let Err(e) = self.write(&path, &entry) {
+            warn!("[PARSE] could not write surrogate cache entry {path:?}: {e}");
+        }
+        entry.surrogate
+    }
+
+    /// Writes to a temporary file first, so concurrent imports never read a partially written entry
+    fn write(&self, path: &Path, entry: &CacheEntry) -> std::io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let tmp_path = path.with_extension(format!(
+            "{}.{}.tmp",
+            std::process::id(),
+            N_WRITES.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&tmp_path, serde_json::to_vec(entry)?)?;
+        fs::rename(&tmp_path, path)
+    }
+}
+
+fn entry_key(shape: &SimplePolygon, config: SPSurrogateConfig) -> u64 {
+    let mut hasher = FnvHasher::default();
+    hasher.write(env!("CARGO_PKG_VERSION").as_bytes());
+    hasher.write(&(shape.number_of_points() as u64).to_le_bytes());
+    for p in shape.points.iter() {
+        hasher.write(&p.0.to_le_bytes());
+        hasher.write(&p.1.to_le_bytes());
+    }
+    hasher.write(&config.pole_coverage_goal.to_le_bytes());
+    hasher.write(&(config.max_poles as u64).to_le_bytes());
+    hasher.write(&(config.n_ff_poles as u64).to_le_bytes());
+    hasher.write(&(config.n_ff_piers as u64).to_le_bytes());
+    hasher.finish()
+}
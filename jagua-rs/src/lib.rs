@@ -3,6 +3,11 @@
 //!
 //!
 //! This crate can be configured to use single or double precision for floating points (see [fsize]).
+//!
+//! Without the (default) feature **std**, the crate is `no_std` (but requires `alloc`): the parser and the export of solutions are unavailable.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 /// Everything collision detection engine related
 pub mod collision_detection;
@@ -26,13 +31,13 @@ cfg_if::cfg_if! {
         #[allow(non_camel_case_types)]
         pub type fsize = f64;
         /// π as [fsize].
-        pub const PI : fsize = std::f64::consts::PI;
+        pub const PI : fsize = core::f64::consts::PI;
     } else {
         /// The floating point type used in jagua-rs.
         /// ```f32``` by default, ```f64``` when feature **double-precision** is enabled.
         #[allow(non_camel_case_types)]
         pub type fsize = f32;
         /// π as [fsize].
-        pub const PI: fsize = std::f32::consts::PI;
+        pub const PI: fsize = core::f32::consts::PI;
     }
 }
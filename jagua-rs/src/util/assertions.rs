@@ -1,6 +1,7 @@
+use alloc::{boxed::Box, vec, vec::Vec};
+
 use itertools::Itertools;
 use log::error;
-use std::collections::HashSet;
 
 use crate::collision_detection::cd_engine::CDEngine;
 use crate::collision_detection::hazard::Hazard;
@@ -19,10 +20,14 @@ use crate::entities::layout::Layout;
 use crate::entities::layout::LayoutSnapshot;
 use crate::entities::problems::problem_generic::ProblemGeneric;
 use crate::entities::solution::Solution;
-use crate::geometry::geo_traits::{Shape, Transformable};
+use crate::geometry::fail_fast::sp_surrogate::SPSurrogate;
+use crate::geometry::geo_enums::BorderPosition;
+use crate::geometry::geo_traits::{DistanceFrom, Shape, Transformable};
 use crate::geometry::primitives::aa_rectangle::AARectangle;
+use crate::geometry::primitives::simple_polygon::SimplePolygon;
 use crate::geometry::transformation::Transformation;
-use crate::util;
+use crate::util::fnv::FnvIndexSet;
+use crate::util::fpa::FPA;
 
 //Various checks to verify correctness of the state of the system
 //Used in debug_assertion!() blocks
@@ -135,8 +140,9 @@ pub fn layout_is_collision_free(layout: &Layout) -> bool {
             hazard_filter::generate_irrelevant_hazards(&combo_filter, layout.cde().all_hazards());
 
         if layout.cde().poly_collides(&pi.shape, &entities_to_ignore) {
-            println!("Collision detected for item {:.?}", pi.item_id);
-            util::print_layout(layout);
+            error!("Collision detected for item {:.?}", pi.item_id);
+            #[cfg(feature = "std")]
+            crate::util::print_layout(layout);
             return false;
         }
     }
@@ -150,8 +156,7 @@ pub fn qt_node_contains_no_deactivated_hazards<'a>(
     stacktrace.push(node);
     let deactivated_hazard = node.hazards.all_hazards().iter().find(|h| !h.active);
     if deactivated_hazard.is_some() {
-        println!("Deactivated hazard found");
-        dbg!(&stacktrace);
+        error!("Deactivated hazard found: {:?}", &stacktrace);
         return (false, stacktrace);
     }
 
@@ -187,8 +192,7 @@ fn qt_node_contains_no_dangling_hazards(node: &QTNode, parent: &QTNode) -> bool
         .all_hazards()
         .iter()
         .map(|h| &h.entity)
-        .unique()
-        .collect_vec();
+        .collect::<FnvIndexSet<_>>();
 
     let dangling_hazards = node
         .hazards
@@ -196,7 +200,7 @@ fn qt_node_contains_no_dangling_hazards(node: &QTNode, parent: &QTNode) -> bool
         .iter()
         .any(|h| !parent_h_entities.contains(&&h.entity));
     if dangling_hazards {
-        println!("Node contains dangling hazard");
+        error!("Node contains dangling hazard");
         return false;
     }
 
@@ -218,7 +222,7 @@ pub fn qt_hz_entity_activation_consistent(cde: &CDEngine) -> bool {
         .all_hazards()
         .iter()
         .map(|h| (h.active, &h.entity))
-        .unique()
+        .collect::<FnvIndexSet<_>>()
     {
         if !hz_entity_same_everywhere(cde.quadtree(), hz_entity, active) {
             return false;
@@ -235,7 +239,7 @@ pub fn hz_entity_same_everywhere(qt_node: &QTNode, hz_entity: &HazardEntity, act
         .find(|h| &h.entity == hz_entity)
     {
         if h.active != active {
-            println!("Hazard entity activation inconsistent");
+            error!("Hazard entity activation inconsistent");
             return false;
         }
     }
@@ -277,25 +281,22 @@ fn qt_nodes_match(qn1: Option<&QTNode>, qn2: Option<&QTNode>) -> bool {
                 .active_hazards()
                 .iter()
                 .map(|h| (&h.entity, h.active, (&h.presence).into()))
-                .collect::<HashSet<(&HazardEntity, bool, u8)>>();
+                .collect::<FnvIndexSet<(&HazardEntity, bool, u8)>>();
 
             let active_haz_2 = hv2
                 .active_hazards()
                 .iter()
                 .map(|h| (&h.entity, h.active, (&h.presence).into()))
-                .collect::<HashSet<(&HazardEntity, bool, u8)>>();
+                .collect::<FnvIndexSet<(&HazardEntity, bool, u8)>>();
 
             let active_in_1_but_not_2 = active_haz_1
                 .difference(&active_haz_2)
-                .collect::<HashSet<_>>();
+                .collect::<FnvIndexSet<_>>();
             let active_in_2_but_not_1 = active_haz_2
                 .difference(&active_haz_1)
-                .collect::<HashSet<_>>();
+                .collect::<FnvIndexSet<_>>();
 
             if !(active_in_1_but_not_2.is_empty() && active_in_2_but_not_1.is_empty()) {
-                let from_1 = **active_in_1_but_not_2.iter().next().unwrap();
-                let from_2 = **active_in_2_but_not_1.iter().next().unwrap();
-                println!("{}", from_1 == from_2);
                 error!(
                     "Active hazards don't match {:?} vs {:?}",
                     active_in_1_but_not_2, active_in_2_but_not_1
@@ -372,16 +373,16 @@ fn hazards_match(chv1: &[Hazard], chv2: &[Hazard]) -> bool {
         .iter()
         .filter(|h| h.active)
         .map(|h| &h.entity)
-        .collect::<HashSet<_>>();
+        .collect::<FnvIndexSet<_>>();
 
     let chv2_active_hazards = chv2
         .iter()
         .filter(|h| h.active)
         .map(|h| &h.entity)
-        .collect::<HashSet<_>>();
+        .collect::<FnvIndexSet<_>>();
 
     if chv1_active_hazards != chv2_active_hazards {
-        println!("Hazard vecs don't match");
+        error!("Hazard vecs don't match");
         return false;
     }
     true
@@ -412,7 +413,7 @@ pub fn hpg_update_no_affected_cells_remain(
             .iter()
             .map(|i| hpg.grid.to_row_col(*i).unwrap())
             .collect_vec();
-        println!(
+        error!(
             "{} detected affected cells, radius: {}",
             undetected_cells_indices.len(),
             hpg.cell_radius
@@ -421,15 +422,14 @@ pub fn hpg_update_no_affected_cells_remain(
             .iter()
             .zip(undetected_row_cols.iter())
         {
-            println!(
+            error!(
                 "cell [{},{}] with {} neighbors",
                 row,
                 col,
                 hpg.grid.get_neighbors(i).map(|j| j != i).iter().count()
             );
-            println!("old {:?}", &old_cells[i].as_ref().unwrap().uni_prox);
-            println!("new {:?}", &hpg.grid.cells[i].as_ref().unwrap().uni_prox);
-            println!()
+            error!("old {:?}", &old_cells[i].as_ref().unwrap().uni_prox);
+            error!("new {:?}", &hpg.grid.cells[i].as_ref().unwrap().uni_prox);
         }
         false
     } else {
@@ -437,6 +437,24 @@ pub fn hpg_update_no_affected_cells_remain(
     }
 }
 
+/// Whether every pole of the surrogate lies inside its shape.
+/// Poles may touch the boundary, they can stick out by the tolerance of [`FPA`], relative to the diameter of the shape.
+pub fn surrogate_poles_inside(shape: &SimplePolygon, surrogate: &SPSurrogate) -> bool {
+    let tolerance = FPA::tolerance() * shape.diameter();
+    for pole in surrogate.poles.iter() {
+        let inside = match shape.distance_from_border(&pole.center) {
+            (BorderPosition::Interior, distance) => pole.radius <= distance + tolerance,
+            (BorderPosition::Boundary, _) => pole.radius <= tolerance,
+            (BorderPosition::Exterior, _) => false,
+        };
+        if !inside {
+            error!("Pole {:?} is not contained in its shape", pole);
+            return false;
+        }
+    }
+    true
+}
+
 /// Checks if the quadrants follow the layout set in [AARectangle::QUADRANT_NEIGHBOR_LAYOUT]
 pub fn quadrants_have_valid_layout(quadrants: &[&AARectangle; 4]) -> bool {
     let layout = AARectangle::QUADRANT_NEIGHBOR_LAYOUT;
@@ -1,16 +1,46 @@
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 use serde::{Deserialize, Serialize};
 
+use crate::collision_detection::hazard::HazardEntity;
 use crate::fsize;
+use crate::geometry::geo_traits::Shape;
+use crate::geometry::primitives::aa_rectangle::AARectangle;
 
 ///Configuration of the Collision Detection Engine
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
 pub struct CDEConfig {
     ///Maximum depth of the quadtree
     pub quadtree_depth: u8,
+    ///Choose the depth of the quadtree for every container from the number of items, see [`CDEConfig::auto`].
+    ///The `quadtree_depth` is then ignored.
+    #[serde(default)]
+    pub quadtree_depth_auto: bool,
+    ///Minimum number of hazards partially present in a quadtree node before it is split into four children.
+    ///Nodes at the maximum depth are never split, regardless of how many hazards they contain.
+    #[serde(default = "default_min_hazards_to_split")]
+    pub quadtree_min_hazards_to_split: usize,
     ///Target number of cells in the Hazard Proximity Grid
     pub hpg_n_cells: usize,
     ///Configuration of the surrogate generation for items
     pub item_surrogate_config: SPSurrogateConfig,
+    ///Items with an area below this threshold do not get a fail-fast surrogate, they are checked using exact tests only
+    #[serde(default)]
+    pub item_surrogate_min_area: fsize,
+    ///Minimum gap required between items and any hazard, shapes closer to a hazard than this are considered colliding.
+    ///With a margin of 0, only overlapping shapes collide.
+    #[serde(default)]
+    pub collision_margin: fsize,
+    ///Minimum gap required between items and the container: the exterior and holes of the bin and its quality zones.
+    ///Replaces the `collision_margin` for these hazards, which then only applies between items. Set to `None` to use the `collision_margin` everywhere.
+    #[serde(default)]
+    pub container_margin: Option<fsize>,
+    ///Shapes which only touch each other (along an edge or in a vertex) are consistently considered non-colliding.
+    ///Shapes are tested as if shrunk by a tolerance of 1e-6 times the size of the engine, in the order of floating point noise,
+    ///so overlaps shallower than this tolerance are accepted, while any deeper one is still detected.
+    ///Only applies when both the `collision_margin` and `container_margin` are 0.
+    #[serde(default)]
+    pub allow_touching: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
@@ -25,7 +55,58 @@ pub struct SPSurrogateConfig {
     pub n_ff_piers: usize,
 }
 
+/// Number of quadtree leaves aimed for per item in the container when the depth is chosen automatically
+const AUTO_QT_LEAVES_PER_ITEM: fsize = 16.0;
+
+/// Range of quadtree depths chosen automatically
+const AUTO_QT_DEPTH_RANGE: (u8, u8) = (3, 10);
+
+impl CDEConfig {
+    /// Returns a copy of the config with the depth of the quadtree chosen for `n_items` items in `container`.
+    /// The depth is the log4 of the number of leaves needed to give every item about 16 of them,
+    /// corrected for the part of the (square) quadtree which lies outside the container.
+    pub fn auto(self, n_items: usize, container: &AARectangle) -> Self {
+        let square_fraction = container.area() / container.inflate_to_square().area();
+        let n_leaves = n_items.max(1) as fsize * AUTO_QT_LEAVES_PER_ITEM / square_fraction;
+        let depth = (n_leaves.ln() / fsize::ln(4.0)).round() as u8;
+        Self {
+            quadtree_depth: depth.clamp(AUTO_QT_DEPTH_RANGE.0, AUTO_QT_DEPTH_RANGE.1),
+            ..self
+        }
+    }
+
+    /// Applies [`CDEConfig::auto`] if `quadtree_depth_auto` is enabled, otherwise returns the config unchanged
+    pub fn for_container(self, n_items: usize, container: &AARectangle) -> Self {
+        match self.quadtree_depth_auto {
+            true => self.auto(n_items, container),
+            false => self,
+        }
+    }
+
+    /// The margin that applies between items and the given hazard
+    pub fn margin_for(&self, hazard: &HazardEntity) -> fsize {
+        match hazard.is_dynamic() {
+            true => self.collision_margin,
+            false => self.container_margin.unwrap_or(self.collision_margin),
+        }
+    }
+
+    /// The smallest and largest margin that apply to any hazard
+    pub fn margin_range(&self) -> (fsize, fsize) {
+        let container_margin = self.container_margin.unwrap_or(self.collision_margin);
+        (
+            fsize::min(self.collision_margin, container_margin),
+            fsize::max(self.collision_margin, container_margin),
+        )
+    }
+}
+
+fn default_min_hazards_to_split() -> usize {
+    1
+}
+
 impl SPSurrogateConfig {
+    /// No fail-fast surrogate at all, see [`SPSurrogate::bbox_only`](crate::geometry::fail_fast::sp_surrogate::SPSurrogate::bbox_only)
     pub fn none() -> Self {
         Self {
             pole_coverage_goal: 0.0,
@@ -0,0 +1,30 @@
+use core::hash::{BuildHasherDefault, Hasher};
+
+use indexmap::IndexSet;
+
+/// [FNV-1a](https://en.wikipedia.org/wiki/Fowler%E2%80%93Noll%E2%80%93Vo_hash_function) hasher.
+/// Unlike [`std::collections::hash_map::DefaultHasher`] it is available without `std` and its output is stable across runs.
+#[derive(Clone, Copy, Debug)]
+pub struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        FnvHasher(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+/// [`IndexSet`] hashed with [`FnvHasher`], usable with and without `std`
+pub type FnvIndexSet<T> = IndexSet<T, BuildHasherDefault<FnvHasher>>;
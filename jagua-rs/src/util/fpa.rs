@@ -1,5 +1,5 @@
-use std::cmp::Ordering;
-use std::fmt::{Debug, Display};
+use core::cmp::Ordering;
+use core::fmt::{Debug, Display};
 
 use almost::AlmostEqual;
 
@@ -49,7 +49,7 @@ impl PartialOrd<Self> for FPA {
 }
 
 impl Display for FPA {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        std::fmt::Display::fmt(&self.0, f)
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(&self.0, f)
     }
 }
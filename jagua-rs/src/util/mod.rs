@@ -1,3 +1,4 @@
+#[cfg(feature = "std")]
 use crate::entities::layout::Layout;
 
 /// Set of functions used throughout assure the correctness of the library.
@@ -6,12 +7,19 @@ pub mod assertions;
 /// Configuration options for the library
 pub mod config;
 
+/// Hashing which does not depend on `std`
+pub mod fnv;
+
 pub mod fpa;
 
 /// Functions to simplify polygons in preprocessing
 pub mod polygon_simplification;
 
+/// Timing of the different phases, enabled by the feature **trace**
+pub mod trace;
+
 ///Prints code to recreate a layout. Intended for debugging purposes.
+#[cfg(feature = "std")]
 pub fn print_layout(layout: &Layout) {
     println!(
         "let mut layout = Layout::new(0, instance.bin({}).clone());",
@@ -1,4 +1,8 @@
-use std::cmp::Ordering;
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
 
 use itertools::Itertools;
 use log::{debug, info};
@@ -53,7 +57,7 @@ struct Corner(pub usize, pub usize, pub usize);
 
 impl Corner {
     pub fn flip(&mut self) {
-        std::mem::swap(&mut self.0, &mut self.2);
+        core::mem::swap(&mut self.0, &mut self.2);
     }
 
     pub fn to_points(self, points: &[Point]) -> [Point; 3] {
@@ -187,6 +191,32 @@ pub fn simplify_shape(
     simpl_shape
 }
 
+/// Densifies a shape (inserting vertices), the inverse of [`simplify_shape`].
+/// Every edge longer than `max_edge_len` is split into equally long parts which do not exceed it, shorter edges are untouched.
+/// The area of the shape is preserved.
+/// Vertices along long edges allow the surrogate to cover long thin shapes better.
+pub fn densify(shape: &SimplePolygon, max_edge_len: fsize) -> SimplePolygon {
+    assert!(max_edge_len > 0.0, "maximum edge length must be positive");
+    let mut points = Vec::with_capacity(shape.number_of_points());
+    for edge in shape.edge_iter() {
+        let n_parts = (edge.diameter() / max_edge_len).ceil().max(1.0) as usize;
+        let Edge { start, end } = edge;
+        points.extend((0..n_parts).map(|i| {
+            let t = i as fsize / n_parts as fsize;
+            Point(
+                start.0 + t * (end.0 - start.0),
+                start.1 + t * (end.1 - start.1),
+            )
+        }));
+    }
+    debug!(
+        "[PS] densified from {} to {} edges (max edge length: {max_edge_len})",
+        shape.number_of_points(),
+        points.len()
+    );
+    SimplePolygon::new(points)
+}
+
 fn calculate_area_delta(
     shape: &[Point],
     candidate: &Candidate,
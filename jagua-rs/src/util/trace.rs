@@ -0,0 +1,56 @@
+#[cfg(feature = "trace")]
+use std::time::Instant;
+
+use log::Level;
+
+/// Log target of all phase timings, to filter or collect them separately from the other logs
+pub const TRACE_TARGET: &str = "jagua_rs::trace";
+
+/// Times a phase (import, surrogate generation, solve...) from its creation until it is dropped.
+/// When the feature **trace** is enabled, the elapsed time is logged to [`TRACE_TARGET`] on drop.
+/// Otherwise, the span is a zero-sized no-op.
+#[must_use = "the phase ends when the span is dropped"]
+pub struct PhaseSpan {
+    #[cfg(feature = "trace")]
+    name: &'static str,
+    #[cfg(feature = "trace")]
+    level: Level,
+    #[cfg(feature = "trace")]
+    start: Instant,
+}
+
+impl PhaseSpan {
+    /// Starts timing a phase, logged at [`Level::Debug`]
+    #[inline(always)]
+    pub fn enter(name: &'static str) -> Self {
+        Self::enter_with_level(name, Level::Debug)
+    }
+
+    /// Starts timing a phase, logged at the given level.
+    /// Use [`Level::Trace`] for fine-grained phases which occur many times, such as per item.
+    #[inline(always)]
+    #[allow(unused_variables)]
+    pub fn enter_with_level(name: &'static str, level: Level) -> Self {
+        Self {
+            #[cfg(feature = "trace")]
+            name,
+            #[cfg(feature = "trace")]
+            level,
+            #[cfg(feature = "trace")]
+            start: Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "trace")]
+impl Drop for PhaseSpan {
+    fn drop(&mut self) {
+        log::log!(
+            target: TRACE_TARGET,
+            self.level,
+            "[TRACE] {} took {:.3}ms",
+            self.name,
+            self.start.elapsed().as_secs_f64() * 1000.0
+        );
+    }
+}
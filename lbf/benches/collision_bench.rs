@@ -0,0 +1,136 @@
+use std::fs::File;
+use std::io::BufReader;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use itertools::Itertools;
+use rand::prelude::SmallRng;
+use rand::SeedableRng;
+
+use jagua_rs::entities::instances::instance_generic::InstanceGeneric;
+use jagua_rs::entities::problems::problem_generic::{LayoutIndex, ProblemGeneric};
+use jagua_rs::geometry::geo_traits::TransformableFrom;
+use jagua_rs::geometry::primitives::aa_rectangle::AARectangle;
+use jagua_rs::io::json_instance::JsonInstance;
+use lbf::samplers::uniform_rect_sampler::UniformAARectSampler;
+
+use crate::util::{create_base_config, N_ITEMS_REMOVED, SWIM_PATH};
+
+criterion_main!(benches);
+criterion_group!(benches, collision_bench);
+
+mod util;
+
+const N_TOTAL_SAMPLES: usize = 100_000;
+const N_SAMPLES_PER_ITER: usize = 1000;
+
+/// Benchmark the collision detection hot path with the default configuration (surrogates and hazard proximity grid enabled).
+/// From a solution created by the LBF optimizer, 5 items are removed and 1000 sampled transformations of them are checked per iteration:
+/// * `surrogate_or_poly_collides`: the check used during the search, surrogate first
+/// * `poly_collides`: the exact check on the transformed shape only
+/// * `collect_poly_collisions`: detecting all colliding entities instead of stopping at the first one
+/// * `poly_collides_interior`: the exact check, for transformations in the central part of the bin, mostly out of reach of its exterior
+///
+/// The bench lives in `lbf` rather than in `jagua-rs`: the layout is created by the LBF optimizer,
+/// which `jagua-rs` cannot depend on, and criterion is only set up for the benches of this crate.
+fn collision_bench(c: &mut Criterion) {
+    let json_instance: JsonInstance =
+        serde_json::from_reader(BufReader::new(File::open(SWIM_PATH).unwrap())).unwrap();
+    let config = create_base_config();
+    let instance = util::create_instance(
+        &json_instance,
+        config.cde_config,
+        config.poly_simpl_tolerance,
+    );
+    let (problem, selected_pi_uids) =
+        util::create_blf_problem(instance.clone(), config, N_ITEMS_REMOVED);
+
+    let layout = problem.get_layout(LayoutIndex::Real(0));
+    let sampler = UniformAARectSampler::new(layout.bin.bbox(), instance.item(0));
+    let mut rng = SmallRng::seed_from_u64(0);
+    let samples = (0..N_TOTAL_SAMPLES)
+        .map(|_| sampler.sample(&mut rng).compose())
+        .collect_vec();
+    let bbox = layout.bin.bbox();
+    let interior = AARectangle::new(
+        bbox.x_min + bbox.width() / 4.0,
+        bbox.y_min + bbox.height() / 4.0,
+        bbox.x_max - bbox.width() / 4.0,
+        bbox.y_max - bbox.height() / 4.0,
+    );
+    let interior_sampler = UniformAARectSampler::new(interior, instance.item(0));
+    let interior_samples = (0..N_TOTAL_SAMPLES)
+        .map(|_| interior_sampler.sample(&mut rng).compose())
+        .collect_vec();
+    let item_ids = selected_pi_uids
+        .iter()
+        .map(|pi_uid| pi_uid.item_id)
+        .collect_vec();
+
+    let mut group = c.benchmark_group("collision");
+    group.throughput(Throughput::Elements(N_SAMPLES_PER_ITER as u64));
+
+    let mut sample_cycler = samples.chunks(N_SAMPLES_PER_ITER).cycle();
+    let mut item_id_cycler = item_ids.iter().cycle();
+    group.bench_function("surrogate_or_poly_collides", |b| {
+        b.iter(|| {
+            let item = instance.item(*item_id_cycler.next().unwrap());
+            let mut buffer_shape = item.shape.as_ref().clone();
+            for transf in sample_cycler.next().unwrap() {
+                let collides = layout.cde().surrogate_or_poly_collides(
+                    &item.shape,
+                    transf,
+                    &mut buffer_shape,
+                    &[],
+                );
+                criterion::black_box(collides);
+            }
+        })
+    });
+
+    let mut sample_cycler = samples.chunks(N_SAMPLES_PER_ITER).cycle();
+    let mut item_id_cycler = item_ids.iter().cycle();
+    group.bench_function("poly_collides", |b| {
+        b.iter(|| {
+            let item = instance.item(*item_id_cycler.next().unwrap());
+            let mut buffer_shape = item.shape.as_ref().clone();
+            for transf in sample_cycler.next().unwrap() {
+                buffer_shape.transform_from(&item.shape, transf);
+                let collides = layout.cde().poly_collides(&buffer_shape, &[]);
+                criterion::black_box(collides);
+            }
+        })
+    });
+
+    let mut sample_cycler = samples.chunks(N_SAMPLES_PER_ITER).cycle();
+    let mut item_id_cycler = item_ids.iter().cycle();
+    group.bench_function("collect_poly_collisions", |b| {
+        b.iter(|| {
+            let item = instance.item(*item_id_cycler.next().unwrap());
+            let mut buffer_shape = item.shape.as_ref().clone();
+            let mut detected = vec![];
+            for transf in sample_cycler.next().unwrap() {
+                buffer_shape.transform_from(&item.shape, transf);
+                layout
+                    .cde()
+                    .collect_poly_collisions(&buffer_shape, &[], &mut detected);
+                criterion::black_box(detected.len());
+                detected.clear();
+            }
+        })
+    });
+
+    let mut sample_cycler = interior_samples.chunks(N_SAMPLES_PER_ITER).cycle();
+    let mut item_id_cycler = item_ids.iter().cycle();
+    group.bench_function("poly_collides_interior", |b| {
+        b.iter(|| {
+            let item = instance.item(*item_id_cycler.next().unwrap());
+            let mut buffer_shape = item.shape.as_ref().clone();
+            for transf in sample_cycler.next().unwrap() {
+                buffer_shape.transform_from(&item.shape, transf);
+                let collides = layout.cde().poly_collides(&buffer_shape, &[]);
+                criterion::black_box(collides);
+            }
+        })
+    });
+    group.finish();
+}
@@ -167,7 +167,11 @@ fn modify_instance(instance: &Instance, multiplier: usize, config: LBFConfig) ->
         .collect_vec();
 
     match instance {
-        Instance::SP(spi) => Instance::SP(SPInstance::new(modified_items, spi.strip_height)),
+        Instance::SP(spi) => Instance::SP(SPInstance::new(
+            modified_items,
+            spi.strip_height,
+            spi.fixed_strip_width,
+        )),
         Instance::BP(bpi) => Instance::BP(BPInstance::new(modified_items, bpi.bins.clone())),
     }
 }
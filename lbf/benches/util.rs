@@ -17,7 +17,7 @@ use jagua_rs::util::config::{CDEConfig, SPSurrogateConfig};
 use jagua_rs::util::polygon_simplification::PolySimplConfig;
 use lbf::io;
 use lbf::io::svg_util::SvgDrawOptions;
-use lbf::lbf_config::LBFConfig;
+use lbf::lbf_config::{BPObjective, BinOpening, BinSelection, ImprovementThrottle, LBFConfig};
 use lbf::lbf_optimizer::LBFOptimizer;
 
 pub const SWIM_PATH: &str = "../assets/swim.json";
@@ -33,7 +33,7 @@ pub fn create_instance(
         None => PolySimplConfig::Disabled,
     };
     let parser = Parser::new(poly_simpl_config, cde_config, true);
-    parser.parse(json_instance)
+    parser.parse(json_instance).unwrap()
 }
 
 /// Creates a Strip Packing Problem, fill the layout using with the LBF Optimizer and removes some items from the layout
@@ -111,6 +111,8 @@ pub fn create_base_config() -> LBFConfig {
     LBFConfig {
         cde_config: CDEConfig {
             quadtree_depth: 5,
+            quadtree_depth_auto: false,
+            quadtree_min_hazards_to_split: 1,
             hpg_n_cells: 2000,
             item_surrogate_config: SPSurrogateConfig {
                 pole_coverage_goal: 0.9,
@@ -118,11 +120,24 @@ pub fn create_base_config() -> LBFConfig {
                 n_ff_poles: 4,
                 n_ff_piers: 0,
             },
+            item_surrogate_min_area: 0.0,
+            collision_margin: 0.0,
+            container_margin: None,
+            allow_touching: false,
         },
         poly_simpl_tolerance: Some(0.001),
         prng_seed: Some(0),
         n_samples: 5000,
         ls_frac: 0.2,
+        sampler_origin: None,
+        max_bins: None,
+        bin_selection: BinSelection::FirstFit,
+        bin_opening: BinOpening::InOrder,
+        bp_objective: BPObjective::MinBins,
+        contact_slide: false,
+        record_tightness: false,
+        prune_rotations: false,
+        improvement_throttle: ImprovementThrottle::Disabled,
         svg_draw_options: Default::default(),
     }
 }
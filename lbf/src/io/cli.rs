@@ -8,8 +8,13 @@ use log::LevelFilter;
 pub struct Cli {
     #[arg(short, long, value_name = "FILE")]
     pub input_file: PathBuf,
-    #[arg(short, long, value_name = "FOLDER")]
-    pub solution_folder: PathBuf,
+    #[arg(
+        short,
+        long,
+        value_name = "FOLDER",
+        required_unless_present = "validate"
+    )]
+    pub solution_folder: Option<PathBuf>,
     #[arg(short, long, value_name = "FILE")]
     pub config_file: Option<PathBuf>,
     #[arg(
@@ -19,4 +24,20 @@ pub struct Cli {
         default_value = "info"
     )]
     pub log_level: LevelFilter,
+    /// Number of independent runs, with seeds derived from the instance and the configured seed. The best solution is written
+    #[arg(short, long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub runs: u32,
+    /// Write the layouts to numbered SVGs after every placement, to follow the construction of the solution
+    #[arg(long)]
+    pub dump_improvements: bool,
+    /// Treat the input file as a solution file and check it for overlaps and items out of bounds, instead of solving.
+    /// Exits with a non-zero code if the solution is not valid
+    #[arg(long)]
+    pub validate: bool,
+    /// Additionally write the solution in the XML format of the ESICUP nesting datasets
+    #[arg(long)]
+    pub esicup: bool,
+    /// Folder to cache the surrogates of the items in, so later runs on the same items skip generating them
+    #[arg(long, value_name = "FOLDER")]
+    pub surrogate_cache: Option<PathBuf>,
 }
@@ -1,14 +1,19 @@
 use crate::io::svg_util::SvgDrawOptions;
 use crate::io::{svg_export, svg_util};
+use itertools::Itertools;
 use jagua_rs::entities::instances::instance::Instance;
 use jagua_rs::entities::instances::instance_generic::InstanceGeneric;
 use jagua_rs::entities::layout::Layout;
 use jagua_rs::entities::layout::LayoutSnapshot;
+use jagua_rs::entities::solution::Solution;
 use jagua_rs::fsize;
+use jagua_rs::geometry::geo_traits::Shape;
 use jagua_rs::geometry::primitives::circle::Circle;
+use jagua_rs::geometry::primitives::simple_polygon::SimplePolygon;
 use jagua_rs::geometry::transformation::Transformation;
 use jagua_rs::io::parser;
-use svg::node::element::{Definitions, Group, Title, Use};
+use svg::node::element::path::Data;
+use svg::node::element::{Definitions, Group, Text, Title, Use};
 use svg::Document;
 
 pub fn s_layout_to_svg(
@@ -28,6 +33,7 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
     let vbox = bin.bbox().clone().scale(1.05);
 
     let theme = &options.theme;
+    let precision = options.coordinate_precision;
 
     let stroke_width =
         fsize::min(vbox.width(), vbox.height()) * 0.001 * theme.stroke_width_multiplier;
@@ -44,7 +50,7 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
         //outer
         bin_group = bin_group
             .add(svg_export::data_to_path(
-                svg_export::simple_polygon_data(&bin.outer),
+                polygon_data(precision, &bin.outer),
                 &[
                     ("fill", &*format!("{}", theme.bin_fill)),
                     ("stroke", "black"),
@@ -53,11 +59,27 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
             ))
             .add(title);
 
+        //usable region, if the bin has margins
+        if bin.edge_margins.iter().any(|m| *m > 0.0) {
+            bin_group = bin_group.add(
+                svg_export::data_to_path(
+                    polygon_data(precision, &bin.usable),
+                    &[
+                        ("fill", "none"),
+                        ("stroke", "black"),
+                        ("stroke-width", &*format!("{}", 1.0 * stroke_width)),
+                        ("stroke-dasharray", &*format!("{}", 2.0 * stroke_width)),
+                    ],
+                )
+                .add(Title::new("usable region")),
+            );
+        }
+
         //holes
         for (hole_idx, hole) in bin.holes.iter().enumerate() {
             bin_group = bin_group.add(
                 svg_export::data_to_path(
-                    svg_export::simple_polygon_data(hole),
+                    polygon_data(precision, hole),
                     &[
                         ("fill", &*format!("{}", theme.hole_fill)),
                         ("stroke", "black"),
@@ -67,6 +89,22 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
                 .add(Title::new(format!("hole #{}", hole_idx))),
             );
         }
+
+        //soft zones
+        for (sz_idx, soft_zone) in bin.soft_zones.iter().enumerate() {
+            bin_group = bin_group.add(
+                svg_export::data_to_path(
+                    polygon_data(precision, soft_zone),
+                    &[
+                        ("fill", "none"),
+                        ("stroke", "black"),
+                        ("stroke-width", &*format!("{}", 1.0 * stroke_width)),
+                        ("stroke-dasharray", &*format!("{}", 5.0 * stroke_width)),
+                    ],
+                )
+                .add(Title::new(format!("soft zone #{}", sz_idx))),
+            );
+        }
         bin_group
     };
 
@@ -80,7 +118,7 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
             for qz_shape in qz.zones.iter() {
                 qz_group = qz_group.add(
                     svg_export::data_to_path(
-                        svg_export::simple_polygon_data(qz_shape),
+                        polygon_data(precision, qz_shape),
                         &[
                             ("fill", &*format!("{}", color)),
                             ("fill-opacity", "0.50"),
@@ -100,10 +138,13 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
     };
 
     //draw items
-    let (items_group, surrogate_group) = {
-        //define all the items and their surrogates (if enabled)
+    let margin = layout.cde().config().collision_margin;
+    let draw_halos = options.draw_spacing_halo && margin > 0.0;
+    let (items_group, surrogate_group, halo_group) = {
+        //define all the items, their surrogates and spacing halos (if enabled)
         let mut item_defs = Definitions::new();
         let mut surrogate_defs = Definitions::new();
+        let mut halo_defs = Definitions::new();
         for (internal_item, _) in instance.items() {
             let item = parser::pretransform_item(
                 internal_item,
@@ -116,7 +157,7 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
             };
             item_defs = item_defs.add(Group::new().set("id", format!("item_{}", item.id)).add(
                 svg_export::data_to_path(
-                    svg_export::simple_polygon_data(shape),
+                    polygon_data(precision, shape),
                     &[
                         ("fill", &*format!("{}", color)),
                         ("stroke-width", &*format!("{}", stroke_width)),
@@ -127,6 +168,23 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
                 ),
             ));
 
+            if draw_halos {
+                //a stroke of twice the margin, centered on the contour, covers all points within the margin of the item
+                halo_defs = halo_defs.add(Group::new().set("id", format!("halo_{}", item.id)).add(
+                    svg_export::data_to_path(
+                        polygon_data(precision, shape),
+                        &[
+                            ("fill", "none"),
+                            ("stroke", &*format!("{}", theme.item_fill)),
+                            ("stroke-width", &*format!("{}", 2.0 * margin)),
+                            ("stroke-opacity", "0.3"),
+                            ("stroke-linejoin", "round"),
+                            ("stroke-linecap", "round"),
+                        ],
+                    ),
+                ));
+            }
+
             if options.surrogate {
                 let mut surrogate_group = Group::new().set("id", format!("surrogate_{}", item.id));
                 let poi_style = [
@@ -178,6 +236,7 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
         }
         let mut items_group = Group::new().set("id", "items").add(item_defs);
         let mut surrogate_group = Group::new().set("id", "surrogates").add(surrogate_defs);
+        let mut halo_group = Group::new().set("id", "spacing_halos").add(halo_defs);
 
         for pi in layout.placed_items().values() {
             let abs_transf = parser::internal_to_absolute_transform(
@@ -191,34 +250,47 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
                 abs_transf.decompose()
             ));
             let pi_ref = Use::new()
-                .set("transform", transform_to_svg(&abs_transf))
+                .set("transform", transform_to_svg(precision, &abs_transf))
                 .set("xlink:href", format!("#item_{}", pi.item_id))
                 .add(title);
 
             items_group = items_group.add(pi_ref);
 
+            if draw_halos {
+                let pi_halo_ref = Use::new()
+                    .set("transform", transform_to_svg(precision, &abs_transf))
+                    .set("xlink:href", format!("#halo_{}", pi.item_id));
+
+                halo_group = halo_group.add(pi_halo_ref);
+            }
+
             if options.surrogate {
                 let pi_surr_ref = Use::new()
-                    .set("transform", transform_to_svg(&abs_transf))
+                    .set("transform", transform_to_svg(precision, &abs_transf))
                     .set("xlink:href", format!("#surrogate_{}", pi.item_id));
 
                 surrogate_group = surrogate_group.add(pi_surr_ref);
             }
         }
 
-        match options.surrogate {
-            false => (items_group, None),
-            true => (items_group, Some(surrogate_group)),
-        }
+        let surrogate_group = match options.surrogate {
+            false => None,
+            true => Some(surrogate_group),
+        };
+        let halo_group = match draw_halos {
+            false => None,
+            true => Some(halo_group),
+        };
+        (items_group, surrogate_group, halo_group)
     };
 
     let qt_group = match options.quadtree {
         false => None,
         true => {
             let qt_data = svg_export::quad_tree_data(layout.cde().quadtree(), &[]);
-            let qt_group = Group::new()
+            let mut qt_group = Group::new()
                 .set("id", "quadtree")
-                .set("transform", transform_to_svg(&inv_bin_transf))
+                .set("transform", transform_to_svg(precision, &inv_bin_transf))
                 .add(svg_export::data_to_path(
                     qt_data.0,
                     &[
@@ -248,6 +320,27 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
                         ("stroke", "black"),
                     ],
                 ));
+            if options.quadtree_hazard_counts {
+                //label the leaves with the number of partially present hazards, which are resolved edge by edge
+                let dump = layout.cde().export_quadtree();
+                for node in dump.nodes.iter().filter(|n| n.leaf && n.n_partial > 0) {
+                    let (cx, cy) = (
+                        (node.x_min + node.x_max) / 2.0,
+                        (node.y_min + node.y_max) / 2.0,
+                    );
+                    let mut label = Text::new(format!("{}", node.n_partial))
+                        .set("x", cx)
+                        .set("y", cy)
+                        .set("font-size", (node.y_max - node.y_min) * 0.5)
+                        .set("text-anchor", "middle")
+                        .set("dominant-baseline", "central");
+                    if options.y_axis_up {
+                        //keep the label readable when the layout is mirrored
+                        label = label.set("transform", format!("matrix(1 0 0 -1 0 {})", 2.0 * cy));
+                    }
+                    qt_group = qt_group.add(label);
+                }
+            }
             Some(qt_group)
         }
     };
@@ -257,7 +350,7 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
         true => {
             let mut hpg_group = Group::new()
                 .set("id", "haz_prox_grid")
-                .set("transform", transform_to_svg(&inv_bin_transf));
+                .set("transform", transform_to_svg(precision, &inv_bin_transf));
             let hpg = layout.cde().haz_prox_grid().unwrap();
             for hp_cell in hpg.grid.cells.iter().flatten() {
                 let center = hp_cell.centroid;
@@ -286,20 +379,124 @@ pub fn layout_to_svg(layout: &Layout, instance: &Instance, options: SvgDrawOptio
         .flatten()
         .fold(Group::new().set("id", "optionals"), |g, opt| g.add(opt));
 
+    //halos are drawn beneath the items, overlapping halos remain visible through their transparency
+    let mut layout_group = Group::new().add(bin_group);
+    if let Some(halo_group) = halo_group {
+        layout_group = layout_group.add(halo_group);
+    }
+    layout_group = layout_group.add(items_group).add(qz_group).add(optionals);
+
+    if options.y_axis_up {
+        //mirror everything around the horizontal center line of the view box
+        let flip_ty = vbox.y_min + vbox.y_max;
+        layout_group = layout_group.set("transform", format!("matrix(1 0 0 -1 0 {flip_ty})"));
+    }
+
     Document::new()
         .set("viewBox", vbox_svg)
         .set("xmlns:xlink", "http://www.w3.org/1999/xlink")
-        .add(bin_group)
-        .add(items_group)
-        .add(qz_group)
-        .add(optionals)
+        .add(layout_group)
 }
 
-fn transform_to_svg(t: &Transformation) -> String {
+fn transform_to_svg(precision: Option<usize>, t: &Transformation) -> String {
     //https://developer.mozilla.org/en-US/docs/Web/SVG/Attribute/transform
     //operations are effectively applied from right to left
     let dt = t.decompose();
     let (tx, ty) = dt.translation();
     let r = dt.rotation().to_degrees();
+    let [tx, ty, r] = match precision {
+        None => [tx, ty, r],
+        Some(decimals) => [tx, ty, r].map(|v| svg_export::round_to(v, decimals)),
+    };
     format!("translate({tx} {ty}), rotate({r})")
 }
+
+fn polygon_data(precision: Option<usize>, shape: &SimplePolygon) -> Data {
+    match precision {
+        None => svg_export::simple_polygon_data(shape),
+        Some(decimals) => svg_export::rounded_simple_polygon_data(shape, decimals),
+    }
+}
+
+/// Draws all items which are missing from the solution on a grid, one cell per copy, ordered by item id.
+/// Items with an unlimited demand are not considered.
+/// Returns `None` if all items are placed.
+pub fn unplaced_items_to_svg(
+    solution: &Solution,
+    instance: &Instance,
+    options: SvgDrawOptions,
+) -> Option<Document> {
+    let unplaced_items = solution
+        .missing_item_qtys(instance)
+        .iter()
+        .enumerate()
+        .filter(|(id, _)| !instance.item_qty_unlimited(*id))
+        .flat_map(|(id, &qty)| (0..qty.max(0)).map(move |_| id))
+        .map(|id| {
+            let internal_item = instance.item(id);
+            parser::pretransform_item(internal_item, &internal_item.pretransform.clone().inverse())
+        })
+        .collect_vec();
+
+    if unplaced_items.is_empty() {
+        return None;
+    }
+
+    //square cells, large enough to fit every item with some padding
+    let max_dim = unplaced_items
+        .iter()
+        .map(|item| {
+            let bbox = item.shape.bbox();
+            fsize::max(bbox.width(), bbox.height())
+        })
+        .fold(0.0, fsize::max);
+    let padding = max_dim * 0.05;
+    let cell_size = max_dim + 2.0 * padding;
+    let n_cols = (unplaced_items.len() as fsize).sqrt().ceil() as usize;
+    let n_rows = unplaced_items.len().div_ceil(n_cols);
+
+    let theme = &options.theme;
+    let precision = options.coordinate_precision;
+    let stroke_width = cell_size * 0.002 * theme.stroke_width_multiplier;
+
+    let mut items_group = Group::new().set("id", "unplaced_items");
+    for (i, item) in unplaced_items.iter().enumerate() {
+        let (col, row) = (i % n_cols, i / n_cols);
+        let bbox = item.shape.bbox();
+        let translation = Transformation::from_translation((
+            col as fsize * cell_size + padding - bbox.x_min,
+            row as fsize * cell_size + padding - bbox.y_min,
+        ));
+        let color = match item.base_quality {
+            None => theme.item_fill.to_owned(),
+            Some(q) => svg_util::blend_colors(theme.item_fill, theme.qz_fill[q]),
+        };
+        items_group = items_group.add(
+            svg_export::data_to_path(
+                polygon_data(precision, &item.shape),
+                &[
+                    ("fill", &*format!("{}", color)),
+                    ("stroke-width", &*format!("{}", stroke_width)),
+                    ("fill-rule", "nonzero"),
+                    ("stroke", "black"),
+                    ("opacity", "0.9"),
+                ],
+            )
+            .set("transform", transform_to_svg(precision, &translation))
+            .add(Title::new(format!("unplaced item, id: {}", item.id))),
+        );
+    }
+
+    let vbox = (
+        0.0,
+        0.0,
+        n_cols as fsize * cell_size,
+        n_rows as fsize * cell_size,
+    );
+    if options.y_axis_up {
+        let flip_ty = vbox.3;
+        items_group = items_group.set("transform", format!("matrix(1 0 0 -1 0 {flip_ty})"));
+    }
+
+    Some(Document::new().set("viewBox", vbox).add(items_group))
+}
@@ -6,7 +6,9 @@ use std::path::Path;
 use log::{info, log, Level, LevelFilter};
 use svg::Document;
 
+use jagua_rs::entities::instances::instance::Instance;
 use jagua_rs::io::json_instance::JsonInstance;
+use jagua_rs::io::parser::Parser;
 
 use crate::io::json_output::JsonOutput;
 use crate::EPOCH;
@@ -25,6 +27,26 @@ pub fn read_json_instance(path: &Path) -> JsonInstance {
         .unwrap_or_else(|err| panic!("could not parse instance file: {}, {}", path.display(), err))
 }
 
+/// Reads a solution file written by the binary, see [`write_json_output`]
+pub fn read_json_output(path: &Path) -> JsonOutput {
+    let file = File::open(path)
+        .unwrap_or_else(|err| panic!("could not open solution file: {}, {}", path.display(), err));
+    let reader = BufReader::new(file);
+    serde_json::from_reader(reader)
+        .unwrap_or_else(|err| panic!("could not parse solution file: {}, {}", path.display(), err))
+}
+
+/// Reads and parses an instance file in a streaming fashion, see [`Parser::parse_streaming`]
+pub fn read_instance_streaming(path: &Path, parser: &Parser) -> Instance {
+    let file = File::open(path)
+        .unwrap_or_else(|err| panic!("could not open instance file: {}, {}", path.display(), err));
+    let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(file));
+    parser
+        .parse_streaming(&mut deserializer)
+        .and_then(|instance| deserializer.end().map(|_| instance))
+        .unwrap_or_else(|err| panic!("could not parse instance file: {}, {}", path.display(), err))
+}
+
 pub fn write_json_output(json_output: &JsonOutput, path: &Path) {
     let file = File::create(path)
         .unwrap_or_else(|_| panic!("could not open solution file: {}", path.display()));
@@ -43,6 +65,18 @@ pub fn write_json_output(json_output: &JsonOutput, path: &Path) {
     );
 }
 
+pub fn write_esicup_xml(xml: &str, path: &Path) {
+    fs::write(path, xml)
+        .unwrap_or_else(|_| panic!("could not write ESICUP solution file: {}", path.display()));
+    info!(
+        "Solution XML written to file://{}",
+        fs::canonicalize(path)
+            .expect("could not canonicalize path")
+            .to_str()
+            .unwrap()
+    );
+}
+
 pub fn write_svg(document: &Document, path: &Path) {
     svg::save(path, document).expect("failed to write svg file");
     info!(
@@ -17,6 +17,21 @@ pub fn simple_polygon_data(s_poly: &SimplePolygon) -> Data {
     data.close()
 }
 
+/// Same as [`simple_polygon_data`], but with all coordinates rounded to `decimals` decimal places
+pub fn rounded_simple_polygon_data(s_poly: &SimplePolygon, decimals: usize) -> Data {
+    let round = |Point(x, y): Point| (round_to(x, decimals), round_to(y, decimals));
+    let mut data = Data::new().move_to(round(s_poly.get_point(0)));
+    for i in 1..s_poly.number_of_points() {
+        data = data.line_to(round(s_poly.get_point(i)));
+    }
+    data.close()
+}
+
+pub fn round_to(value: fsize, decimals: usize) -> fsize {
+    let factor = (10.0 as fsize).powi(decimals as i32);
+    (value * factor).round() / factor
+}
+
 pub fn quad_tree_data(qt_root: &QTNode, irrelevant_hazards: &[HazardEntity]) -> (Data, Data, Data) {
     qt_node_data(
         qt_root,
@@ -14,12 +14,25 @@ pub struct SvgDrawOptions {
     ///Draw the quadtree on top
     #[serde(default)]
     pub quadtree: bool,
+    ///Label the leaves of the quadtree with their number of partially present hazards, only if `quadtree` is drawn
+    #[serde(default)]
+    pub quadtree_hazard_counts: bool,
     ///Draw the hazard proximity grid on top
     #[serde(default)]
     pub haz_prox_grid: bool,
     ///Draw the fail fast surrogate on top of each item
     #[serde(default)]
     pub surrogate: bool,
+    ///Draw a translucent halo around each item, covering the clearance zone of the collision margin of the layout
+    #[serde(default)]
+    pub draw_spacing_halo: bool,
+    ///Orient the y-axis upwards instead of the SVG default (downwards)
+    #[serde(default)]
+    pub y_axis_up: bool,
+    ///Number of decimals of the coordinates of polygons and placements, all decimals are kept if `None`.
+    ///Purely cosmetic: the rendered shapes can shift up to half a unit of the last decimal, which might show as tiny overlaps, the solution itself is unaffected.
+    #[serde(default)]
+    pub coordinate_precision: Option<usize>,
 }
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize, Copy)]
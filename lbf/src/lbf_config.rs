@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 
 use jagua_rs::fsize;
@@ -14,10 +16,42 @@ pub struct LBFConfig {
     pub poly_simpl_tolerance: Option<fsize>,
     /// Seed for the PRNG. If undefined, the algorithm will run in non-deterministic mode using entropy
     pub prng_seed: Option<u64>,
-    /// Total budget of samples per item per layout
+    /// Total budget of samples per item per layout, trading speed for quality.
+    /// Regardless of the budget, the bottom-left corner of the bin is always tried as well
     pub n_samples: usize,
-    /// Fraction of `n_samples_per_item` used for the local search sampler, the rest is sampled uniformly.
+    /// Fraction of `n_samples` used for the local search sampler, the rest is sampled uniformly.
     pub ls_frac: f32,
+    /// Fixed origin (in the coordinates of the input) relative to which the samplers generate translations.
+    /// If undefined, the origin is derived from the bin
+    #[serde(default)]
+    pub sampler_origin: Option<(fsize, fsize)>,
+    /// Maximum number of bins to open in bin packing problems, items which do not fit are left unplaced.
+    /// If undefined, bins are opened as long as there is stock
+    #[serde(default)]
+    pub max_bins: Option<usize>,
+    /// Strategy to select the bin in which an item is placed, in bin packing problems
+    #[serde(default)]
+    pub bin_selection: BinSelection,
+    /// Strategy to select the type of bin to open when an item does not fit in any of the open bins
+    #[serde(default)]
+    pub bin_opening: BinOpening,
+    /// Objective in bin packing problems, see [`BPObjective`]
+    #[serde(default)]
+    pub bp_objective: BPObjective,
+    /// After sampling, slide the best placement left and down until it makes contact with a hazard
+    #[serde(default)]
+    pub contact_slide: bool,
+    /// Record how many feasible candidate positions were found for every placed item.
+    /// Requires validating every uniform sample, which slows down the search
+    #[serde(default)]
+    pub record_tightness: bool,
+    /// In strip packing, never sample rotations in which an item is taller than the strip.
+    /// Items which are too tall in all of their allowed rotations are left unplaced
+    #[serde(default)]
+    pub prune_rotations: bool,
+    /// Limits how often improvements are reported during a solve, see [`ImprovementThrottle`]
+    #[serde(default)]
+    pub improvement_throttle: ImprovementThrottle,
     /// Optional SVG drawing options
     #[serde(default)]
     pub svg_draw_options: SvgDrawOptions,
@@ -28,6 +62,8 @@ impl Default for LBFConfig {
         Self {
             cde_config: CDEConfig {
                 quadtree_depth: 5,
+                quadtree_depth_auto: false,
+                quadtree_min_hazards_to_split: 1,
                 hpg_n_cells: 2000,
                 item_surrogate_config: SPSurrogateConfig {
                     pole_coverage_goal: 0.9,
@@ -35,12 +71,86 @@ impl Default for LBFConfig {
                     n_ff_poles: 2,
                     n_ff_piers: 0,
                 },
+                item_surrogate_min_area: 0.0,
+                collision_margin: 0.0,
+                container_margin: None,
+                allow_touching: false,
             },
             poly_simpl_tolerance: Some(0.001),
             prng_seed: Some(0),
             n_samples: 5000,
             ls_frac: 0.2,
+            sampler_origin: None,
+            max_bins: None,
+            bin_selection: BinSelection::FirstFit,
+            bin_opening: BinOpening::InOrder,
+            bp_objective: BPObjective::MinBins,
+            contact_slide: false,
+            record_tightness: false,
+            prune_rotations: false,
+            improvement_throttle: ImprovementThrottle::Disabled,
             svg_draw_options: SvgDrawOptions::default(),
         }
     }
 }
+
+/// Strategy to select the bin in which an item is placed.
+/// A new bin is only opened if the item does not fit in any of the open bins.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BinSelection {
+    /// Place the item in the first open bin in which it fits
+    #[default]
+    FirstFit,
+    /// Place the item in the open bin where its placement has the lowest cost
+    BestFit,
+}
+
+/// Strategy to select the type of bin to open, only bin types with remaining stock are considered
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BinOpening {
+    /// Open the first bin type, in the order of the instance, in which the item fits
+    #[default]
+    InOrder,
+    /// Open the bin type in which the item fits, that would be filled best by the remaining items.
+    /// The expected fill is the remaining item area relative to the area of the bin (capped at 100%),
+    /// ties are broken in favor of the lowest cost per unit of area
+    BestFill,
+}
+
+/// Objective of the optimizer in bin packing problems
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BPObjective {
+    /// Place all items in as few bins as possible
+    #[default]
+    MinBins,
+    /// Maximize the total value of the placed items (knapsack).
+    /// Items are placed in order of decreasing value, ties broken by decreasing area.
+    /// Combine with `max_bins` to set the bin budget, the least valuable items are the first to be left out
+    MaxValue,
+}
+
+/// Limits how often improvements are reported to the callback of a solve, intermediate improvements in between are coalesced.
+/// The final solution is always reported if it contains placements which were not reported yet
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+pub enum ImprovementThrottle {
+    /// Report an improvement after every placement
+    #[default]
+    Disabled,
+    /// Report at most one improvement per interval, in seconds. The first placement is always reported
+    MinInterval(f64),
+    /// Report an improvement once at least this many items were placed since the last report
+    MinPlacements(usize),
+}
+
+impl ImprovementThrottle {
+    /// Whether an improvement should be reported, given the time since the last report (if any) and the number of placements since then
+    pub fn is_due(&self, since_last_report: Option<Duration>, n_unreported: usize) -> bool {
+        match self {
+            ImprovementThrottle::Disabled => true,
+            ImprovementThrottle::MinInterval(secs) => {
+                since_last_report.is_none_or(|d| d.as_secs_f64() >= *secs)
+            }
+            ImprovementThrottle::MinPlacements(n) => n_unreported >= *n,
+        }
+    }
+}
@@ -1,13 +1,22 @@
 use ordered_float::NotNan;
 
+use jagua_rs::collision_detection::cd_engine::CDEngine;
+use jagua_rs::entities::item::PreferredRegion;
 use jagua_rs::fsize;
-use jagua_rs::geometry::geo_traits::Shape;
+use jagua_rs::geometry::geo_traits::{DistanceFrom, Shape};
 use jagua_rs::geometry::primitives::simple_polygon::SimplePolygon;
 
 const X_MULTIPLIER: fsize = 10.0;
 
+/// Penalty per unit of distance a shape is within the repelling range of a soft zone
+const SOFT_ZONE_MULTIPLIER: fsize = 2.0 * X_MULTIPLIER;
+
+/// Penalty per unit of distance (and unit of weight) a shape is removed from its preferred region
+const PREFERRED_REGION_MULTIPLIER: fsize = X_MULTIPLIER;
+
 /// The cost LBF assigned to a placing option.
 /// Weighted sum of the x_max and y_max of the shape, with the horizontal dimension being more important.
+/// Shapes in proximity to soft zones and shapes outside their preferred region are penalized.
 /// <br>
 /// A pure lexicographic comparison (always prioritizing x-axis) would lead to undesirable results due to the continuous nature of the values.
 #[derive(PartialEq, PartialOrd, Copy, Clone, Debug, Eq, Ord)]
@@ -19,7 +28,25 @@ impl LBFPlacingCost {
         LBFPlacingCost(NotNan::new(cost).expect("cost is NaN"))
     }
 
-    pub fn from_shape(shape: &SimplePolygon) -> Self {
-        LBFPlacingCost::new(shape.bbox().x_max, shape.bbox().y_max)
+    /// The `preferred_region` has to be expressed in the internal coordinates of the bin
+    pub fn from_shape(
+        shape: &SimplePolygon,
+        cde: &CDEngine,
+        preferred_region: Option<&PreferredRegion>,
+    ) -> Self {
+        let LBFPlacingCost(cost) = LBFPlacingCost::new(shape.bbox().x_max, shape.bbox().y_max);
+        let soft_zone_penalty = match cde.soft_hazard_proximity(shape) {
+            None => 0.0,
+            //soft zones repel shapes within a range of their diameter
+            Some(proximity) => fsize::max(shape.diameter() - proximity, 0.0) * SOFT_ZONE_MULTIPLIER,
+        };
+        let preference_penalty = match preferred_region {
+            None => 0.0,
+            //the center of the shape is attracted to the region, proportional to the weight of the preference
+            Some(pr) => {
+                pr.rect.distance(&shape.bbox().centroid()) * pr.weight * PREFERRED_REGION_MULTIPLIER
+            }
+        };
+        LBFPlacingCost(cost + soft_zone_penalty + preference_penalty)
     }
 }
@@ -1,4 +1,6 @@
 use std::cmp::{Ordering, Reverse};
+use std::sync::mpsc::{self, Receiver};
+use std::thread::{self, JoinHandle};
 use std::time::Instant;
 
 use itertools::Itertools;
@@ -6,13 +8,16 @@ use log::{debug, info};
 use ordered_float::NotNan;
 use rand::prelude::SmallRng;
 use rand::Rng;
+use rayon::prelude::*;
 use thousands::Separable;
 
 use jagua_rs::collision_detection::hazard_filter;
+use jagua_rs::entities::instances::bin_packing::BPInstance;
 use jagua_rs::entities::instances::instance::Instance;
 use jagua_rs::entities::instances::instance_generic::InstanceGeneric;
-use jagua_rs::entities::item::Item;
+use jagua_rs::entities::item::{Item, PreferredRegion};
 use jagua_rs::entities::layout::Layout;
+use jagua_rs::entities::placed_item::PItemKey;
 use jagua_rs::entities::placing_option::PlacingOption;
 use jagua_rs::entities::problems::bin_packing::BPProblem;
 use jagua_rs::entities::problems::problem::Problem;
@@ -21,13 +26,21 @@ use jagua_rs::entities::problems::strip_packing::SPProblem;
 use jagua_rs::entities::solution::Solution;
 use jagua_rs::fsize;
 use jagua_rs::geometry::convex_hull::convex_hull_from_points;
-use jagua_rs::geometry::geo_traits::{Shape, TransformableFrom};
+use jagua_rs::geometry::d_transformation::DTransformation;
+use jagua_rs::geometry::geo_enums::AllowedRotation;
+use jagua_rs::geometry::geo_traits::{Shape, Transformable, TransformableFrom};
+use jagua_rs::geometry::primitives::aa_rectangle::AARectangle;
+use jagua_rs::geometry::primitives::point::Point;
 use jagua_rs::geometry::primitives::simple_polygon::SimplePolygon;
+use jagua_rs::geometry::transformation::Transformation;
+use jagua_rs::util::trace::PhaseSpan;
 
-use crate::lbf_config::LBFConfig;
+use crate::lbf_config::{BPObjective, BinOpening, BinSelection, LBFConfig};
 use crate::lbf_cost::LBFPlacingCost;
+use crate::samplers::contact_slider::ContactSlider;
 use crate::samplers::hpg_sampler::HPGSampler;
 use crate::samplers::ls_sampler::LSSampler;
+use crate::util;
 
 //limits the number of items to be placed, for debugging purposes
 pub const ITEM_LIMIT: usize = usize::MAX;
@@ -41,13 +54,34 @@ pub struct LBFOptimizer {
     pub sample_counter: usize,
 }
 
+/// How constrained an item was at the moment it was placed, see [`LBFOptimizer::solve_with_tightness`]
+#[derive(Debug, Clone, Copy)]
+pub struct PlacementTightness {
+    /// Index of the layout in [`Solution::layout_snapshots`]
+    pub layout: usize,
+    /// Key of the placed item in the layout snapshot
+    pub pik: PItemKey,
+    pub item_id: usize,
+    /// Number of uniform samples which were feasible positions for the item, across all searched layouts
+    pub n_feasible: usize,
+}
+
 impl LBFOptimizer {
     pub fn new(instance: Instance, config: LBFConfig, rng: SmallRng) -> Self {
         assert!(config.n_samples > 0);
         let problem = match instance.clone() {
             Instance::BP(bpi) => BPProblem::new(bpi.clone()).into(),
             Instance::SP(spi) => {
-                let strip_width = instance.item_area() * 2.0 / spi.strip_height; //initiate with 50% usage
+                let strip_width = match spi.fixed_strip_width {
+                    Some(fixed_width) => fixed_width,
+                    None => {
+                        assert!(
+                            instance.item_area() > 0.0,
+                            "a strip width has to be fixed when all items have an unlimited demand"
+                        );
+                        instance.item_area() * 2.0 / spi.strip_height //initiate with 50% usage
+                    }
+                };
                 SPProblem::new(spi.clone(), strip_width, config.cde_config).into()
             }
         };
@@ -62,32 +96,121 @@ impl LBFOptimizer {
     }
 
     pub fn solve(&mut self) -> Solution {
-        //sort the items by descending diameter of convex hull
-        let sorted_item_indices = (0..self.instance.items().len())
-            .sorted_by_cached_key(|i| {
-                let item = &self.instance.items()[*i].0;
-                let ch = SimplePolygon::new(convex_hull_from_points(item.shape.points.clone()));
-                let ch_diam = NotNan::new(ch.diameter()).expect("convex hull diameter is NaN");
-                Reverse(ch_diam)
-            })
-            .collect_vec();
+        self.solve_with_callback(None)
+    }
+
+    /// Same as [`LBFOptimizer::solve`], but also returns the tightness of every placed item in the solution.
+    /// The tightness is only recorded if `record_tightness` is enabled in the config, otherwise none is returned.
+    pub fn solve_with_tightness(&mut self) -> (Solution, Vec<PlacementTightness>) {
+        self.solve_and_record(None)
+    }
+
+    /// Solves on a background thread, a snapshot of the problem is sent over the returned channel after every placement
+    /// (subject to the [`ImprovementThrottle`](crate::lbf_config::ImprovementThrottle)).
+    /// The channel is closed when the solve finishes, after which the final solution can be obtained by joining the handle.
+    /// Dropping the receiver does not stop the solve.
+    pub fn solve_in_background(mut self) -> (Receiver<Solution>, JoinHandle<Solution>) {
+        let (sender, receiver) = mpsc::channel();
+        let handle = thread::spawn(move || {
+            //a disconnected receiver is not an error, the solve simply continues
+            let mut send = |solution: &Solution| sender.send(solution.clone()).unwrap_or(());
+            self.solve_with_callback(Some(&mut send))
+        });
+        (receiver, handle)
+    }
+
+    /// Same as [`LBFOptimizer::solve`], but `on_improvement` is called with a snapshot of the problem after every placement,
+    /// or less often if an [`ImprovementThrottle`](crate::lbf_config::ImprovementThrottle) is configured
+    pub fn solve_with_callback(
+        &mut self,
+        on_improvement: Option<&mut dyn FnMut(&Solution)>,
+    ) -> Solution {
+        self.solve_and_record(on_improvement).0
+    }
+
+    fn solve_and_record(
+        &mut self,
+        mut on_improvement: Option<&mut dyn FnMut(&Solution)>,
+    ) -> (Solution, Vec<PlacementTightness>) {
+        let _span = PhaseSpan::enter("LBF solve");
+        let maximize_value = matches!(
+            (&self.problem, self.config.bp_objective),
+            (Problem::BP(_), BPObjective::MaxValue)
+        );
+        let sorted_item_indices = match maximize_value {
+            false => {
+                //sort the items by descending diameter of convex hull, items with unlimited demand are placed last
+                (0..self.instance.items().len())
+                    .sorted_by_cached_key(|i| {
+                        let item = &self.instance.items()[*i].0;
+                        let ch =
+                            SimplePolygon::new(convex_hull_from_points(item.shape.points.clone()));
+                        let ch_diam =
+                            NotNan::new(ch.diameter()).expect("convex hull diameter is NaN");
+                        (self.instance.item_qty_unlimited(*i), Reverse(ch_diam))
+                    })
+                    .collect_vec()
+            }
+            true => {
+                //sort the items by descending value, ties broken by descending area, items with unlimited demand are placed last
+                (0..self.instance.items().len())
+                    .sorted_by_cached_key(|i| {
+                        let item = &self.instance.items()[*i].0;
+                        let area = NotNan::new(item.shape.area()).expect("item area is NaN");
+                        (
+                            self.instance.item_qty_unlimited(*i),
+                            Reverse(item.value),
+                            Reverse(area),
+                        )
+                    })
+                    .collect_vec()
+            }
+        };
 
         let start = Instant::now();
+        let mut last_report: Option<Instant> = None;
+        let mut n_unreported = 0;
+        //the layout, item and transformation identify a placement, even when the strip is modified afterwards
+        let mut tightness = vec![];
 
         'outer: for item_index in sorted_item_indices {
             let item = &self.instance.items()[item_index].0;
+            let pruned_item;
+            let item = match (&self.problem, self.config.prune_rotations) {
+                (Problem::SP(sp_problem), true) => {
+                    match prune_rotations(item, sp_problem.strip_height()) {
+                        Some(pruned) => {
+                            pruned_item = pruned;
+                            &pruned_item
+                        }
+                        None => {
+                            info!(
+                                "[LBF] item {} is taller than the strip in all of its allowed rotations, left unplaced",
+                                item_index
+                            );
+                            continue;
+                        }
+                    }
+                }
+                _ => item,
+            };
             //place all items of this type
             while self.problem.missing_item_qtys()[item_index] > 0 {
                 //find a position and insert it
+                let mut n_feasible = 0;
                 match find_lbf_placement(
                     &self.problem,
                     item,
                     &self.config,
                     &mut self.rng,
                     &mut self.sample_counter,
+                    &mut n_feasible,
                 ) {
                     Some(i_opt) => {
                         let l_index = self.problem.place_item(i_opt);
+                        if self.config.record_tightness {
+                            tightness.push((l_index.0, i_opt.item_id, i_opt.d_transf, n_feasible));
+                        }
                         info!(
                             "[LBF] placing item {}/{} with id {} at [{}] in Layout {:?}",
                             self.problem.placed_item_qtys().sum::<usize>(),
@@ -96,6 +219,19 @@ impl LBFOptimizer {
                             i_opt.d_transf,
                             l_index
                         );
+                        n_unreported += 1;
+                        if let Some(on_improvement) = on_improvement.as_mut() {
+                            let since_last_report = last_report.map(|t| t.elapsed());
+                            if self
+                                .config
+                                .improvement_throttle
+                                .is_due(since_last_report, n_unreported)
+                            {
+                                on_improvement(&self.problem.create_solution(None));
+                                last_report = Some(Instant::now());
+                                n_unreported = 0;
+                            }
+                        }
                         #[allow(clippy::absurd_extreme_comparisons)]
                         if self.problem.placed_item_qtys().sum::<usize>() >= ITEM_LIMIT {
                             break 'outer;
@@ -105,6 +241,12 @@ impl LBFOptimizer {
                         match &mut self.problem {
                             Problem::BP(_) => break,
                             Problem::SP(sp_problem) => {
+                                let extendable = sp_problem.instance.fixed_strip_width.is_none()
+                                    && !self.instance.item_qty_unlimited(item_index);
+                                if !extendable {
+                                    //the strip is filled as far as possible with this item
+                                    break;
+                                }
                                 let new_width = sp_problem.strip_width() * 1.1;
                                 info!("[LBF] no placement found, extending strip width by 10% to {:.3}", new_width);
                                 sp_problem.modify_strip_in_back(new_width);
@@ -113,19 +255,53 @@ impl LBFOptimizer {
                     }
                 }
             }
+            if self.instance.item_qty_unlimited(item_index) {
+                info!(
+                    "[LBF] placed {} copies of item {} (unlimited demand)",
+                    self.problem.placed_item_qtys().nth(item_index).unwrap(),
+                    item_index
+                );
+            }
         }
         match &mut self.problem {
-            Problem::BP(_) => {}
+            Problem::BP(bp_problem) => {
+                let bin_limit_reached = self
+                    .config
+                    .max_bins
+                    .is_some_and(|max_bins| bp_problem.layouts().len() >= max_bins);
+                if bin_limit_reached {
+                    let n_unplaced = bp_problem
+                        .missing_item_qtys()
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| !self.instance.item_qty_unlimited(*i))
+                        .map(|(_, &qty)| qty.max(0) as usize)
+                        .sum::<usize>();
+                    info!(
+                        "[LBF] bin limit of {} reached, {} items left unplaced",
+                        bp_problem.layouts().len(),
+                        n_unplaced
+                    );
+                }
+            }
             Problem::SP(sp_problem) => {
-                sp_problem.fit_strip();
-                info!(
-                    "[LBF] fitted strip width to {:.3}",
-                    sp_problem.strip_width()
-                );
+                if sp_problem.instance.fixed_strip_width.is_none() {
+                    sp_problem.fit_strip();
+                    info!(
+                        "[LBF] fitted strip width to {:.3}",
+                        sp_problem.strip_width()
+                    );
+                }
             }
         }
 
         let solution: Solution = self.problem.create_solution(None);
+        if let Some(on_improvement) = on_improvement.as_mut() {
+            //placements held back by the throttle are reported with the final solution
+            if n_unreported > 0 {
+                on_improvement(&solution);
+            }
+        }
 
         info!(
             "[LBF] optimization finished in {:.3}ms ({} samples)",
@@ -138,8 +314,135 @@ impl LBFOptimizer {
             solution.n_items_placed(),
             solution.usage * 100.0
         );
-        solution
+        if maximize_value {
+            info!(
+                "[LBF] total value of placed items: {}",
+                solution.placed_value(&self.instance)
+            );
+        }
+        let tightness = tightness
+            .into_iter()
+            .filter_map(|(layout_idx, item_id, d_transf, n_feasible)| {
+                let LayoutIndex::Real(layout) = layout_idx else {
+                    unreachable!("items are placed in real layouts")
+                };
+                let pik = solution.layout_snapshots[layout]
+                    .placed_items
+                    .iter()
+                    .find(|(_, pi)| pi.item_id == item_id && pi.d_transf == d_transf)
+                    .map(|(pik, _)| pik)?;
+                Some(PlacementTightness {
+                    layout,
+                    pik,
+                    item_id,
+                    n_feasible,
+                })
+            })
+            .collect_vec();
+        (solution, tightness)
+    }
+}
+
+/// A group of items which is packed independently of the other groups, in its own bins
+#[derive(Debug, Clone)]
+pub struct BPGroup {
+    pub item_ids: Vec<usize>,
+    pub bin_ids: Vec<usize>,
+}
+
+/// Solves a bin packing instance of which the items are partitioned in independent groups (for example by material),
+/// each to be packed in its own bins.
+/// Every group is solved concurrently by a separate [`LBFOptimizer`], seeded by [`util::derive_rng`] with the index of the group,
+/// after which all layouts are merged into a single solution.
+/// Items which are not part of any group are not placed. The `max_bins` of the config applies to every group separately.
+pub fn solve_bp_groups(
+    instance: &BPInstance,
+    groups: &[BPGroup],
+    config: LBFConfig,
+    seed: u64,
+) -> Solution {
+    assert!(
+        groups.iter().flat_map(|g| &g.bin_ids).all_unique(),
+        "groups cannot share bins"
+    );
+    assert!(
+        groups.iter().flat_map(|g| &g.item_ids).all_unique(),
+        "groups cannot share items"
+    );
+
+    let group_solutions = groups
+        .par_iter()
+        .enumerate()
+        .map(|(i, group)| {
+            //the instance of a group keeps all items and bins, so their ids remain valid, but only demands its own ones
+            let items = instance
+                .items
+                .iter()
+                .map(|(item, qty)| match group.item_ids.contains(&item.id) {
+                    true => (item.clone(), *qty),
+                    false => (item.clone(), 0),
+                })
+                .collect_vec();
+            let bins = instance
+                .bins
+                .iter()
+                .map(|(bin, qty)| match group.bin_ids.contains(&bin.id) {
+                    true => (bin.clone(), *qty),
+                    false => (bin.clone(), 0),
+                })
+                .collect_vec();
+            let group_instance = Instance::BP(BPInstance::new(items, bins));
+            let rng = util::derive_rng(seed, i as u32);
+            LBFOptimizer::new(group_instance, config, rng).solve()
+        })
+        .collect::<Vec<_>>();
+
+    let mut problem = BPProblem::new(instance.clone());
+    for group_solution in group_solutions.iter() {
+        let all_layouts = problem.layout_indices().collect_vec();
+        problem.merge_residual_solution(&all_layouts, group_solution);
     }
+    let solution = problem.create_solution(None);
+    info!(
+        "[LBF] merged {} groups into {} layouts, containing {} items with a usage of {:.3}%",
+        groups.len(),
+        solution.layout_snapshots.len(),
+        solution.n_items_placed(),
+        solution.usage * 100.0
+    );
+    solution
+}
+
+/// Places an additional item in an existing bin packing solution of `instance`, without moving any of the placed items.
+/// The item is added to the instance with the next available id and placed in the existing bins if possible,
+/// otherwise a new bin is opened (if the stock and `max_bins` of the config allow it).
+/// Copies of other items which are missing from the solution are attempted to be placed as well.
+/// Returns the extended instance, to which the updated solution belongs.
+pub fn add_item_to_solution(
+    instance: &BPInstance,
+    solution: &Solution,
+    item: Item,
+    demand: usize,
+    config: LBFConfig,
+    rng: SmallRng,
+) -> (BPInstance, Solution) {
+    let mut problem = BPProblem::from_solution(instance.clone(), solution);
+    let item_id = problem.add_item(item, demand);
+    let extended_instance = problem.instance.clone();
+
+    let mut optimizer = LBFOptimizer {
+        instance: Instance::BP(extended_instance.clone()),
+        problem: problem.into(),
+        config,
+        rng,
+        sample_counter: 0,
+    };
+    let solution = optimizer.solve();
+    info!(
+        "[LBF] added item {} to the solution, {}/{} copies placed",
+        item_id, solution.placed_item_qtys[item_id], demand
+    );
+    (extended_instance, solution)
 }
 
 pub fn find_lbf_placement(
@@ -148,20 +451,150 @@ pub fn find_lbf_placement(
     config: &LBFConfig,
     rng: &mut impl Rng,
     sample_counter: &mut usize,
+    feasible_counter: &mut usize,
 ) -> Option<PlacingOption> {
-    //search all existing layouts and template layouts with remaining stock
-    let existing_layouts = problem.layout_indices();
-    let template_layouts = problem.template_layout_indices_with_stock();
+    //search all existing layouts and template layouts with remaining stock, as long as the bin limit is not reached
+    let bin_limit_reached = config
+        .max_bins
+        .is_some_and(|max_bins| problem.layouts().len() >= max_bins);
+    let template_layouts = problem
+        .template_layout_indices_with_stock()
+        .filter(|_| !bin_limit_reached);
+
+    let existing_layouts = match config.bin_selection {
+        BinSelection::FirstFit => Some(problem.layout_indices()),
+        BinSelection::BestFit => {
+            //search all existing layouts for the cheapest placement
+            let best = problem
+                .layout_indices()
+                .filter_map(|layout| {
+                    debug!("searching in layout {:?}", layout);
+                    sample_layout(
+                        problem,
+                        layout,
+                        item,
+                        config,
+                        rng,
+                        sample_counter,
+                        feasible_counter,
+                    )
+                })
+                .min_by_key(|(_, cost)| *cost);
+            if let Some((placing_opt, _)) = best {
+                return Some(placing_opt);
+            }
+            //only open a new bin if the item does not fit in any of the existing ones
+            None
+        }
+    };
 
-    //sequential search until a valid placement is found
-    for layout in existing_layouts.chain(template_layouts) {
+    //sequential search in the existing layouts until a valid placement is found
+    for layout in existing_layouts.into_iter().flatten() {
         debug!("searching in layout {:?}", layout);
-        if let Some(placing_opt) = sample_layout(problem, layout, item, config, rng, sample_counter)
-        {
+        if let Some((placing_opt, _)) = sample_layout(
+            problem,
+            layout,
+            item,
+            config,
+            rng,
+            sample_counter,
+            feasible_counter,
+        ) {
             return Some(placing_opt);
         }
     }
-    None
+
+    //open a new bin
+    match config.bin_opening {
+        BinOpening::InOrder => {
+            for layout in template_layouts {
+                debug!("searching in layout {:?}", layout);
+                if let Some((placing_opt, _)) = sample_layout(
+                    problem,
+                    layout,
+                    item,
+                    config,
+                    rng,
+                    sample_counter,
+                    feasible_counter,
+                ) {
+                    return Some(placing_opt);
+                }
+            }
+            None
+        }
+        BinOpening::BestFill => {
+            //total area of the items which still have to be placed, including the current one
+            let remaining_area = problem
+                .missing_item_qtys()
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !problem.instance().item_qty_unlimited(*i))
+                .map(|(i, &qty)| problem.instance().item(i).shape.area() * qty.max(0) as fsize)
+                .sum::<fsize>();
+
+            //expected fill of a bin if it were to be filled with the remaining items, ties broken by cost per unit of area
+            let expected_fill = |layout: LayoutIndex| {
+                let bin = &problem.get_layout(layout).bin;
+                let fill = fsize::min(remaining_area, bin.area) / bin.area;
+                let cost_per_area = bin.value as fsize / bin.area;
+                (
+                    NotNan::new(fill).expect("fill is NaN"),
+                    Reverse(NotNan::new(cost_per_area).expect("cost per area is NaN")),
+                )
+            };
+
+            template_layouts
+                .sorted_by_cached_key(|layout| Reverse(expected_fill(*layout)))
+                .find_map(|layout| {
+                    debug!("searching in layout {:?}", layout);
+                    sample_layout(
+                        problem,
+                        layout,
+                        item,
+                        config,
+                        rng,
+                        sample_counter,
+                        feasible_counter,
+                    )
+                })
+                .map(|(placing_opt, _)| placing_opt)
+        }
+    }
+}
+
+/// Copy of the item without the discrete rotations in which it is taller than `strip_height`.
+/// Returns `None` if no allowed rotation remains, continuous rotations are left untouched.
+pub fn prune_rotations(item: &Item, strip_height: fsize) -> Option<Item> {
+    let fits = |rotation: fsize| {
+        //the rotated bounding box of the item encloses the rotated item, so it is a cheap sufficient check
+        if item.shape.bbox().rotated_bbox(rotation).height() <= strip_height {
+            return true;
+        }
+        let rotated_bbox = item
+            .shape
+            .transform_clone(&Transformation::from_rotation(rotation))
+            .bbox();
+        rotated_bbox.height() <= strip_height
+    };
+    let allowed_rotation = match &item.allowed_rotation {
+        AllowedRotation::Continuous => AllowedRotation::Continuous,
+        AllowedRotation::None => match fits(0.0) {
+            true => AllowedRotation::None,
+            false => return None,
+        },
+        AllowedRotation::Discrete(angles) => {
+            let angles = angles.iter().copied().filter(|&r| fits(r)).collect_vec();
+            match angles.is_empty() {
+                true => return None,
+                false => AllowedRotation::Discrete(angles),
+            }
+        }
+    };
+    Some(Item {
+        allowed_rotation,
+        ..item.clone()
+    })
 }
 
 pub fn sample_layout(
@@ -171,7 +604,8 @@ pub fn sample_layout(
     config: &LBFConfig,
     rng: &mut impl Rng,
     sample_counter: &mut usize,
-) -> Option<PlacingOption> {
+    feasible_counter: &mut usize,
+) -> Option<(PlacingOption, LBFPlacingCost)> {
     let layout: &Layout = problem.get_layout(layout_idx);
     let cde = layout.cde();
     let irrel_hazards = match item.hazard_filter.as_ref() {
@@ -187,6 +621,18 @@ pub fn sample_layout(
         buffer
     };
 
+    //convert the preferred region of the item to the internal coordinate system of the bin
+    let preferred_region = item.preferred_region.as_ref().map(|pr| {
+        let corners = pr
+            .rect
+            .corners()
+            .map(|c| c.transform_clone(&layout.bin.pretransform));
+        PreferredRegion {
+            rect: AARectangle::from_points(&corners).expect("rectangle has corners"),
+            weight: pr.weight,
+        }
+    });
+
     let mut best: Option<(PlacingOption, LBFPlacingCost)> = None;
 
     //calculate the number of uniform and local search samples
@@ -194,14 +640,58 @@ pub fn sample_layout(
     let uni_sample_budget = config.n_samples - ls_sample_budget;
 
     //uniform sampling within the valid cells of the Hazard Proximity Grid, tracking the best valid insertion option
-    let mut hpg_sampler = HPGSampler::new(item, layout)?;
+    let mut hpg_sampler = match config.sampler_origin {
+        None => HPGSampler::new(item, layout)?,
+        Some(origin) => {
+            //convert the origin to the internal coordinate system of the bin
+            let origin = Point::from(origin).transform_clone(&layout.bin.pretransform);
+            HPGSampler::with_origin(item, layout, origin)?
+        }
+    };
+
+    //always try the trivial position in the bottom-left corner of the bin, so even tiny sample budgets can find it
+    let usable_bbox = layout.bin.usable.bbox();
+    let bl_rotations = match &item.allowed_rotation {
+        AllowedRotation::Discrete(angles) => angles.clone(),
+        AllowedRotation::None | AllowedRotation::Continuous => vec![0.0],
+    };
+    for rotation in bl_rotations {
+        let rotated_bbox = item
+            .shape
+            .transform_clone(&Transformation::from_rotation(rotation))
+            .bbox();
+        let d_transf = DTransformation::new(
+            rotation,
+            (
+                usable_bbox.x_min - rotated_bbox.x_min,
+                usable_bbox.y_min - rotated_bbox.y_min,
+            ),
+        );
+        buffer.transform_from(&item.shape, &d_transf.compose());
+        *sample_counter += 1;
+        if !cde.poly_collides(&buffer, &irrel_hazards) {
+            let cost = LBFPlacingCost::from_shape(&buffer, cde, preferred_region.as_ref());
+            if best.as_ref().is_none_or(|(_, best_cost)| cost < *best_cost) {
+                debug!("[BL] better: {}", &d_transf);
+                hpg_sampler.tighten(cost);
+                best = Some((
+                    PlacingOption {
+                        layout_idx,
+                        item_id: item.id,
+                        d_transf,
+                    },
+                    cost,
+                ));
+            }
+        }
+    }
 
     for i in 0..uni_sample_budget {
         let transform = hpg_sampler.sample(rng);
         if !cde.surrogate_collides(surrogate, &transform, &irrel_hazards) {
             //if no collision is detected on the surrogate, apply the transformation
             buffer.transform_from(&item.shape, &transform);
-            let cost = LBFPlacingCost::from_shape(&buffer);
+            let cost = LBFPlacingCost::from_shape(&buffer, cde, preferred_region.as_ref());
 
             //only validate the sample if it possibly can replace the current best
             let worth_testing = match (best.as_ref(), &cost) {
@@ -211,20 +701,26 @@ pub fn sample_layout(
                 (None, _) => true,
             };
 
-            if worth_testing && !cde.poly_collides(&buffer, &irrel_hazards) {
-                //sample is valid and improves on the current best
-                let p_opt = PlacingOption {
-                    layout_idx,
-                    item_id: item.id,
-                    d_transf: transform.decompose(),
-                };
-                hpg_sampler.tighten(cost);
-                debug!(
-                    "[UNI: {i}/{uni_sample_budget}] better: {} ",
-                    &p_opt.d_transf
-                );
-
-                best = Some((p_opt, cost));
+            //when recording tightness, every sample is validated to count the feasible ones
+            let validate = worth_testing || config.record_tightness;
+
+            if validate && !cde.poly_collides(&buffer, &irrel_hazards) {
+                *feasible_counter += 1;
+                if worth_testing {
+                    //sample is valid and improves on the current best
+                    let p_opt = PlacingOption {
+                        layout_idx,
+                        item_id: item.id,
+                        d_transf: transform.decompose(),
+                    };
+                    hpg_sampler.tighten(cost);
+                    debug!(
+                        "[UNI: {i}/{uni_sample_budget}] better: {} ",
+                        &p_opt.d_transf
+                    );
+
+                    best = Some((p_opt, cost));
+                }
             }
         }
     }
@@ -247,7 +743,7 @@ pub fn sample_layout(
         let transf = d_transf.compose();
         if !cde.surrogate_collides(surrogate, &transf, &irrel_hazards) {
             buffer.transform_from(&item.shape, &transf);
-            let cost = LBFPlacingCost::from_shape(&buffer);
+            let cost = LBFPlacingCost::from_shape(&buffer, cde, preferred_region.as_ref());
 
             //only validate the sample if it possibly can replace the current best
             let worth_testing = cost < *best_cost;
@@ -270,5 +766,18 @@ pub fn sample_layout(
 
     *sample_counter += ls_sampler.n_samples;
 
-    best.map(|(p_opt, _)| p_opt)
+    if config.contact_slide {
+        //close the remaining gap between the item and its neighbours
+        let mut slider = ContactSlider::new(item, cde, &irrel_hazards);
+        let d_transf = slider.slide(&best_opt.d_transf);
+        buffer.transform_from(&item.shape, &d_transf.compose());
+        let cost = LBFPlacingCost::from_shape(&buffer, cde, preferred_region.as_ref());
+        if cost < *best_cost {
+            debug!("[SLIDE] better: {}", &d_transf);
+            (best_opt.d_transf, *best_cost) = (d_transf, cost);
+        }
+        *sample_counter += slider.n_samples;
+    }
+
+    best
 }
@@ -6,6 +6,8 @@ pub mod io;
 pub mod lbf_config;
 pub mod lbf_cost;
 pub mod lbf_optimizer;
+pub mod opt;
 pub mod samplers;
+pub mod util;
 
 pub static EPOCH: Lazy<Instant> = Lazy::new(Instant::now);
@@ -1,23 +1,34 @@
+use std::cmp::Ordering;
 use std::fs;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::Path;
+use std::process;
 
 use clap::Parser as ClapParser;
-use log::{error, warn};
+use itertools::Itertools;
+use log::{error, info, warn};
 use mimalloc::MiMalloc;
 use rand::prelude::SmallRng;
 use rand::SeedableRng;
 
+use jagua_rs::entities::instances::instance::Instance;
+use jagua_rs::entities::instances::instance_generic::InstanceGeneric;
+use jagua_rs::entities::layout::Layout;
+use jagua_rs::entities::solution::Solution;
+use jagua_rs::fsize;
+use jagua_rs::io::export;
+use jagua_rs::io::json_solution::JsonContainer;
 use jagua_rs::io::parser;
 use jagua_rs::io::parser::Parser;
+use jagua_rs::io::surrogate_cache::SurrogateCache;
 use jagua_rs::util::polygon_simplification::PolySimplConfig;
 use lbf::io::cli::Cli;
 use lbf::io::json_output::JsonOutput;
-use lbf::io::layout_to_svg::s_layout_to_svg;
-use lbf::lbf_config::LBFConfig;
+use lbf::io::layout_to_svg::{s_layout_to_svg, unplaced_items_to_svg};
+use lbf::lbf_config::{BPObjective, LBFConfig};
 use lbf::lbf_optimizer::LBFOptimizer;
-use lbf::{io, EPOCH};
+use lbf::{io, util, EPOCH};
 
 #[global_allocator]
 static GLOBAL: MiMalloc = MiMalloc;
@@ -27,6 +38,26 @@ fn main() {
     let args = Cli::parse();
     io::init_logger(args.log_level);
 
+    if args.validate {
+        let json_output = io::read_json_output(args.input_file.as_path());
+        let violations = validate_solution(&json_output);
+        for violation in violations.iter() {
+            error!("[VALIDATE] {}", violation);
+        }
+        match violations.is_empty() {
+            true => info!("[VALIDATE] solution is valid"),
+            false => {
+                error!("[VALIDATE] solution has {} violation(s)", violations.len());
+                process::exit(1);
+            }
+        }
+        return;
+    }
+    let solution_folder = args
+        .solution_folder
+        .clone()
+        .expect("solution folder is required");
+
     let config = match args.config_file {
         None => {
             warn!("No config file provided, use --config-file to provide a custom config");
@@ -56,15 +87,85 @@ fn main() {
     };
 
     let parser = Parser::new(poly_simpl_config, config.cde_config, true);
-    let instance = parser.parse(&json_instance);
+    let parser = match args.surrogate_cache {
+        Some(dir) => parser.surrogate_cache(SurrogateCache::new(dir)),
+        None => parser,
+    };
+    let instance = parser.parse(&json_instance).unwrap_or_else(|err| {
+        error!("Instance could not be parsed: {}", err);
+        panic!();
+    });
 
-    let rng = match config.prng_seed {
-        Some(seed) => SmallRng::seed_from_u64(seed),
-        None => SmallRng::from_entropy(),
+    if !solution_folder.exists() {
+        fs::create_dir_all(&solution_folder)
+            .unwrap_or_else(|_| panic!("could not create solution folder: {:?}", solution_folder));
+    }
+
+    let input_file_stem = args.input_file.file_stem().unwrap().to_str().unwrap();
+
+    //writes the layouts of every intermediate solution of a run to numbered SVGs
+    let run_solve = |optimizer: &mut LBFOptimizer, run: Option<u32>| match args.dump_improvements {
+        false => optimizer.solve(),
+        true => {
+            let prefix = match run {
+                None => format!("sol_{}_improvement", input_file_stem),
+                Some(run) => format!("sol_{}_run_{}_improvement", input_file_stem, run),
+            };
+            let mut n_improvements = 0;
+            let mut dump = |solution: &Solution| {
+                for (i, s_layout) in solution.layout_snapshots.iter().enumerate() {
+                    let svg_path =
+                        solution_folder.join(format!("{}_{:04}_{}.svg", prefix, n_improvements, i));
+                    io::write_svg(
+                        &s_layout_to_svg(s_layout, &instance, config.svg_draw_options),
+                        Path::new(&svg_path),
+                    );
+                }
+                n_improvements += 1;
+            };
+            optimizer.solve_with_callback(Some(&mut dump))
+        }
     };
 
-    let mut optimizer = LBFOptimizer::new(instance.clone(), config, rng);
-    let solution = optimizer.solve();
+    let mut solution = match args.runs {
+        1 => {
+            let rng = match config.prng_seed {
+                Some(seed) => SmallRng::seed_from_u64(seed),
+                None => SmallRng::from_entropy(),
+            };
+            run_solve(&mut LBFOptimizer::new(instance.clone(), config, rng), None)
+        }
+        n_runs => {
+            let base_seed = config.prng_seed.unwrap_or_else(rand::random);
+            let instance_hash = instance.content_hash() ^ base_seed;
+            (0..n_runs)
+                .map(|run| {
+                    let rng = util::derive_rng(instance_hash, run);
+                    let mut optimizer = LBFOptimizer::new(instance.clone(), config, rng);
+                    let solution = run_solve(&mut optimizer, Some(run));
+                    info!(
+                        "[LBF] run {}/{}: {} items placed, usage of {:.3}%",
+                        run + 1,
+                        n_runs,
+                        solution.n_items_placed(),
+                        solution.usage * 100.0
+                    );
+                    solution
+                })
+                .max_by(|a, b| {
+                    let key = |s: &Solution| match (&instance, config.bp_objective) {
+                        (Instance::BP(_), BPObjective::MaxValue) => {
+                            (s.placed_value(&instance) as fsize, s.usage)
+                        }
+                        _ => (s.completeness(&instance), s.usage),
+                    };
+                    key(a).partial_cmp(&key(b)).unwrap_or(Ordering::Equal)
+                })
+                .expect("at least one run")
+        }
+    };
+    //number the layouts consistently across runs
+    solution.sort_layouts();
 
     let json_output = JsonOutput {
         instance: json_instance.clone(),
@@ -72,29 +173,93 @@ fn main() {
         config,
     };
 
-    if !args.solution_folder.exists() {
-        fs::create_dir_all(&args.solution_folder).unwrap_or_else(|_| {
-            panic!(
-                "could not create solution folder: {:?}",
-                args.solution_folder
-            )
-        });
-    }
-
-    let input_file_stem = args.input_file.file_stem().unwrap().to_str().unwrap();
-
-    let solution_path = args
-        .solution_folder
-        .join(format!("sol_{}.json", input_file_stem));
+    let solution_path = solution_folder.join(format!("sol_{}.json", input_file_stem));
     io::write_json_output(&json_output, Path::new(&solution_path));
 
+    if args.esicup {
+        let xml = export::esicup_solution_xml(&solution, &instance, &json_instance.name);
+        let xml_path = solution_folder.join(format!("sol_{}.xml", input_file_stem));
+        io::write_esicup_xml(&xml, Path::new(&xml_path));
+    }
+
     for (i, s_layout) in solution.layout_snapshots.iter().enumerate() {
-        let svg_path = args
-            .solution_folder
-            .join(format!("sol_{}_{}.svg", input_file_stem, i));
+        let svg_path = solution_folder.join(format!("sol_{}_{}.svg", input_file_stem, i));
         io::write_svg(
             &s_layout_to_svg(s_layout, &instance, config.svg_draw_options),
             Path::new(&svg_path),
         );
     }
+
+    if let Some(unplaced_svg) = unplaced_items_to_svg(&solution, &instance, config.svg_draw_options)
+    {
+        let svg_path = solution_folder.join(format!("sol_{}_unplaced.svg", input_file_stem));
+        io::write_svg(&unplaced_svg, Path::new(&svg_path));
+    }
+}
+
+/// Rebuilds the solution of a solution file and returns a description of every violation:
+/// bins used more often than their stock, items placed more often than demanded, and any collisions.
+fn validate_solution(json_output: &JsonOutput) -> Vec<String> {
+    let config = &json_output.config;
+    let poly_simpl_config = match config.poly_simpl_tolerance {
+        Some(tolerance) => PolySimplConfig::Enabled { tolerance },
+        None => PolySimplConfig::Disabled,
+    };
+    let parser = Parser::new(poly_simpl_config, config.cde_config, true);
+    let json_layouts = &json_output.solution.layouts;
+    let mut violations = vec![];
+
+    if let Some(json_bins) = &json_output.instance.bins {
+        let bin_counts = json_layouts
+            .iter()
+            .filter_map(|jl| match jl.container {
+                JsonContainer::Bin { index } => Some(index),
+                JsonContainer::Strip { .. } => None,
+            })
+            .counts();
+        for (index, count) in bin_counts.into_iter().sorted() {
+            let stock = json_bins.get(index).and_then(|b| b.stock);
+            if stock.is_some_and(|stock| count as u64 > stock) {
+                violations.push(format!(
+                    "bin {} is used {} times, but only {} are in stock",
+                    index,
+                    count,
+                    stock.unwrap()
+                ));
+            }
+        }
+        if !violations.is_empty() {
+            //the solution cannot be rebuilt
+            return violations;
+        }
+    }
+
+    let (instance, solution) =
+        match parser.parse_and_build_solution(&json_output.instance, json_layouts) {
+            Ok(parsed) => parsed,
+            Err(err) => return vec![format!("solution could not be rebuilt: {}", err)],
+        };
+
+    for (i, &qty) in solution.placed_item_qtys.iter().enumerate() {
+        if !instance.item_qty_unlimited(i) && qty > instance.item_qty(i) {
+            violations.push(format!(
+                "item {} is placed {} times, but only {} are demanded",
+                i,
+                qty,
+                instance.item_qty(i)
+            ));
+        }
+    }
+
+    for (i, ls) in solution.layout_snapshots.iter().enumerate() {
+        let layout = Layout::from_snapshot(ls);
+        for (pik, collisions) in layout.collisions() {
+            let pi = &layout.placed_items()[pik];
+            violations.push(format!(
+                "item {} in layout {} at [{}] collides with {:?}",
+                pi.item_id, i, pi.d_transf, collisions
+            ));
+        }
+    }
+    violations
 }
@@ -0,0 +1 @@
+pub mod shelf_optimizer;
@@ -0,0 +1,179 @@
+use std::cmp::Reverse;
+use std::time::Instant;
+
+use log::{info, warn};
+use ordered_float::NotNan;
+
+use jagua_rs::collision_detection::hazard_filter;
+use jagua_rs::entities::instances::instance_generic::{InstanceGeneric, UNLIMITED_ITEM_QTY};
+use jagua_rs::entities::instances::strip_packing::SPInstance;
+use jagua_rs::entities::item::Item;
+use jagua_rs::entities::placing_option::PlacingOption;
+use jagua_rs::entities::problems::problem_generic::{ProblemGeneric, STRIP_LAYOUT_IDX};
+use jagua_rs::entities::problems::strip_packing::SPProblem;
+use jagua_rs::entities::solution::Solution;
+use jagua_rs::fsize;
+use jagua_rs::geometry::geo_enums::AllowedRotation;
+use jagua_rs::geometry::geo_traits::{Shape, Transformable};
+use jagua_rs::geometry::primitives::aa_rectangle::AARectangle;
+use jagua_rs::geometry::transformation::Transformation;
+use jagua_rs::util::config::CDEConfig;
+use jagua_rs::util::trace::PhaseSpan;
+use jagua_rs::PI;
+
+/// Gap between consecutive items and shelves, as a fraction of the strip height
+const SHELF_GAP_RATIO: fsize = 1e-4;
+
+/// Fast, rough optimizer for strip packing problems, based on the bounding boxes of the items (next fit decreasing).
+/// The strip has a fixed height and a variable width, so the shelves are columns spanning the height of the strip:
+/// items are stacked bottom-to-top in shelves of decreasing width, shelves are added left-to-right.
+/// Items which do not fit in the strip height in any allowed orientation are left unplaced,
+/// see [`SPProblem::missing_item_qtys`](ProblemGeneric::missing_item_qtys).
+/// The CDE is only used to validate the final placements.
+/// Useful to quickly obtain an upper bound on the strip width.
+pub struct ShelfOptimizerSPP {
+    pub problem: SPProblem,
+}
+
+impl ShelfOptimizerSPP {
+    pub fn new(instance: SPInstance, cde_config: CDEConfig) -> Self {
+        //upper bound of the strip width: all items next to each other
+        let max_width = instance
+            .items
+            .iter()
+            .filter(|(_, qty)| *qty != UNLIMITED_ITEM_QTY)
+            .map(|(item, qty)| item.shape.diameter() * *qty as fsize)
+            .sum::<fsize>();
+        let strip_width = instance.fixed_strip_width.unwrap_or(max_width.max(1.0));
+        let problem = SPProblem::new(instance, strip_width, cde_config);
+
+        Self { problem }
+    }
+
+    pub fn solve(&mut self) -> Solution {
+        let _span = PhaseSpan::enter("shelf solve");
+        let start = Instant::now();
+        let strip_height = self.problem.strip_height();
+        let strip_width_limit = self.problem.instance.fixed_strip_width;
+
+        //determine the orientation of every item: the narrowest one which fits in the strip height
+        let mut oriented_items = vec![];
+        for (item_id, (item, qty)) in self.problem.instance.items.iter().enumerate() {
+            if self.problem.instance.item_qty_unlimited(item_id) {
+                warn!("[SHELF] item {item_id} has an unlimited demand, not placed");
+                continue;
+            }
+            match narrowest_fitting_orientation(item, strip_height) {
+                Some((rotation, bbox)) => {
+                    oriented_items.extend((0..*qty).map(|_| (item_id, rotation, bbox.clone())))
+                }
+                None => {
+                    warn!("[SHELF] item {item_id} does not fit in the strip height, not placed")
+                }
+            }
+        }
+
+        //sort by decreasing width, ties broken by decreasing height
+        oriented_items.sort_by_key(|(_, _, bbox)| {
+            Reverse((
+                NotNan::new(bbox.width()).unwrap(),
+                NotNan::new(bbox.height()).unwrap(),
+            ))
+        });
+
+        //small gap between the bounding boxes, touching shapes are considered colliding
+        let gap = strip_height * SHELF_GAP_RATIO;
+
+        //next fit decreasing width
+        let (mut shelf_x, mut shelf_width, mut y) = (gap, 0.0, gap);
+        let mut positions = vec![];
+        for (item_id, rotation, bbox) in oriented_items {
+            if y + bbox.height() + gap > strip_height {
+                //open a new shelf
+                shelf_x += shelf_width + gap;
+                shelf_width = 0.0;
+                y = gap;
+            }
+            shelf_width = fsize::max(shelf_width, bbox.width());
+            if strip_width_limit.is_some_and(|w| shelf_x + bbox.width() + gap > w) {
+                continue;
+            }
+            let translation = (shelf_x - bbox.x_min, y - bbox.y_min);
+            positions.push((item_id, rotation, translation));
+            y += bbox.height() + gap;
+        }
+
+        //validate and place the items
+        let mut n_invalid = 0;
+        for (item_id, rotation, translation) in positions {
+            let item = self.problem.instance.item(item_id);
+            let transform = Transformation::from_rotation(rotation).translate(translation);
+            let layout = &self.problem.layout;
+            let irrel_hazards = match item.hazard_filter.as_ref() {
+                None => vec![],
+                Some(hf) => {
+                    hazard_filter::generate_irrelevant_hazards(hf, layout.cde().all_hazards())
+                }
+            };
+            let shape = item.shape.transform_clone(&transform);
+            match layout.cde().poly_collides(&shape, &irrel_hazards) {
+                true => n_invalid += 1,
+                false => {
+                    self.problem.place_item(PlacingOption {
+                        layout_idx: STRIP_LAYOUT_IDX,
+                        item_id,
+                        d_transf: transform.decompose(),
+                    });
+                }
+            }
+        }
+        if n_invalid > 0 {
+            warn!("[SHELF] {n_invalid} items collided during validation, not placed");
+        }
+
+        if strip_width_limit.is_none() {
+            self.problem.fit_strip();
+        }
+
+        let solution = self.problem.create_solution(None);
+        let n_unplaced = self
+            .problem
+            .missing_item_qtys()
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !self.problem.instance.item_qty_unlimited(*i))
+            .map(|(_, &qty)| qty.max(0) as usize)
+            .sum::<usize>();
+
+        info!(
+            "[SHELF] finished in {:.3}ms, strip width of {:.3} ({} items placed, {} unplaced)",
+            start.elapsed().as_secs_f64() * 1000.0,
+            self.problem.strip_width(),
+            solution.n_items_placed(),
+            n_unplaced
+        );
+
+        solution
+    }
+}
+
+/// Returns the rotation and resulting bounding box of the narrowest orientation of the item which fits within the height.
+fn narrowest_fitting_orientation(item: &Item, height: fsize) -> Option<(fsize, AARectangle)> {
+    let rotations = match &item.allowed_rotation {
+        AllowedRotation::None => vec![0.0],
+        AllowedRotation::Continuous => vec![0.0, PI / 2.0],
+        AllowedRotation::Discrete(rotations) => rotations.clone(),
+    };
+
+    rotations
+        .into_iter()
+        .map(|r| {
+            let bbox = item
+                .shape
+                .transform_clone(&Transformation::from_rotation(r))
+                .bbox();
+            (r, bbox)
+        })
+        .filter(|(_, bbox)| bbox.height() * (1.0 + 2.0 * SHELF_GAP_RATIO) <= height)
+        .min_by_key(|(_, bbox)| NotNan::new(bbox.width()).unwrap())
+}
@@ -0,0 +1,114 @@
+use jagua_rs::collision_detection::cd_engine::CDEngine;
+use jagua_rs::collision_detection::hazard::HazardEntity;
+use jagua_rs::entities::item::Item;
+use jagua_rs::fsize;
+use jagua_rs::geometry::d_transformation::DTransformation;
+use jagua_rs::geometry::geo_traits::{Shape, TransformableFrom};
+use jagua_rs::geometry::primitives::simple_polygon::SimplePolygon;
+
+/// Slides stop once the step size is below this fraction of the item's diameter
+const SLIDE_TOLERANCE: fsize = 1e-4;
+
+/// Maximum number of left-down rounds per slide
+const MAX_SLIDE_ROUNDS: usize = 10;
+
+/// Moves feasible placements of an item towards the bottom-left of a layout, until they make contact with a hazard.
+/// The item alternately slides left and down, as long as one of them still makes progress.
+/// Closes the small gaps that samples, drawn from continuous distributions, leave between items.
+pub struct ContactSlider<'a> {
+    item: &'a Item,
+    cde: &'a CDEngine,
+    irrel_hazards: &'a [HazardEntity],
+    buffer: SimplePolygon,
+    tolerance: fsize,
+    max_distance: fsize,
+    pub(crate) n_samples: usize,
+}
+
+impl<'a> ContactSlider<'a> {
+    pub fn new(item: &'a Item, cde: &'a CDEngine, irrel_hazards: &'a [HazardEntity]) -> Self {
+        let buffer = {
+            let mut buffer = (*item.shape).clone();
+            buffer.surrogate = None;
+            buffer
+        };
+        let tolerance = item.shape.diameter() * SLIDE_TOLERANCE;
+        let max_distance = cde.bbox().diameter();
+        Self {
+            item,
+            cde,
+            irrel_hazards,
+            buffer,
+            tolerance,
+            max_distance,
+            n_samples: 0,
+        }
+    }
+
+    /// Slides a feasible placement left and down until it is in contact with a hazard.
+    /// Returns the transformation of the slid placement, which is feasible as well.
+    pub fn slide(&mut self, d_transf: &DTransformation) -> DTransformation {
+        let bbox = self.item.shape.bbox();
+        let (mut tx, mut ty) = d_transf.translation();
+        let rotation = d_transf.rotation();
+
+        for _ in 0..MAX_SLIDE_ROUNDS {
+            let dx = self.max_displacement(rotation, (tx, ty), (-1.0, 0.0), bbox.width());
+            tx -= dx;
+            let dy = self.max_displacement(rotation, (tx, ty), (0.0, -1.0), bbox.height());
+            ty -= dy;
+            if dx < self.tolerance && dy < self.tolerance {
+                break;
+            }
+        }
+
+        DTransformation::new(rotation, (tx, ty))
+    }
+
+    /// Furthest distance the item can be moved in `direction` from a feasible position, without colliding.
+    /// The step is doubled as long as the item remains feasible, followed by a bisection to the point of contact.
+    /// Steps are capped by the extent of the item in the direction, so it cannot jump over hazards in between.
+    /// The displacement never exceeds the diameter of the engine, if no contact is found by then the last feasible distance is returned.
+    fn max_displacement(
+        &mut self,
+        rotation: fsize,
+        (tx, ty): (fsize, fsize),
+        (dir_x, dir_y): (fsize, fsize),
+        max_step: fsize,
+    ) -> fsize {
+        let mut feasible = 0.0;
+        let mut step = self.tolerance;
+
+        //expand until a collision is found
+        let mut infeasible = loop {
+            if feasible >= self.max_distance {
+                return feasible;
+            }
+            let d = fsize::min(feasible + step, self.max_distance);
+            match self.collides(rotation, (tx + dir_x * d, ty + dir_y * d)) {
+                true => break d,
+                false => {
+                    feasible = d;
+                    step = fsize::min(step * 2.0, max_step);
+                }
+            }
+        };
+
+        //bisect between the last feasible and first infeasible distance
+        while infeasible - feasible > self.tolerance {
+            let d = (feasible + infeasible) / 2.0;
+            match self.collides(rotation, (tx + dir_x * d, ty + dir_y * d)) {
+                true => infeasible = d,
+                false => feasible = d,
+            }
+        }
+        feasible
+    }
+
+    fn collides(&mut self, rotation: fsize, translation: (fsize, fsize)) -> bool {
+        self.n_samples += 1;
+        let transf = DTransformation::new(rotation, translation).compose();
+        self.buffer.transform_from(&self.item.shape, &transf);
+        self.cde.poly_collides(&self.buffer, self.irrel_hazards)
+    }
+}
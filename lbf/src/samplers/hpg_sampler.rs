@@ -8,6 +8,7 @@ use jagua_rs::entities::layout::Layout;
 use jagua_rs::fsize;
 use jagua_rs::geometry::geo_traits::Shape;
 use jagua_rs::geometry::primitives::aa_rectangle::AARectangle;
+use jagua_rs::geometry::primitives::point::Point;
 use jagua_rs::geometry::transformation::Transformation;
 
 use crate::lbf_cost::LBFPlacingCost;
@@ -28,6 +29,11 @@ pub struct HPGSampler<'a> {
 
 impl<'a> HPGSampler<'a> {
     pub fn new(item: &'a Item, layout: &Layout) -> Option<HPGSampler<'a>> {
+        Self::with_origin(item, layout, Point(0.0, 0.0))
+    }
+
+    /// Creates a sampler which samples translations relative to `origin`, see [UniformAARectSampler::with_origin].
+    pub fn with_origin(item: &'a Item, layout: &Layout, origin: Point) -> Option<HPGSampler<'a>> {
         let poi = &item.shape.poi;
         let bin_bbox = layout.bin.bbox();
 
@@ -45,7 +51,7 @@ impl<'a> HPGSampler<'a> {
                 //(at low densities, the cells could extend significantly beyond the layout's bbox)
                 AARectangle::from_intersection(&c.bbox, &bin_bbox)
             })
-            .map(|bbox| UniformAARectSampler::new(bbox, item))
+            .map(|bbox| UniformAARectSampler::with_origin(bbox, item, origin))
             .collect_vec();
 
         let coverage_area = cell_samplers.iter().map(|s| s.bbox.area()).sum();
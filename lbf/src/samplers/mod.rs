@@ -1,3 +1,4 @@
+pub mod contact_slider;
 pub mod hpg_sampler;
 pub mod ls_sampler;
 pub mod rotation_distr;
@@ -5,12 +5,15 @@ use jagua_rs::entities::item::Item;
 use jagua_rs::fsize;
 use jagua_rs::geometry::d_transformation::DTransformation;
 use jagua_rs::geometry::primitives::aa_rectangle::AARectangle;
+use jagua_rs::geometry::primitives::point::Point;
 
 use crate::samplers::rotation_distr::UniformRotDistr;
 
 /// Samples a `DTransformation` from a uniform distribution over a given `AARectangle` and a `UniformRotDistr`.
+/// Translations are sampled relative to an origin.
 pub struct UniformAARectSampler {
     pub bbox: AARectangle,
+    pub origin: Point,
     pub uniform_x: Uniform<fsize>,
     pub uniform_y: Uniform<fsize>,
     pub uniform_r: UniformRotDistr,
@@ -18,11 +21,16 @@ pub struct UniformAARectSampler {
 
 impl UniformAARectSampler {
     pub fn new(bbox: AARectangle, item: &Item) -> Self {
-        let uniform_x = Uniform::new(bbox.x_min, bbox.x_max);
-        let uniform_y = Uniform::new(bbox.y_min, bbox.y_max);
+        Self::with_origin(bbox, item, Point(0.0, 0.0))
+    }
+
+    pub fn with_origin(bbox: AARectangle, item: &Item, origin: Point) -> Self {
+        let uniform_x = Uniform::new(bbox.x_min - origin.0, bbox.x_max - origin.0);
+        let uniform_y = Uniform::new(bbox.y_min - origin.1, bbox.y_max - origin.1);
         let uniform_r = UniformRotDistr::from_item(item);
         Self {
             bbox,
+            origin,
             uniform_x,
             uniform_y,
             uniform_r,
@@ -31,8 +39,8 @@ impl UniformAARectSampler {
 
     pub fn sample(&self, rng: &mut impl Rng) -> DTransformation {
         let r_sample = self.uniform_r.sample(rng);
-        let x_sample = self.uniform_x.sample(rng);
-        let y_sample = self.uniform_y.sample(rng);
+        let x_sample = self.origin.0 + self.uniform_x.sample(rng);
+        let y_sample = self.origin.1 + self.uniform_y.sample(rng);
 
         DTransformation::new(r_sample, (x_sample, y_sample))
     }
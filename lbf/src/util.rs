@@ -0,0 +1,73 @@
+use itertools::Itertools;
+use jagua_rs::entities::instances::instance::Instance;
+use jagua_rs::entities::solution::Solution;
+use jagua_rs::geometry::d_transformation::DTransformation;
+use rand::prelude::SmallRng;
+use rand::SeedableRng;
+
+use crate::lbf_config::LBFConfig;
+use crate::lbf_optimizer::LBFOptimizer;
+
+/// Derives a PRNG seed from the hash of an instance (see `Instance::content_hash`) and the index of a run.
+/// The same instance and run index always result in the same seed, different run indices in unrelated seeds.
+/// Mixes both inputs with the finalizer of [SplitMix64](https://prng.di.unimi.it/splitmix64.c).
+pub fn derive_seed(instance_hash: u64, run_index: u32) -> u64 {
+    let mut z = instance_hash.wrapping_add(
+        (run_index as u64)
+            .wrapping_add(1)
+            .wrapping_mul(0x9E3779B97F4A7C15),
+    );
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+/// Creates a [SmallRng] seeded with [`derive_seed`]
+pub fn derive_rng(instance_hash: u64, run_index: u32) -> SmallRng {
+    SmallRng::seed_from_u64(derive_seed(instance_hash, run_index))
+}
+
+/// Solves the instance on a single-threaded and on a multithreaded [rayon] pool, and panics if the solutions differ.
+/// Both runs are seeded with `config.prng_seed` (0 if absent), so any difference is caused by the threading.
+pub fn assert_deterministic(instance: &Instance, config: LBFConfig) {
+    let n_threads = std::thread::available_parallelism().map_or(2, |n| n.get().max(2));
+    let solve_on = |n_threads: usize| {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(n_threads)
+            .build()
+            .expect("failed to build thread pool");
+        let rng = SmallRng::seed_from_u64(config.prng_seed.unwrap_or(0));
+        pool.install(|| LBFOptimizer::new(instance.clone(), config, rng).solve())
+    };
+
+    let single = solution_summary(&solve_on(1));
+    let multi = solution_summary(&solve_on(n_threads));
+
+    assert_eq!(
+        single.len(),
+        multi.len(),
+        "number of layouts differs between 1 and {n_threads} threads"
+    );
+    for (i, (s, m)) in single.iter().zip(multi.iter()).enumerate() {
+        assert_eq!(
+            s, m,
+            "layout {i} differs between 1 and {n_threads} threads (bin id, [(item id, transformation)])"
+        );
+    }
+}
+
+/// Bin id and placements of every layout of a solution
+fn solution_summary(solution: &Solution) -> Vec<(usize, Vec<(usize, DTransformation)>)> {
+    solution
+        .layout_snapshots
+        .iter()
+        .map(|sl| {
+            let placements = sl
+                .placed_items
+                .values()
+                .map(|pi| (pi.item_id, pi.d_transf))
+                .collect_vec();
+            (sl.bin.id, placements)
+        })
+        .collect()
+}
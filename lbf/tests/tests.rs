@@ -1,22 +1,115 @@
 #[cfg(test)]
 mod tests {
     use std::path::Path;
+    use std::time::{Duration, Instant};
 
     use rand::prelude::IteratorRandom;
     use rand::prelude::SmallRng;
     use rand::{Rng, SeedableRng};
     use test_case::test_case;
 
+    use jagua_rs::collision_detection::hazard::HazardEntity;
+    use jagua_rs::entities::instances::bin_packing::BPInstance;
+    use jagua_rs::entities::instances::instance::{Instance, InstanceBuilder, InstanceContainer};
+    use jagua_rs::entities::instances::instance_generic::InstanceGeneric;
+    use jagua_rs::entities::item::Item;
+    use jagua_rs::entities::layout::{Layout, LayoutSnapshot};
+    use jagua_rs::entities::problems::bin_packing::{BPProblem, PlacementRejection};
+    use jagua_rs::entities::problems::problem::Problem;
     use jagua_rs::entities::problems::problem_generic::LayoutIndex;
     use jagua_rs::entities::problems::problem_generic::ProblemGeneric;
+    use jagua_rs::entities::solution::Solution;
+    use jagua_rs::geometry::d_transformation::DTransformation;
+    use jagua_rs::geometry::geo_enums::{AllowedRotation, BorderPosition};
+    use jagua_rs::geometry::geo_traits::{
+        CollidesWith, DistanceFrom, Shape, Transformable, TransformableFrom,
+    };
+    use jagua_rs::geometry::minkowski::minkowski_sum;
+    use jagua_rs::geometry::nfp::{no_fit_polygon, NoFitPolygon};
+    use jagua_rs::geometry::offset::{offset_polygon, OffsetJoin};
+    use jagua_rs::geometry::primitives::aa_rectangle::AARectangle;
+    use jagua_rs::geometry::primitives::circle::Circle;
+    use jagua_rs::geometry::primitives::edge::Edge;
+    use jagua_rs::geometry::primitives::point::Point;
+    use jagua_rs::geometry::primitives::simple_polygon::SimplePolygon;
+    use jagua_rs::geometry::shape_modification::convex_decompose;
+    use jagua_rs::geometry::transformation::Transformation;
+    use jagua_rs::io::json_instance::JsonInstance;
+    use jagua_rs::io::json_solution::JsonLayout;
+    use jagua_rs::io::parse_error::{ParseError, PolygonDefect, ShapeOwner};
+    use jagua_rs::io::parser;
     use jagua_rs::io::parser::Parser;
-    use jagua_rs::util::polygon_simplification::PolySimplConfig;
+    use jagua_rs::io::surrogate_cache::SurrogateCache;
+    use jagua_rs::util::assertions;
+    use jagua_rs::util::config::CDEConfig;
+    use jagua_rs::util::polygon_simplification::{densify, PolySimplConfig};
     use lbf::io;
-    use lbf::lbf_config::LBFConfig;
-    use lbf::lbf_optimizer::LBFOptimizer;
+    use lbf::lbf_config::{ImprovementThrottle, LBFConfig};
+    use lbf::lbf_optimizer::{add_item_to_solution, prune_rotations, LBFOptimizer};
+    use lbf::opt::shelf_optimizer::ShelfOptimizerSPP;
+    use lbf::samplers::contact_slider::ContactSlider;
+    use lbf::util::assert_deterministic;
 
     const N_ITEMS_TO_REMOVE: usize = 5;
 
+    fn rect(w: f32, h: f32) -> SimplePolygon {
+        SimplePolygon::from(AARectangle::new(0.0, 0.0, w, h))
+    }
+
+    /// U-shape of 5 by 5, with legs of 1.5 wide, a base of 1 high and a notch of 2 wide between both legs
+    fn u_shape() -> SimplePolygon {
+        SimplePolygon::new(
+            [
+                (0.0, 0.0),
+                (5.0, 0.0),
+                (5.0, 5.0),
+                (3.5, 5.0),
+                (3.5, 1.0),
+                (1.5, 1.0),
+                (1.5, 5.0),
+                (0.0, 5.0),
+            ]
+            .map(Point::from)
+            .to_vec(),
+        )
+    }
+
+    /// Default config with fewer samples, to keep the tests fast
+    fn lbf_config() -> LBFConfig {
+        LBFConfig {
+            n_samples: 100,
+            ..Default::default()
+        }
+    }
+
+    /// Parses `../assets/<name>.json` without polygon simplification
+    fn parse_asset(name: &str, cde_config: CDEConfig) -> Instance {
+        let json_instance = io::read_json_instance(Path::new(&format!("../assets/{name}.json")));
+        Parser::new(PolySimplConfig::Disabled, cde_config, true)
+            .parse(&json_instance)
+            .unwrap()
+    }
+
+    /// Bin packing instance with a single bin (with a stock of 1), none of the shapes are centered
+    fn bp_instance(
+        bin: SimplePolygon,
+        items: Vec<(SimplePolygon, usize, AllowedRotation)>,
+        cde_config: CDEConfig,
+    ) -> BPInstance {
+        let builder = InstanceBuilder::new(InstanceContainer::Bins(vec![(bin, 1)]), cde_config)
+            .center_polygons(false);
+        let instance = items
+            .into_iter()
+            .fold(builder, |b, (shape, qty, rotation)| {
+                b.add_item(shape, qty, rotation)
+            })
+            .build();
+        let Instance::BP(instance) = instance else {
+            unreachable!()
+        };
+        instance
+    }
+
     #[test_case("../assets/swim.json"; "swim")]
     #[test_case("../assets/shirts.json"; "shirts")]
     #[test_case("../assets/trousers.json"; "trousers")]
@@ -40,7 +133,7 @@ mod tests {
         };
 
         let parser = Parser::new(poly_simpl_config, config.cde_config, true);
-        let instance = parser.parse(&json_instance);
+        let instance = parser.parse(&json_instance).unwrap();
 
         let mut optimizer = LBFOptimizer::new(instance.clone(), config, SmallRng::seed_from_u64(0));
 
@@ -76,4 +169,1542 @@ mod tests {
             optimizer.solve();
         }
     }
+
+    #[test]
+    fn centroid_with_off_center_hole() {
+        let square = SimplePolygon::from(AARectangle::new(0.0, 0.0, 10.0, 10.0));
+        let hole = SimplePolygon::from(AARectangle::new(6.0, 6.0, 8.0, 8.0));
+
+        //(100 * 5 - 4 * 7) / 96
+        let expected = 472.0 / 96.0;
+        let Point(x, y) = square.centroid_with_holes(&[&hole]);
+        assert!((x - expected).abs() < 1e-4 && (y - expected).abs() < 1e-4);
+        assert!(
+            x < 5.0 && y < 5.0,
+            "centroid should shift away from the hole"
+        );
+
+        let Point(x, y) = square.centroid_with_holes(&[]);
+        assert!((x - 5.0).abs() < 1e-4 && (y - 5.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn convex_decomposition_of_concave_shape() {
+        let is_convex = |p: &SimplePolygon| {
+            let n = p.number_of_points();
+            (0..n).all(|i| {
+                let (a, b, c) = (
+                    p.get_point(i),
+                    p.get_point((i + 1) % n),
+                    p.get_point((i + 2) % n),
+                );
+                (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0) >= -1e-4
+            })
+        };
+        let u_shape = u_shape();
+        let parts = convex_decompose(&u_shape);
+        //at least one part for every leg and one for the base
+        assert!(parts.len() >= 3, "{}", parts.len());
+        assert!(parts.iter().all(is_convex));
+        let area = parts.iter().map(|p| p.area).sum::<f32>();
+        assert!((area - u_shape.area).abs() < 1e-3, "{area}");
+
+        //convex shapes are returned as a single part
+        let square = rect(2.0, 2.0);
+        assert_eq!(convex_decompose(&square).len(), 1);
+    }
+
+    #[test]
+    fn minkowski_sum_of_square_and_triangle() {
+        let square = SimplePolygon::from(AARectangle::new(0.0, 0.0, 2.0, 2.0));
+        let triangle = SimplePolygon::new(vec![Point(0.0, 0.0), Point(1.0, 0.0), Point(0.0, 1.0)]);
+
+        //the corner of the 3x3 square opposite to the right angle of the triangle is cut off
+        let sum = minkowski_sum(&square, &triangle);
+        assert_eq!(sum.number_of_points(), 5);
+        assert!((sum.area - 8.5).abs() < 1e-4);
+        assert!(sum.points.contains(&Point(3.0, 2.0)) && sum.points.contains(&Point(2.0, 3.0)));
+
+        //parallel edges are merged
+        let unit = SimplePolygon::from(AARectangle::new(0.0, 0.0, 1.0, 1.0));
+        let sum = minkowski_sum(&square, &unit);
+        assert_eq!(sum.number_of_points(), 4);
+        assert!((sum.area - 9.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn no_fit_polygon_of_convex_and_concave_shapes() {
+        let square = |r: f32| SimplePolygon::from(AARectangle::new(-r, -r, r, r));
+        //collinear vertex on the bottom edge
+        let orbiting = SimplePolygon::new(
+            [
+                (-0.5, -0.5),
+                (0.0, -0.5),
+                (0.5, -0.5),
+                (0.5, 0.5),
+                (-0.5, 0.5),
+            ]
+            .map(Point::from)
+            .to_vec(),
+        );
+        let NoFitPolygon::Convex(nfp) = no_fit_polygon(&square(1.0), &orbiting) else {
+            panic!("NFP of two convex polygons should be convex")
+        };
+        assert_eq!(nfp.number_of_points(), 4);
+        assert!((nfp.area - 9.0).abs() < 1e-4, "{}", nfp.area);
+
+        //a square of 2 wide fits exactly in the notch of the U-shape
+        let u_shape = u_shape();
+        let nfp = no_fit_polygon(&u_shape, &square(1.0));
+        assert!(matches!(nfp, NoFitPolygon::Union(_)));
+        let overlaps = |p: Point| {
+            nfp.parts()
+                .iter()
+                .any(|part| part.distance_from_border(&p).0 == BorderPosition::Interior)
+        };
+        assert!(!overlaps(Point(2.5, 3.0)), "exact fit in the notch");
+        assert!(!overlaps(Point(2.5, 7.0)));
+        assert!(overlaps(Point(0.75, 2.5)));
+        assert!(overlaps(Point(2.6, 3.0)));
+    }
+
+    #[test]
+    fn offset_polygon_grows_and_shrinks() {
+        let square = SimplePolygon::from(AARectangle::new(0.0, 0.0, 2.0, 2.0));
+        let round = OffsetJoin::Round { n_segments: 64 };
+
+        let grown = offset_polygon(&square, 1.0, OffsetJoin::Miter).unwrap();
+        assert_eq!(grown.number_of_points(), 4);
+        assert!((grown.area - 16.0).abs() < 1e-4, "{}", grown.area);
+        let grown = offset_polygon(&square, 1.0, round).unwrap();
+        assert!(
+            (grown.area - (12.0 + jagua_rs::PI)).abs() < 0.01,
+            "{}",
+            grown.area
+        );
+
+        let shrunk = offset_polygon(&square, -0.5, round).unwrap();
+        assert_eq!(shrunk.number_of_points(), 4);
+        assert!((shrunk.area - 1.0).abs() < 1e-4, "{}", shrunk.area);
+        assert!(offset_polygon(&square, -1.0, OffsetJoin::Miter).is_none());
+        assert!(offset_polygon(&square, -1.5, OffsetJoin::Miter).is_none());
+
+        let u_shape = u_shape();
+        let shrunk = offset_polygon(&u_shape, -0.25, round).unwrap();
+        assert!(shrunk.number_of_points() > u_shape.number_of_points());
+        assert!(shrunk.area < u_shape.area);
+        assert!(offset_polygon(&u_shape, 0.5, OffsetJoin::Miter).is_some());
+        //the base splits and the notch closes
+        assert!(offset_polygon(&u_shape, -0.6, round).is_none());
+        assert!(offset_polygon(&u_shape, 1.1, OffsetJoin::Miter).is_none());
+    }
+
+    #[test]
+    fn densify_splits_only_long_edges() {
+        let rect = SimplePolygon::from(AARectangle::new(0.0, 0.0, 10.0, 1.0));
+
+        //both long edges are split in 4 parts, the short ones are untouched
+        let dense = densify(&rect, 3.0);
+        assert_eq!(dense.number_of_points(), 10);
+        assert!(dense.edge_iter().all(|e| e.diameter() <= 3.0));
+        assert!((dense.area - rect.area).abs() < 1e-4);
+
+        let untouched = densify(&rect, 10.0);
+        assert_eq!(untouched.points, rect.points);
+    }
+
+    #[test]
+    fn rectangle_from_points() {
+        let points = [Point(1.0, 4.0), Point(-2.0, 0.5), Point(3.0, 2.0)];
+        let bbox = AARectangle::from_points(&points).unwrap();
+        assert_eq!(
+            (bbox.x_min, bbox.y_min, bbox.x_max, bbox.y_max),
+            (-2.0, 0.5, 3.0, 4.0)
+        );
+        assert!(AARectangle::from_points(&[]).is_none());
+    }
+
+    #[test]
+    fn rotated_bbox_of_rectangle() {
+        let rect = AARectangle::new(1.0, 2.0, 4.0, 3.0);
+        let bounds = |r: AARectangle| (r.x_min, r.y_min, r.x_max, r.y_max);
+        let quarter = jagua_rs::PI / 2.0;
+        assert_eq!(bounds(rect.rotated_bbox(0.0)), (1.0, 2.0, 4.0, 3.0));
+        assert_eq!(bounds(rect.rotated_bbox(quarter)), (-3.0, 1.0, -2.0, 4.0));
+        assert_eq!(
+            bounds(rect.rotated_bbox(2.0 * quarter)),
+            (-4.0, -3.0, -1.0, -2.0)
+        );
+        assert_eq!(bounds(rect.rotated_bbox(-quarter)), (2.0, -4.0, 3.0, -1.0));
+        assert_eq!(
+            bounds(rect.rotated_bbox(90f32.to_radians())),
+            (-3.0, 1.0, -2.0, 4.0)
+        );
+
+        //arbitrary angles match the bounding box of the rotated polygon
+        for angle in [0.3, 1.0, 2.5, -0.7] {
+            let expected = SimplePolygon::from(rect.clone())
+                .transform_clone(&Transformation::from_rotation(angle))
+                .bbox();
+            let rotated = rect.rotated_bbox(angle);
+            let (r, e) = (bounds(rotated), bounds(expected));
+            assert!(
+                [(r.0, e.0), (r.1, e.1), (r.2, e.2), (r.3, e.3)]
+                    .iter()
+                    .all(|(a, b)| (a - b).abs() < 1e-5),
+                "{r:?} != {e:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn builder_matches_parsed_instance() {
+        let json_instance = serde_json::from_str(
+            r#"{
+                "Name": "builder",
+                "Items": [
+                    {"Demand": 3, "AllowedOrientations": [0.0, 90.0], "Shape": {"Type": "Rectangle", "Data": {"Width": 10.0, "Height": 5.0}}},
+                    {"Demand": 2, "Shape": {"Type": "Rectangle", "Data": {"Width": 7.0, "Height": 7.0}}}
+                ],
+                "Objects": [
+                    {"Cost": 0, "Stock": 2, "Shape": {"Type": "Rectangle", "Data": {"Width": 100.0, "Height": 50.0}}}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let cde_config = LBFConfig::default().cde_config;
+        let parsed = Parser::new(PolySimplConfig::Disabled, cde_config, true)
+            .parse(&json_instance)
+            .unwrap();
+
+        let built = InstanceBuilder::new(
+            InstanceContainer::Bins(vec![(rect(100.0, 50.0), 2)]),
+            cde_config,
+        )
+        .add_item(
+            rect(10.0, 5.0),
+            3,
+            AllowedRotation::Discrete(vec![0.0, 90.0f32.to_radians()]),
+        )
+        .add_item(rect(7.0, 7.0), 2, AllowedRotation::Continuous)
+        .build();
+
+        assert_eq!(built.content_hash(), parsed.content_hash());
+    }
+
+    #[test]
+    fn solve_is_deterministic_across_threads() {
+        let config = lbf_config();
+        let instance = parse_asset("swim", config.cde_config);
+
+        assert_deterministic(&instance, config);
+    }
+
+    #[test]
+    fn batch_collision_queries_match_single_queries() {
+        let config = lbf_config();
+        let instance = parse_asset("baldacci1", config.cde_config);
+        let solution =
+            LBFOptimizer::new(instance.clone(), config, SmallRng::seed_from_u64(0)).solve();
+        //keep a few of the placed items, so part of the queries is free of collisions
+        let mut layout = Layout::from_snapshot(&solution.layout_snapshots[0]);
+        let removed = layout.placed_items().keys().skip(3).collect::<Vec<_>>();
+        for pk in removed {
+            layout.remove_item(pk, true);
+        }
+        let cde = layout.cde();
+        assert!(cde.collides_batch(&[], &[]).is_empty());
+
+        let mut rng = SmallRng::seed_from_u64(0);
+        let bbox = layout.bin.bbox();
+        let queries = (0..500)
+            .map(|_| {
+                let item = &instance.items()[rng.gen_range(0..instance.items().len())].0;
+                let transform = DTransformation::new(
+                    rng.gen_range(0.0..2.0 * jagua_rs::PI),
+                    (
+                        rng.gen_range(bbox.x_min..bbox.x_max),
+                        rng.gen_range(bbox.y_min..bbox.y_max),
+                    ),
+                )
+                .compose();
+                (transform, item.shape.as_ref())
+            })
+            .collect::<Vec<_>>();
+
+        let batch = cde.collides_batch(&queries, &[]);
+        let single = queries
+            .iter()
+            .map(|(t, shape)| {
+                let mut buffer = (*shape).clone();
+                cde.surrogate_or_poly_collides(shape, t, &mut buffer, &[])
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(batch, single);
+        assert!(batch.contains(&true) && batch.contains(&false));
+    }
+
+    #[test]
+    fn items_within_radius_match_exhaustive_search() {
+        let config = lbf_config();
+        let instance = parse_asset("baldacci1", config.cde_config);
+        let solution = LBFOptimizer::new(instance, config, SmallRng::seed_from_u64(0)).solve();
+        let layout = Layout::from_snapshot(&solution.layout_snapshots[0]);
+
+        let mut rng = SmallRng::seed_from_u64(0);
+        let bbox = layout.bin.bbox();
+        let mut n_found = 0;
+        for i in 0..500 {
+            let point = Point(
+                rng.gen_range(bbox.x_min..bbox.x_max),
+                rng.gen_range(bbox.y_min..bbox.y_max),
+            );
+            let radius = match i % 2 == 0 {
+                true => 0.0,
+                false => rng.gen_range(0.0..bbox.width() / 10.0),
+            };
+            let circle = Circle::new(point, radius);
+            let mut expected = layout
+                .placed_items()
+                .iter()
+                .filter(|(_, pi)| {
+                    pi.shape.collides_with(&point)
+                        || (radius > 0.0 && pi.shape.edge_iter().any(|e| circle.collides_with(&e)))
+                })
+                .map(|(k, _)| k)
+                .collect::<Vec<_>>();
+            let mut found = layout.items_within(&point, radius);
+            expected.sort();
+            found.sort();
+            assert_eq!(found, expected, "{point:?}, {radius}");
+            n_found += found.len();
+        }
+        assert!(n_found > 0);
+    }
+
+    #[test]
+    fn quadtree_split_threshold_does_not_change_collisions() {
+        let config_file = std::fs::read_to_string("../assets/config_lbf.json").unwrap();
+        let mut config: LBFConfig = serde_json::from_str(&config_file).unwrap();
+        assert_eq!(config.cde_config.quadtree_min_hazards_to_split, 1);
+        config.n_samples = 100;
+
+        let solve = |config: LBFConfig| {
+            let instance = parse_asset("baldacci1", config.cde_config);
+            let solution = LBFOptimizer::new(instance, config, SmallRng::seed_from_u64(0)).solve();
+            let n_nodes = Layout::from_snapshot(&solution.layout_snapshots[0])
+                .cde()
+                .number_of_nodes();
+            (solution, n_nodes)
+        };
+        let (default, default_nodes) = solve(config);
+        config.cde_config.quadtree_min_hazards_to_split = 4;
+        let (coarse, coarse_nodes) = solve(config);
+
+        assert!(
+            coarse_nodes < default_nodes,
+            "{coarse_nodes} >= {default_nodes}"
+        );
+        let placements = |s: &Solution| {
+            s.layout_snapshots
+                .iter()
+                .flat_map(|ls| ls.placed_items.values().map(|pi| (pi.item_id, pi.d_transf)))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(placements(&default), placements(&coarse));
+    }
+
+    #[test]
+    fn auto_quadtree_depth_increases_with_item_count() {
+        let config = LBFConfig::default().cde_config;
+        let container = AARectangle::new(0.0, 0.0, 2000.0, 1000.0);
+        let depths = (0..=14)
+            .map(|e| config.auto(1 << e, &container).quadtree_depth)
+            .collect::<Vec<_>>();
+        assert!(depths.windows(2).all(|w| w[0] <= w[1]), "{depths:?}");
+        assert!(
+            config.auto(5, &container).quadtree_depth
+                < config.auto(5000, &container).quadtree_depth
+        );
+
+        //the parser tunes the depth of every bin when auto mode is requested
+        let auto_config = CDEConfig {
+            quadtree_depth_auto: true,
+            ..config
+        };
+        let Instance::BP(instance) = parse_asset("baldacci1", auto_config) else {
+            panic!("expected a bin packing instance");
+        };
+        for (bin, _) in instance.bins.iter() {
+            let expected = auto_config.auto(instance.total_item_qty(), &bin.bbox());
+            assert_eq!(bin.base_cde.config(), expected);
+        }
+    }
+
+    #[test]
+    fn item_added_without_moving_placed_items() {
+        let config = lbf_config();
+        let Instance::BP(instance) = parse_asset("baldacci1", config.cde_config) else {
+            panic!("expected a bin packing instance");
+        };
+        let solution =
+            LBFOptimizer::new(instance.clone().into(), config, SmallRng::seed_from_u64(0)).solve();
+
+        let new_item = instance.items[0].0.clone();
+        let (extended, updated) = add_item_to_solution(
+            &instance,
+            &solution,
+            new_item,
+            1,
+            config,
+            SmallRng::seed_from_u64(1),
+        );
+
+        let new_id = instance.items.len();
+        assert_eq!(extended.items.len(), new_id + 1);
+        assert_eq!(updated.placed_item_qtys[new_id], 1);
+        assert_eq!(updated.n_items_placed(), solution.n_items_placed() + 1);
+        let placements = |ls: &LayoutSnapshot| {
+            ls.placed_items
+                .values()
+                .map(|pi| (pi.item_id, pi.d_transf))
+                .collect::<Vec<_>>()
+        };
+        for (old, new) in solution
+            .layout_snapshots
+            .iter()
+            .zip(&updated.layout_snapshots)
+        {
+            let (old, new) = (placements(old), placements(new));
+            assert_eq!(old[..], new[..old.len()]);
+        }
+    }
+
+    #[test]
+    fn composition_is_associative_with_identity() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut random_t = || {
+            Transformation::from_rotation(rng.gen_range(0.0..std::f32::consts::TAU))
+                .translate((rng.gen_range(-100.0..100.0), rng.gen_range(-100.0..100.0)))
+        };
+        let approx_eq = |t1: &Transformation, t2: &Transformation| {
+            t1.matrix()
+                .iter()
+                .flatten()
+                .zip(t2.matrix().iter().flatten())
+                .all(|(v1, v2)| (v1.into_inner() - v2.into_inner()).abs() < 1e-3)
+        };
+        let empty = Transformation::empty();
+        for _ in 0..1000 {
+            let (a, b, c) = (random_t(), random_t(), random_t());
+            assert!(approx_eq(
+                &a.compose(&b.compose(&c)),
+                &a.compose(&b).compose(&c)
+            ));
+            assert!(approx_eq(&a.compose(&empty), &a));
+            assert!(approx_eq(&empty.compose(&a), &a));
+            assert!(approx_eq(&a.compose(&b), &b.clone().transform(&a)));
+        }
+    }
+
+    #[test]
+    fn transformed_polygon_has_up_to_date_caches() {
+        let shape = SimplePolygon::new(vec![
+            Point(0.0, 0.0),
+            Point(10.0, 0.0),
+            Point(10.0, 2.0),
+            Point(3.0, 6.0),
+        ]);
+        let t = Transformation::from_rotation(1.0).translate((5.0, -3.0));
+        let mut centroid = shape.centroid();
+        centroid.transform(&t);
+
+        let assert_caches = |poly: &SimplePolygon| {
+            let bbox = AARectangle::from_points(&poly.points).unwrap();
+            assert_eq!(poly.bbox(), bbox);
+            assert!((poly.area() - shape.area()).abs() < 1e-4);
+            assert!((poly.diameter() - shape.diameter()).abs() < 1e-4);
+            assert!(poly.centroid().distance(centroid) < 1e-4);
+        };
+
+        let mut in_place = shape.clone();
+        in_place.transform(&t);
+        assert_caches(&in_place);
+
+        //a buffer of another shape with the same number of points
+        let mut buffer = SimplePolygon::from(AARectangle::new(0.0, 0.0, 1.0, 1.0));
+        buffer.transform_from(&shape, &t);
+        assert_caches(&buffer);
+    }
+
+    #[test]
+    fn touching_items_do_not_collide() {
+        let mut cde_config = LBFConfig::default().cde_config;
+        cde_config.allow_touching = true;
+        let instance = bp_instance(
+            rect(20.0, 10.0),
+            vec![(rect(10.0, 10.0), 3, AllowedRotation::None)],
+            cde_config,
+        );
+        let mut problem = BPProblem::new(instance);
+        let at = |x| DTransformation::new(0.0, (x, 0.0));
+
+        //flush against the walls of the bin and against each other
+        let (layout, _) = problem
+            .try_place_at(0, LayoutIndex::Template(0), at(0.0))
+            .unwrap();
+        assert!(problem.try_place_at(0, layout, at(9.99)).is_err());
+        assert!(problem.try_place_at(0, layout, at(10.0)).is_ok());
+    }
+
+    #[test]
+    fn placements_with_unknown_ids_are_rejected() {
+        let instance = bp_instance(
+            rect(20.0, 10.0),
+            vec![(rect(5.0, 5.0), 1, AllowedRotation::None)],
+            LBFConfig::default().cde_config,
+        );
+        let mut problem = BPProblem::new(instance);
+        let at = DTransformation::new(0.0, (1.0, 1.0));
+
+        assert_eq!(
+            problem.try_place_at(1, LayoutIndex::Template(0), at),
+            Err(PlacementRejection::UnknownItem)
+        );
+        assert_eq!(
+            problem.try_place_at(0, LayoutIndex::Template(1), at),
+            Err(PlacementRejection::UnknownLayout)
+        );
+        assert_eq!(
+            problem.try_place_at(0, LayoutIndex::Real(0), at),
+            Err(PlacementRejection::UnknownLayout)
+        );
+        let (layout, _) = problem
+            .try_place_at(0, LayoutIndex::Template(0), at)
+            .unwrap();
+        assert_eq!(
+            problem.try_place_at(0, layout, at),
+            Err(PlacementRejection::ItemNotDemanded)
+        );
+    }
+
+    #[test]
+    fn containment_allows_flush_edges() {
+        let l_shape = SimplePolygon::new(
+            [
+                (0.0, 0.0),
+                (10.0, 0.0),
+                (10.0, 5.0),
+                (5.0, 5.0),
+                (5.0, 10.0),
+                (0.0, 10.0),
+            ]
+            .map(Point::from)
+            .to_vec(),
+        );
+        let instance = bp_instance(l_shape, vec![], LBFConfig::default().cde_config);
+        let cde = &instance.bins[0].0.base_cde;
+        let square = SimplePolygon::from(AARectangle::new(0.0, 0.0, 2.0, 2.0));
+        let at = |x, y| Transformation::from_translation((x, y));
+
+        //flush against two walls, and fitting exactly in the corner of the L
+        assert!(cde.is_fully_contained(&at(0.0, 0.0), &square));
+        assert!(cde.is_fully_contained(&at(3.0, 3.0), &square));
+        assert!(cde.is_fully_contained(&at(8.0, 3.0), &square));
+        //poking out
+        assert!(!cde.is_fully_contained(&at(9.0, 0.0), &square));
+        assert!(!cde.is_fully_contained(&at(4.0, 4.0), &square));
+
+        //every vertex lies inside, but the diagonal edge cuts through the corner of the L
+        let triangle = SimplePolygon::new(vec![Point(1.0, 1.0), Point(9.0, 4.0), Point(4.0, 9.0)]);
+        assert!(!cde.is_fully_contained(&Transformation::empty(), &triangle));
+    }
+
+    #[test]
+    fn tightness_is_returned_with_the_solution() {
+        let mut config = lbf_config();
+        config.record_tightness = true;
+        let instance = bp_instance(
+            rect(30.0, 12.0),
+            vec![
+                (rect(5.0, 5.0), 3, AllowedRotation::None),
+                (rect(9.0, 9.0), 1, AllowedRotation::None),
+            ],
+            config.cde_config,
+        );
+        let (solution, tightness) =
+            LBFOptimizer::new(instance.into(), config, SmallRng::seed_from_u64(0))
+                .solve_with_tightness();
+
+        assert_eq!(solution.n_items_placed(), 4);
+        assert_eq!(tightness.len(), 4);
+        for t in &tightness {
+            let pi = &solution.layout_snapshots[t.layout].placed_items[t.pik];
+            assert_eq!(pi.item_id, t.item_id);
+            assert!(t.n_feasible <= config.n_samples);
+        }
+        //the large item is placed first, in an empty bin
+        assert_eq!(tightness[0].item_id, 1);
+        assert!(tightness[0].n_feasible > tightness[3].n_feasible);
+
+        //placements are still found after the strip is extended and fitted
+        let instance = parse_asset("albano", config.cde_config);
+        let (solution, tightness) =
+            LBFOptimizer::new(instance, config, SmallRng::seed_from_u64(0)).solve_with_tightness();
+        assert_eq!(tightness.len(), solution.n_items_placed());
+
+        config.record_tightness = false;
+        let instance = parse_asset("baldacci1", config.cde_config);
+        let (_, tightness) =
+            LBFOptimizer::new(instance, config, SmallRng::seed_from_u64(0)).solve_with_tightness();
+        assert!(tightness.is_empty());
+    }
+
+    #[test]
+    fn contact_slider_stops_against_hazards() {
+        let instance = bp_instance(
+            rect(40.0, 10.0),
+            vec![(rect(5.0, 5.0), 2, AllowedRotation::None)],
+            LBFConfig::default().cde_config,
+        );
+        let item = &instance.items[0].0;
+        let mut problem = BPProblem::new(instance.clone());
+        let (layout, _) = problem
+            .try_place_at(
+                0,
+                LayoutIndex::Template(0),
+                DTransformation::new(0.0, (20.0, 1.0)),
+            )
+            .unwrap();
+        let layout = problem.get_layout(layout);
+        let cde = layout.cde();
+        let start = DTransformation::new(0.0, (30.0, 4.0));
+
+        //into the placed item on the left and the bottom wall of the bin
+        let slid = ContactSlider::new(item, cde, &[]).slide(&start);
+        let (tx, ty) = slid.translation();
+        assert!(!cde.poly_collides(&item.shape.transform_clone(&slid.compose()), &[]));
+        let distance = cde
+            .nearest_hazard_distance(&slid.compose(), &item.shape)
+            .unwrap();
+        assert!(
+            distance < 1e-2 && (tx - 25.0).abs() < 1e-2,
+            "{tx}, {distance}"
+        );
+        assert!(ty.abs() < 1e-2, "{ty}");
+
+        //past the ignored item, into the left wall
+        let placed_item = HazardEntity::from(layout.placed_items().values().next().unwrap());
+        let slid = ContactSlider::new(item, cde, &[placed_item]).slide(&start);
+        let (tx, ty) = slid.translation();
+        assert!(tx.abs() < 1e-2 && ty.abs() < 1e-2, "{tx}, {ty}");
+    }
+
+    #[test]
+    fn tiny_items_without_surrogate_collide_exactly() {
+        let mut cde_config = lbf_config().cde_config;
+        cde_config.item_surrogate_min_area = 30.0;
+        let instance = bp_instance(
+            rect(40.0, 10.0),
+            vec![
+                (rect(5.0, 5.0), 2, AllowedRotation::None),
+                (rect(6.0, 6.0), 1, AllowedRotation::None),
+            ],
+            cde_config,
+        );
+        let tiny = &instance.items[0].0.shape;
+        assert!(tiny.surrogate().ff_poles().is_empty());
+        assert!(tiny.surrogate().ff_piers().is_empty());
+        assert!(!instance.items[1].0.shape.surrogate().ff_poles().is_empty());
+
+        let mut problem = BPProblem::new(instance.clone());
+        let (layout, _) = problem
+            .try_place_at(
+                1,
+                LayoutIndex::Template(0),
+                DTransformation::new(0.0, (1.0, 1.0)),
+            )
+            .unwrap();
+        problem
+            .try_place_at(0, layout, DTransformation::new(0.0, (20.0, 1.0)))
+            .unwrap();
+        let cde = problem.get_layout(layout).cde();
+
+        let collides = |x, y| {
+            let t = Transformation::from_translation((x, y));
+            let mut buffer = tiny.as_ref().clone();
+            cde.surrogate_or_poly_collides(tiny, &t, &mut buffer, &[])
+        };
+        assert!(!collides(10.0, 1.0));
+        assert!(collides(4.0, 4.0), "overlaps the large item");
+        assert!(collides(22.0, 2.0), "overlaps the other tiny item");
+        assert!(collides(37.0, 1.0), "sticks out of the bin");
+        //fully inside the large item, no edges intersect
+        assert!(collides(1.5, 1.5) && !collides(30.0, 4.0));
+    }
+
+    #[test]
+    fn nearest_hazard_distance_to_placed_items() {
+        let instance = bp_instance(
+            rect(40.0, 10.0),
+            vec![(rect(5.0, 5.0), 2, AllowedRotation::None)],
+            LBFConfig::default().cde_config,
+        );
+        let square = instance.items[0].0.shape.clone();
+        let at = |x, y| Transformation::from_translation((x, y));
+        //the container itself is not a hazard to measure against
+        assert_eq!(
+            instance.bins[0]
+                .0
+                .base_cde
+                .nearest_hazard_distance(&at(1.0, 1.0), &square),
+            None
+        );
+
+        let mut problem = BPProblem::new(instance);
+        let (layout, _) = problem
+            .try_place_at(
+                0,
+                LayoutIndex::Template(0),
+                DTransformation::new(0.0, (1.0, 1.0)),
+            )
+            .unwrap();
+        problem
+            .try_place_at(0, layout, DTransformation::new(0.0, (30.0, 1.0)))
+            .unwrap();
+        let cde = problem.get_layout(layout).cde();
+
+        let distance = |x, y| cde.nearest_hazard_distance(&at(x, y), &square).unwrap();
+        assert!((distance(9.0, 1.0) - 3.0).abs() < 1e-4);
+        assert!((distance(20.0, 6.0) - 5.0).abs() < 1e-4);
+        assert!((distance(10.0, 9.0) - 5.0).abs() < 1e-4);
+        assert_eq!(distance(4.0, 4.0), 0.0);
+    }
+
+    #[test]
+    fn nearest_hazard_distance_matches_exhaustive_search() {
+        let config = lbf_config();
+        let instance = parse_asset("baldacci1", config.cde_config);
+        let shape = instance.item(0).shape.clone();
+        let solution = LBFOptimizer::new(instance, config, SmallRng::seed_from_u64(0)).solve();
+        let layout = Layout::from_snapshot(&solution.layout_snapshots[0]);
+
+        let mut rng = SmallRng::seed_from_u64(0);
+        let bbox = layout.bin.bbox();
+        for _ in 0..200 {
+            let transform = Transformation::from_translation((
+                rng.gen_range(bbox.x_min..bbox.x_max),
+                rng.gen_range(bbox.y_min..bbox.y_max),
+            ));
+            let transformed = shape.transform_clone(&transform);
+            let expected = layout
+                .placed_items()
+                .values()
+                .map(|pi| transformed.separation_distance(&pi.shape))
+                .fold(f32::MAX, f32::min);
+            let found = layout
+                .cde()
+                .nearest_hazard_distance(&transform, &shape)
+                .unwrap();
+            assert!((found - expected).abs() < 1e-3, "{found} != {expected}");
+        }
+    }
+
+    #[test]
+    fn static_hazard_reach_check_does_not_change_collisions() {
+        let config = lbf_config();
+        let instance = parse_asset("baldacci1", config.cde_config);
+        let solution =
+            LBFOptimizer::new(instance.clone(), config, SmallRng::seed_from_u64(0)).solve();
+        let mut with_hpg = Layout::from_snapshot(&solution.layout_snapshots[0]);
+        //leave room for non-colliding samples
+        let keys = with_hpg.placed_items().keys().collect::<Vec<_>>();
+        for key in keys.into_iter().step_by(2) {
+            with_hpg.remove_item(key, true);
+        }
+
+        //without a hazard proximity grid, static hazards are never out of reach
+        let mut cde_config = config.cde_config;
+        cde_config.hpg_n_cells = 0;
+        let Instance::BP(no_hpg_instance) = parse_asset("baldacci1", cde_config) else {
+            panic!("expected a bin packing instance");
+        };
+        let mut without_hpg = Layout::new(0, no_hpg_instance.bins[with_hpg.bin.id].0.clone());
+        for pi in with_hpg.placed_items().values() {
+            without_hpg.place_item(no_hpg_instance.item(pi.item_id), pi.d_transf);
+        }
+
+        let mut rng = SmallRng::seed_from_u64(0);
+        let bbox = with_hpg.bin.bbox();
+        let mut n_colliding = 0;
+        for i in 0..1000 {
+            let item = instance.item(i % instance.items().len());
+            let transform = DTransformation::new(
+                rng.gen_range(0.0..std::f32::consts::TAU),
+                (
+                    rng.gen_range(bbox.x_min..bbox.x_max),
+                    rng.gen_range(bbox.y_min..bbox.y_max),
+                ),
+            )
+            .compose();
+            let shape = item.shape.transform_clone(&transform);
+            let collides = with_hpg.cde().poly_collides(&shape, &[]);
+            assert_eq!(collides, without_hpg.cde().poly_collides(&shape, &[]));
+
+            let (mut detected, mut expected) = (vec![], vec![]);
+            with_hpg
+                .cde()
+                .collect_poly_collisions(&shape, &[], &mut detected);
+            without_hpg
+                .cde()
+                .collect_poly_collisions(&shape, &[], &mut expected);
+            let hazard_ids = |hazards: &[_]| {
+                let mut ids = hazards.iter().map(|h| format!("{h:?}")).collect::<Vec<_>>();
+                ids.sort();
+                ids
+            };
+            assert_eq!(hazard_ids(&detected), hazard_ids(&expected));
+            n_colliding += collides as usize;
+        }
+        assert!(n_colliding > 0 && n_colliding < 1000);
+    }
+
+    #[test]
+    fn moving_an_item_matches_a_full_rebuild() {
+        let instance = bp_instance(
+            rect(100.0, 100.0),
+            vec![(rect(10.0, 5.0), 5, AllowedRotation::Continuous)],
+            LBFConfig::default().cde_config,
+        );
+        let item = &instance.items[0].0;
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut random_dt = || {
+            DTransformation::new(
+                rng.gen_range(0.0..std::f32::consts::TAU),
+                (rng.gen_range(0.0..90.0), rng.gen_range(0.0..90.0)),
+            )
+        };
+
+        let mut layout = Layout::new(0, instance.bins[0].0.clone());
+        let moving = layout.place_item(item, random_dt());
+        for _ in 0..4 {
+            layout.place_item(item, random_dt());
+        }
+        for _ in 0..10000 {
+            layout.move_item(moving, item, random_dt());
+        }
+
+        let mut rebuilt = Layout::new(1, instance.bins[0].0.clone());
+        for pi in layout.placed_items().values() {
+            rebuilt.place_item(item, pi.d_transf);
+        }
+        assert!(assertions::layout_qt_matches_fresh_qt(&layout));
+        assert_eq!(layout.placed_items().len(), 5);
+        assert_eq!(layout.cde().dynamic_hazards().len(), 5);
+        assert_eq!(
+            layout.cde().number_of_nodes(),
+            rebuilt.cde().number_of_nodes()
+        );
+    }
+
+    #[test]
+    fn rollback_matches_a_layout_rebuilt_from_a_snapshot() {
+        let instance = bp_instance(
+            rect(100.0, 100.0),
+            vec![
+                (rect(10.0, 5.0), 50, AllowedRotation::Continuous),
+                (u_shape(), 50, AllowedRotation::Continuous),
+            ],
+            LBFConfig::default().cde_config,
+        );
+        let mut rng = SmallRng::seed_from_u64(0);
+        let random_dt = |rng: &mut SmallRng| {
+            DTransformation::new(
+                rng.gen_range(0.0..std::f32::consts::TAU),
+                (rng.gen_range(0.0..90.0), rng.gen_range(0.0..90.0)),
+            )
+        };
+        let placements = |layout: &Layout| {
+            let mut placements = layout
+                .placed_items()
+                .values()
+                .map(|pi| format!("{} {:?}", pi.item_id, pi.d_transf))
+                .collect::<Vec<_>>();
+            placements.sort();
+            placements
+        };
+
+        let mut layout = Layout::new(0, instance.bins[0].0.clone());
+        for _ in 0..5 {
+            layout.place_item(&instance.items[0].0, random_dt(&mut rng));
+        }
+        layout.start_journal();
+        let mut snapshots = vec![layout.create_snapshot()];
+        for _ in 0..40 {
+            let key = layout.placed_items().keys().choose(&mut rng);
+            match (key, rng.gen_range(0..4)) {
+                (Some(key), 0) => {
+                    layout.remove_item(key, rng.gen_bool(0.5));
+                }
+                (Some(key), 1) => {
+                    let item = &instance.items[layout.placed_items()[key].item_id].0;
+                    layout.move_item(key, item, random_dt(&mut rng));
+                }
+                _ => {
+                    let item = &instance.items[rng.gen_range(0..2)].0;
+                    layout.place_item(item, random_dt(&mut rng));
+                }
+            }
+            snapshots.push(layout.create_snapshot());
+        }
+        assert_eq!(layout.journal_len(), 40);
+
+        let bbox = layout.bin.bbox();
+        for k in [15, 25] {
+            layout.rollback(k - (40 - layout.journal_len()));
+            assert_eq!(layout.journal_len(), 40 - k);
+
+            let rebuilt = Layout::from_snapshot(&snapshots[40 - k]);
+            assert!(assertions::layouts_match(&layout, &snapshots[40 - k]));
+            assert_eq!(placements(&layout), placements(&rebuilt));
+            assert!(assertions::layout_qt_matches_fresh_qt(&layout));
+            assert_eq!(
+                layout.cde().dynamic_hazards().len(),
+                rebuilt.cde().dynamic_hazards().len()
+            );
+            for _ in 0..100 {
+                let probe = rect(5.0, 5.0).transform_clone(&Transformation::from_translation((
+                    rng.gen_range(bbox.x_min..bbox.x_max),
+                    rng.gen_range(bbox.y_min..bbox.y_max),
+                )));
+                assert_eq!(
+                    layout.cde().poly_collides(&probe, &[]),
+                    rebuilt.cde().poly_collides(&probe, &[])
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn used_perimeter_excludes_shared_edges() {
+        let mut cde_config = LBFConfig::default().cde_config;
+        cde_config.allow_touching = true;
+        let instance = bp_instance(
+            rect(30.0, 10.0),
+            vec![
+                (rect(10.0, 10.0), 2, AllowedRotation::None),
+                (rect(10.0, 5.0), 1, AllowedRotation::None),
+            ],
+            cde_config,
+        );
+        let mut problem = BPProblem::new(instance);
+        let at = |x, y| DTransformation::new(0.0, (x, y));
+
+        let (layout, _) = problem
+            .try_place_at(0, LayoutIndex::Template(0), at(0.0, 0.0))
+            .unwrap();
+        problem.try_place_at(0, layout, at(10.0, 0.0)).unwrap();
+        let perimeter = problem.get_layout(layout).used_perimeter();
+        assert!((perimeter - 60.0).abs() < 1e-3, "{perimeter}");
+
+        //only half of the right edge of the second square is shared with the small rectangle
+        problem.try_place_at(1, layout, at(20.0, 0.0)).unwrap();
+        let perimeter = problem.get_layout(layout).used_perimeter();
+        assert!((perimeter - 80.0).abs() < 1e-3, "{perimeter}");
+    }
+
+    #[test]
+    fn contact_length_sums_separate_segments() {
+        let base = SimplePolygon::from(AARectangle::new(0.0, 0.0, 4.0, 1.0));
+        //standing on the base with two feet, at x in [0, 1] and [3, 4]
+        let arch = SimplePolygon::new(
+            [
+                (0.0, 1.0),
+                (1.0, 1.0),
+                (1.0, 2.0),
+                (3.0, 2.0),
+                (3.0, 1.0),
+                (4.0, 1.0),
+                (4.0, 3.0),
+                (0.0, 3.0),
+            ]
+            .map(Point::from)
+            .to_vec(),
+        );
+        let contact = base.contact_length(&arch, 1e-4);
+        assert!((contact - 2.0).abs() < 1e-4, "{contact}");
+        assert_eq!(contact, arch.contact_length(&base, 1e-4));
+
+        let lifted = arch.transform_clone(&Transformation::from_translation((0.0, 0.1)));
+        assert_eq!(base.contact_length(&lifted, 1e-4), 0.0);
+    }
+
+    #[test]
+    fn items_which_fit_no_container_are_rejected() {
+        let json_instance = serde_json::from_str(
+            r#"{
+                "Name": "unfit",
+                "Items": [
+                    {"Demand": 1, "Shape": {"Type": "Rectangle", "Data": {"Width": 60.0, "Height": 60.0}}},
+                    {"Demand": 1, "AllowedOrientations": [0.0, 90.0], "Shape": {"Type": "Rectangle", "Data": {"Width": 40.0, "Height": 90.0}}},
+                    {"Demand": 1, "Shape": {"Type": "Rectangle", "Data": {"Width": 55.0, "Height": 55.0}}},
+                    {"Demand": 1, "Shape": {"Type": "Rectangle", "Data": {"Width": 95.0, "Height": 45.0}}}
+                ],
+                "Objects": [
+                    {"Cost": 0, "Stock": 1, "Shape": {"Type": "Rectangle", "Data": {"Width": 100.0, "Height": 50.0}}}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let parser = Parser::new(
+            PolySimplConfig::Disabled,
+            LBFConfig::default().cde_config,
+            true,
+        );
+        assert!(parser.parse(&json_instance).is_ok());
+
+        let err = parser
+            .reject_unfit_items(true)
+            .parse(&json_instance)
+            .unwrap_err();
+        assert_eq!(err, ParseError::ItemTooLarge { ids: vec![0, 2] });
+    }
+
+    #[test]
+    fn self_intersecting_polygons_are_rejected() {
+        let parse = |item_shape: &str| {
+            let json_instance = serde_json::from_str::<JsonInstance>(&format!(
+                r#"{{
+                    "Name": "bow-tie",
+                    "Items": [
+                        {{"Demand": 1, "Shape": {{"Type": "Rectangle", "Data": {{"Width": 1.0, "Height": 1.0}}}}}},
+                        {{"Demand": 1, "Shape": {{"Type": "SimplePolygon", "Data": {item_shape}}}}}
+                    ],
+                    "Strip": {{"Height": 10.0}}
+                }}"#
+            ))
+            .unwrap();
+            Parser::new(PolySimplConfig::Disabled, lbf_config().cde_config, true)
+                .parse(&json_instance)
+        };
+
+        assert!(parse("[[0.0, 0.0], [2.0, 0.0], [2.0, 2.0], [0.0, 2.0]]").is_ok());
+        assert_eq!(
+            parse("[[0.0, 0.0], [3.0, 3.0], [3.0, 0.0], [0.0, 2.0]]").err(),
+            Some(ParseError::NonSimplePolygon {
+                owner: ShapeOwner::Item(1)
+            })
+        );
+        assert_eq!(
+            parse("[[0.0, 0.0], [1.0, 1.0], [2.0, 2.0]]").err(),
+            Some(ParseError::InvalidPolygon {
+                owner: ShapeOwner::Item(1),
+                defect: PolygonDefect::ZeroArea
+            })
+        );
+    }
+
+    #[test]
+    fn solutions_with_unknown_items_are_rejected() {
+        let json_instance = io::read_json_instance(Path::new("../assets/swim.json"));
+        let parser = Parser::new(PolySimplConfig::Disabled, lbf_config().cde_config, true);
+        let n_items = json_instance.items.len();
+        let json_layout: JsonLayout = serde_json::from_value(serde_json::json!({
+            "Container": {"Type": "Strip", "Params": {"Width": 10000.0, "Height": 5752.0}},
+            "PlacedItems": [{"Index": n_items, "Transformation": {"Rotation": 0.0, "Translation": [0.0, 0.0]}}],
+            "Statistics": {"Usage": 0.0}
+        }))
+        .unwrap();
+
+        let err = parser
+            .parse_and_build_solution(&json_instance, &[json_layout])
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            ParseError::SolutionMismatch(format!("no item with index {n_items}"))
+        );
+    }
+
+    #[test]
+    fn circle_and_rectangle_collide_with_edges() {
+        let edge = |x1, y1, x2, y2| Edge::new(Point(x1, y1), Point(x2, y2));
+
+        let circle = Circle::new(Point(0.0, 0.0), 1.0);
+        assert!(circle.collides_with(&edge(-2.0, 1.0, 2.0, 1.0)), "tangent");
+        assert!(circle.collides_with(&edge(-0.5, 0.0, 0.5, 0.0)), "inside");
+        assert!(!circle.collides_with(&edge(-2.0, 1.01, 2.0, 1.01)));
+        assert!(!circle.collides_with(&edge(1.0, 1.0, 2.0, 0.5)));
+
+        let rect = AARectangle::new(0.0, 0.0, 10.0, 5.0);
+        assert!(rect.collides_with(&edge(2.0, 1.0, 8.0, 4.0)), "inside");
+        assert!(rect.collides_with(&edge(-1.0, 2.5, 11.0, 2.5)), "crossing");
+        assert!(rect.collides_with(&edge(-1.0, 5.0, 11.0, 5.0)), "touching");
+        assert!(!rect.collides_with(&edge(-3.0, 4.0, 1.0, 8.0)));
+    }
+
+    #[test]
+    fn improvements_are_streamed_from_background_solve() {
+        let config = lbf_config();
+        let instance = parse_asset("baldacci1", config.cde_config);
+
+        let optimizer = LBFOptimizer::new(instance, config, SmallRng::seed_from_u64(0));
+        let (receiver, handle) = optimizer.solve_in_background();
+        let improvements = receiver.iter().collect::<Vec<_>>();
+        let solution = handle.join().unwrap();
+
+        assert_eq!(improvements.len(), solution.n_items_placed());
+        assert!(improvements
+            .windows(2)
+            .all(|w| w[0].n_items_placed() < w[1].n_items_placed()));
+        assert_eq!(
+            improvements.last().unwrap().placed_item_qtys,
+            solution.placed_item_qtys
+        );
+    }
+
+    #[test]
+    fn throttled_improvements_always_include_final_solution() {
+        let mut config = lbf_config();
+        config.improvement_throttle = ImprovementThrottle::MinPlacements(3);
+        let instance = parse_asset("baldacci1", config.cde_config);
+
+        let optimizer = LBFOptimizer::new(instance, config, SmallRng::seed_from_u64(0));
+        let (receiver, handle) = optimizer.solve_in_background();
+        let improvements = receiver.iter().collect::<Vec<_>>();
+        let solution = handle.join().unwrap();
+
+        assert_eq!(improvements.len(), solution.n_items_placed().div_ceil(3));
+        assert!(improvements
+            .iter()
+            .rev()
+            .skip(1)
+            .all(|s| s.n_items_placed() % 3 == 0));
+        assert_eq!(
+            improvements.last().unwrap().placed_item_qtys,
+            solution.placed_item_qtys
+        );
+
+        let throttle = ImprovementThrottle::MinInterval(1.0);
+        assert!(throttle.is_due(None, 1));
+        assert!(!throttle.is_due(Some(Duration::from_millis(500)), 10));
+        assert!(throttle.is_due(Some(Duration::from_secs(1)), 1));
+    }
+
+    #[test]
+    fn distance_queries_report_boundary() {
+        let rect = AARectangle::new(0.0, 0.0, 10.0, 5.0);
+        let poly = SimplePolygon::from(rect.clone());
+        let circle = Circle::new(Point(0.0, 0.0), 1.0);
+
+        assert_eq!(
+            rect.distance_from_border(&Point(10.0, 2.0)).0,
+            BorderPosition::Boundary
+        );
+        assert_eq!(
+            poly.distance_from_border(&Point(4.0, 0.0)).0,
+            BorderPosition::Boundary
+        );
+        assert_eq!(
+            circle.distance_from_border(&Point(0.0, 1.0)).0,
+            BorderPosition::Boundary
+        );
+        assert_eq!(
+            circle
+                .distance_from_border(&Circle::new(Point(3.0, 0.0), 2.0))
+                .0,
+            BorderPosition::Boundary
+        );
+        assert_eq!(
+            poly.distance_from_border(&Point(4.0, 1.0)),
+            (BorderPosition::Interior, 1.0)
+        );
+        assert_eq!(
+            rect.distance_from_border(&Point(12.0, 2.0)),
+            (BorderPosition::Exterior, 2.0)
+        );
+
+        //the tolerance scales with the size of the shape
+        let large = AARectangle::new(0.0, 0.0, 1000.0, 500.0);
+        let small = AARectangle::new(0.0, 0.0, 1.0, 0.5);
+        assert_eq!(
+            large.distance_from_border(&Point(1000.1, 2.0)).0,
+            BorderPosition::Boundary
+        );
+        assert_eq!(
+            SimplePolygon::from(large.clone())
+                .distance_from_border(&Point(999.9, 2.0))
+                .0,
+            BorderPosition::Boundary
+        );
+        assert_eq!(
+            small.distance_from_border(&Point(1.1, 0.2)).0,
+            BorderPosition::Exterior
+        );
+        assert_eq!(
+            small.distance_from_border(&Point(0.9, 0.2)).0,
+            BorderPosition::Interior
+        );
+    }
+
+    #[test]
+    fn sorted_layouts_do_not_depend_on_creation_order() {
+        let config = lbf_config();
+        let instance = parse_asset("baldacci1", config.cde_config);
+        let mut solution = LBFOptimizer::new(instance, config, SmallRng::seed_from_u64(0)).solve();
+        assert!(solution.layout_snapshots.len() > 1);
+
+        let mut reversed = solution.clone();
+        reversed.layout_snapshots.reverse();
+        solution.sort_layouts();
+        reversed.sort_layouts();
+
+        let order = |s: &Solution| {
+            s.layout_snapshots
+                .iter()
+                .map(|ls| ls.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(order(&solution), order(&reversed));
+        assert!(solution
+            .layout_snapshots
+            .windows(2)
+            .all(|w| w[0].usage >= w[1].usage));
+    }
+
+    #[test]
+    fn primitive_item_constructors() {
+        let surrogate_config = LBFConfig::default().cde_config.item_surrogate_config;
+
+        let rect = Item::from_rect(0, 100.0, 50.0, surrogate_config);
+        assert_eq!(rect.shape.number_of_points(), 4);
+        assert!((rect.shape.area() - 5000.0).abs() < 1e-3);
+        assert!(!rect.shape.surrogate().poles.is_empty());
+
+        let hexagon = Item::from_regular_polygon(1, 6, 10.0, surrogate_config);
+        assert_eq!(hexagon.shape.number_of_points(), 6);
+        assert!((hexagon.shape.diameter() - 20.0).abs() < 1e-3);
+
+        let circle = Item::from_circle(2, 10.0, 32, surrogate_config);
+        assert_eq!(circle.shape.number_of_points(), 32);
+        assert!(circle
+            .shape
+            .edge_iter()
+            .all(|e| e.distance(&Point(0.0, 0.0)) >= 10.0 - 1e-3));
+    }
+
+    #[test]
+    fn rotations_taller_than_the_strip_are_pruned() {
+        let json_instance = serde_json::from_str(
+            r#"{
+                "Name": "pruned",
+                "Items": [
+                    {"Demand": 2, "AllowedOrientations": [0.0, 90.0], "Shape": {"Type": "Rectangle", "Data": {"Width": 10.0, "Height": 60.0}}},
+                    {"Demand": 1, "AllowedOrientations": [0.0, 90.0], "Shape": {"Type": "Rectangle", "Data": {"Width": 60.0, "Height": 60.0}}}
+                ],
+                "Strip": {"Height": 50.0}
+            }"#,
+        )
+        .unwrap();
+        let mut config = lbf_config();
+        config.prune_rotations = true;
+        let parser = Parser::new(PolySimplConfig::Disabled, config.cde_config, true);
+        let instance = parser.parse(&json_instance).unwrap();
+
+        let pruned = prune_rotations(instance.item(0), 50.0).unwrap();
+        assert_eq!(
+            pruned.allowed_rotation,
+            AllowedRotation::Discrete(vec![90.0f32.to_radians()])
+        );
+        assert!(prune_rotations(instance.item(1), 50.0).is_none());
+
+        let solution = LBFOptimizer::new(instance, config, SmallRng::seed_from_u64(0)).solve();
+        assert_eq!(solution.placed_item_qtys, vec![2, 0]);
+    }
+
+    #[test]
+    fn shelf_optimizer_packs_shelves_and_skips_oversized_items() {
+        let json_instance = serde_json::from_str(
+            r#"{
+                "Name": "shelves",
+                "Items": [
+                    {"Demand": 4, "Shape": {"Type": "Rectangle", "Data": {"Width": 4.0, "Height": 4.0}}},
+                    {"Demand": 2, "Shape": {"Type": "Rectangle", "Data": {"Width": 3.0, "Height": 3.0}}},
+                    {"Demand": 1, "AllowedOrientations": [0.0, 90.0], "Shape": {"Type": "Rectangle", "Data": {"Width": 12.0, "Height": 12.0}}}
+                ],
+                "Strip": {"Height": 10.0}
+            }"#,
+        )
+        .unwrap();
+        let cde_config = lbf_config().cde_config;
+        let parser = Parser::new(PolySimplConfig::Disabled, cde_config, true);
+        let Instance::SP(instance) = parser.parse(&json_instance).unwrap() else {
+            panic!("expected a strip packing instance");
+        };
+
+        let mut optimizer = ShelfOptimizerSPP::new(instance, cde_config);
+        let solution = optimizer.solve();
+
+        //two shelves of two 4x4 squares, one shelf of two 3x3 squares
+        assert_eq!(solution.placed_item_qtys, vec![4, 2, 0]);
+        let width = optimizer.problem.strip_width();
+        assert!((11.0..11.1).contains(&width), "{width}");
+
+        //the square taller than the strip is reported as unplaced
+        assert_eq!(optimizer.problem.missing_item_qtys(), &[0, 0, 1]);
+        assert!(!solution.is_complete(&optimizer.problem.instance));
+    }
+
+    #[test]
+    fn item_metadata_is_attached_to_placements() {
+        let json_instance: JsonInstance = serde_json::from_str(
+            r#"{
+                "Name": "metadata",
+                "Items": [
+                    {"Demand": 2, "Shape": {"Type": "Rectangle", "Data": {"Width": 10.0, "Height": 5.0}}, "Metadata": {"PartNumber": "A-113"}},
+                    {"Demand": 1, "Shape": {"Type": "Rectangle", "Data": {"Width": 7.0, "Height": 7.0}}}
+                ],
+                "Objects": [
+                    {"Cost": 0, "Stock": 1, "Shape": {"Type": "Rectangle", "Data": {"Width": 100.0, "Height": 50.0}}}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let config = lbf_config();
+        let parser = Parser::new(PolySimplConfig::Disabled, config.cde_config, true);
+        let instance = parser.parse(&json_instance).unwrap();
+        let solution =
+            LBFOptimizer::new(instance.clone(), config, SmallRng::seed_from_u64(0)).solve();
+
+        let json_solution = parser::compose_json_solution(&solution, &instance, Instant::now());
+        let placed_items = &json_solution.layouts[0].placed_items;
+        assert_eq!(placed_items.len(), 3);
+        for placed_item in placed_items {
+            let expected = match placed_item.index {
+                0 => Some(serde_json::json!({"PartNumber": "A-113"})),
+                _ => None,
+            };
+            assert_eq!(placed_item.metadata, expected);
+        }
+
+        //items without metadata are serialized as before
+        let serialized = serde_json::to_string(&json_instance).unwrap();
+        assert_eq!(serialized.matches("Metadata").count(), 1);
+    }
+
+    #[test]
+    fn rotated_solution_round_trips_through_json() {
+        let config = lbf_config();
+        let json_instance = io::read_json_instance(Path::new("../assets/baldacci1.json"));
+        let parser = Parser::new(PolySimplConfig::Disabled, config.cde_config, true);
+        let instance = parser.parse(&json_instance).unwrap();
+        let mut solution =
+            LBFOptimizer::new(instance.clone(), config, SmallRng::seed_from_u64(0)).solve();
+        let exported = parser::compose_json_solution(&solution, &instance, Instant::now());
+
+        let bbox = solution.layout_snapshots[0].bin.bbox();
+        solution.rotate(jagua_rs::PI / 2.0);
+        let rotated_bbox = solution.layout_snapshots[0].bin.bbox();
+        assert!((rotated_bbox.width() - bbox.height()).abs() < 1e-3);
+        assert!((rotated_bbox.height() - bbox.width()).abs() < 1e-3);
+
+        //the rotation is absorbed by the pretransform of the bin, placements relative to the instance are unchanged
+        let exported_rotated = parser::compose_json_solution(&solution, &instance, Instant::now());
+        for (jl, jl_rotated) in exported.layouts.iter().zip(&exported_rotated.layouts) {
+            for (jpi, jpi_rotated) in jl.placed_items.iter().zip(&jl_rotated.placed_items) {
+                let (t, t_rotated) = (&jpi.transformation, &jpi_rotated.transformation);
+                let d_rotation = (t.rotation - t_rotated.rotation).rem_euclid(2.0 * jagua_rs::PI);
+                assert!(d_rotation.min(2.0 * jagua_rs::PI - d_rotation) < 1e-3);
+                assert!((t.translation.0 - t_rotated.translation.0).abs() < 1e-2);
+                assert!((t.translation.1 - t_rotated.translation.1).abs() < 1e-2);
+            }
+        }
+
+        let (_, reparsed) = parser
+            .parse_and_build_solution(&json_instance, &exported_rotated.layouts)
+            .unwrap();
+        assert_eq!(reparsed.placed_item_qtys, solution.placed_item_qtys);
+        for ls in reparsed.layout_snapshots.iter() {
+            assert!(Layout::from_snapshot(ls).collisions().is_empty());
+        }
+    }
+
+    #[test]
+    fn occupied_width_with_candidate_placement() {
+        let config = lbf_config();
+        let instance = parse_asset("swim", config.cde_config);
+        let mut optimizer = LBFOptimizer::new(instance, config, SmallRng::seed_from_u64(0));
+        optimizer.solve();
+        let Problem::SP(problem) = &optimizer.problem else {
+            panic!("expected a strip packing problem");
+        };
+
+        let (min_x, max_x) = problem.occupied_range().unwrap();
+        let placed = &problem.layout.placed_items().values().next().unwrap().shape;
+        assert_eq!(problem.occupied_width_with(placed), max_x - min_x);
+
+        let bbox = placed.bbox();
+        let beyond = placed.transform_clone(&Transformation::from_translation((
+            max_x + 10.0 - bbox.x_min,
+            0.0,
+        )));
+        let expected = max_x + 10.0 + bbox.width() - min_x;
+        assert!((problem.occupied_width_with(&beyond) - expected).abs() < 1e-2);
+    }
+
+    #[test]
+    fn container_margin_differs_from_item_margin() {
+        let mut cde_config = LBFConfig::default().cde_config;
+        cde_config.collision_margin = 6.0;
+        cde_config.container_margin = Some(2.0);
+        let instance = bp_instance(
+            rect(40.0, 20.0),
+            vec![(rect(5.0, 5.0), 3, AllowedRotation::None)],
+            cde_config,
+        );
+        let mut problem = BPProblem::new(instance);
+        let at = |x| DTransformation::new(0.0, (x, 2.5));
+
+        //only 2 away from the walls of the bin
+        assert!(problem
+            .try_place_at(0, LayoutIndex::Template(0), at(1.5))
+            .is_err());
+        let (layout, _) = problem
+            .try_place_at(0, LayoutIndex::Template(0), at(2.5))
+            .unwrap();
+        //but 6 away from the other items
+        assert!(problem.try_place_at(0, layout, at(12.5)).is_err());
+        assert!(problem.try_place_at(0, layout, at(14.0)).is_ok());
+    }
+
+    #[test]
+    fn separation_between_placed_items() {
+        let instance = bp_instance(
+            rect(30.0, 10.0),
+            vec![
+                (rect(5.0, 5.0), 3, AllowedRotation::None),
+                (rect(1.0, 1.0), 1, AllowedRotation::Continuous),
+            ],
+            LBFConfig::default().cde_config,
+        );
+        let mut layout = Layout::new(0, instance.bins[0].0.clone());
+        let at = |r, x, y| DTransformation::new(r, (x, y));
+
+        let a = layout.place_item(instance.item(0), at(0.0, 0.0, 0.0));
+        let touching = layout.place_item(instance.item(0), at(0.0, 5.0, 1.0));
+        let apart = layout.place_item(instance.item(0), at(0.0, 12.0, 0.0));
+        //diamond with its left corner 2.5 to the right of `apart`
+        let diamond = layout.place_item(
+            instance.item(1),
+            at(
+                jagua_rs::PI / 4.0,
+                17.0 + 2.5 + 0.5 * 2.0_f32.sqrt(),
+                2.5 - 0.5 * 2.0_f32.sqrt(),
+            ),
+        );
+
+        assert_eq!(layout.separation_between(a, touching), 0.0);
+        assert_eq!(layout.separation_between(touching, a), 0.0);
+        let d = layout.separation_between(a, apart);
+        assert!((d - 7.0).abs() < 1e-4, "{d}");
+        assert_eq!(d, layout.separation_between(apart, a));
+        let d = layout.separation_between(apart, diamond);
+        assert!((d - 2.5).abs() < 1e-3, "{d}");
+
+        //overlapping items
+        let overlapping = layout.place_item(instance.item(0), at(0.0, 13.0, 1.0));
+        assert_eq!(layout.separation_between(apart, overlapping), 0.0);
+    }
+
+    #[test]
+    fn items_protruding_by_noise_are_clamped_inside() {
+        let instance = bp_instance(
+            rect(20.0, 10.0),
+            vec![(rect(5.0, 5.0), 3, AllowedRotation::None)],
+            LBFConfig::default().cde_config,
+        );
+        let item = instance.item(0);
+        let mut layout = Layout::new(0, instance.bins[0].0.clone());
+        let at = |x, y| DTransformation::new(0.0, (x, y));
+
+        //protruding by noise, protruding for real and colliding with another item
+        layout.place_item(item, at(-0.001, 2.0));
+        layout.place_item(item, at(16.0, 6.0));
+        layout.place_item(item, at(5.5, 2.0));
+        layout.place_item(item, at(10.0, 2.0));
+        assert_eq!(layout.collisions().len(), 4);
+
+        let still_colliding = layout.clamp_to_container(&instance);
+        assert_eq!(still_colliding.len(), 3);
+        let clamped = layout
+            .placed_items()
+            .values()
+            .find(|pi| pi.d_transf.translation().1 == 2.0 && pi.d_transf.translation().0 < 1.0)
+            .unwrap();
+        let (x, _) = clamped.d_transf.translation();
+        assert!((0.0..0.01).contains(&x), "{x}");
+        assert!(layout
+            .collisions()
+            .iter()
+            .all(|(pik, _)| still_colliding.contains(pik)));
+    }
+
+    #[test]
+    fn surrogate_poles_are_inside_concave_shape() {
+        let mut shape = u_shape();
+        shape.generate_surrogate(LBFConfig::default().cde_config.item_surrogate_config);
+        let surrogate = shape.surrogate().clone();
+        assert!(assertions::surrogate_poles_inside(&shape, &surrogate));
+
+        //tangent to the bottom of the notch
+        let mut tangent = surrogate.clone();
+        tangent.poles.push(Circle::new(Point(2.5, 0.5), 0.5));
+        assert!(assertions::surrogate_poles_inside(&shape, &tangent));
+
+        //reaching into the notch
+        let mut bad = surrogate;
+        bad.poles.push(Circle::new(Point(2.5, 0.5), 0.7));
+        assert!(!assertions::surrogate_poles_inside(&shape, &bad));
+    }
+
+    #[test]
+    fn cached_surrogates_match_generated_ones() {
+        let config = LBFConfig::default();
+        let json_instance = io::read_json_instance(Path::new("../assets/swim.json"));
+        let dir =
+            std::env::temp_dir().join(format!("jagua_surrogate_cache_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let n_entries = || std::fs::read_dir(&dir).unwrap().count();
+
+        let parser = Parser::new(PolySimplConfig::Disabled, config.cde_config, true);
+        let generated = parser.parse(&json_instance).unwrap();
+        let cached_parser = || {
+            Parser::new(PolySimplConfig::Disabled, config.cde_config, true)
+                .surrogate_cache(SurrogateCache::new(&dir))
+        };
+        //first parse fills the cache, the second one reads from it
+        let _ = cached_parser().parse(&json_instance).unwrap();
+        let n_items = generated.items().len();
+        assert_eq!(n_entries(), n_items);
+        let cached = cached_parser().parse(&json_instance).unwrap();
+        assert_eq!(n_entries(), n_items);
+
+        for ((gen_item, _), (cached_item, _)) in generated.items().iter().zip(cached.items()) {
+            let (gen_sur, cached_sur) = (gen_item.shape.surrogate(), cached_item.shape.surrogate());
+            assert_eq!(gen_sur.poles, cached_sur.poles);
+            assert_eq!(gen_sur.piers, cached_sur.piers);
+            assert_eq!(gen_sur.n_ff_poles, cached_sur.n_ff_poles);
+        }
+
+        //a different surrogate config does not reuse the existing entries
+        let mut other_cde_config = config.cde_config;
+        other_cde_config.item_surrogate_config.n_ff_poles += 1;
+        let _ = Parser::new(PolySimplConfig::Disabled, other_cde_config, true)
+            .surrogate_cache(SurrogateCache::new(&dir))
+            .parse(&json_instance)
+            .unwrap();
+        assert_eq!(n_entries(), 2 * n_items);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }